@@ -0,0 +1,86 @@
+# OpenTracker shell integration for bash. Add to ~/.bashrc:
+#   eval "$(OpenTracker shell-init bash)"
+# Each command's start/end time, exit code and working directory are sent to the local
+# OpenTracker service in the background; nothing is sent if the service is not running.
+
+__opentracker_url="__OPENTRACKER_URL__"
+__opentracker_ready=
+__opentracker_started=
+__opentracker_command=
+
+__opentracker_now() {
+  if [ -n "$EPOCHREALTIME" ]; then
+    printf '%s' "${EPOCHREALTIME/,/.}"
+  else
+    date +%s
+  fi
+}
+
+__opentracker_send() {
+  (curl -fsS -m 2 \
+    --data-urlencode "command=$1" \
+    --data-urlencode "cwd=$PWD" \
+    --data-urlencode "exit_code=$2" \
+    --data-urlencode "started_at=$3" \
+    --data-urlencode "ended_at=$4" \
+    --data-urlencode "shell=bash" \
+    "$__opentracker_url" >/dev/null 2>&1 &)
+}
+
+# DEBUG trap: fires before every simple command; only the first one after a prompt starts a span.
+__opentracker_preexec() {
+  [ -n "$__opentracker_ready" ] || return
+  [ -n "$COMP_LINE" ] && return
+  [ "$BASH_COMMAND" = "__opentracker_precmd" ] && return
+  __opentracker_ready=
+  __opentracker_started="$(__opentracker_now)"
+  __opentracker_command="$(HISTTIMEFORMAT= builtin history 1 | sed -e 's/^ *[0-9]*\*\{0,1\} *//')"
+}
+
+__opentracker_precmd() {
+  local exit_code=$?
+  __opentracker_ready=
+  if [ -n "$__opentracker_started" ]; then
+    __opentracker_send "$__opentracker_command" "$exit_code" "$__opentracker_started" "$(__opentracker_now)"
+  fi
+  __opentracker_started=
+  return $exit_code
+}
+
+__opentracker_set_status() {
+  return "$1"
+}
+
+# Runs our hook, then the DEBUG trap that was set before ours with the `$?` and `$_` it expects.
+__opentracker_debug_trap() {
+  local status=$? last_argument=$1
+  __opentracker_preexec
+  __opentracker_set_status "$status" "$last_argument"
+  eval "$__opentracker_previous_debug_trap"
+}
+
+# Takes the output of `trap -p DEBUG`, which only shows the trap outside functions and sourced
+# files, so it is installed from PROMPT_COMMAND rather than when this file is loaded.
+__opentracker_install_debug_trap() {
+  case "$1" in
+    *__opentracker_debug_trap*) return ;;
+  esac
+  eval "set -- $1"
+  __opentracker_previous_debug_trap="${3:-}"
+  trap '__opentracker_debug_trap "$_"' DEBUG
+}
+
+__opentracker_bp_preexec() {
+  __opentracker_started="$(__opentracker_now)"
+  __opentracker_command="$1"
+}
+
+# bash-preexec (used by atuin and others) owns the DEBUG trap, so register with it instead.
+# Otherwise any DEBUG trap already set (starship, direnv, ...) keeps running after ours.
+if [ -n "${bash_preexec_imported:-}${__bp_imported:-}" ]; then
+  [[ " ${preexec_functions[*]} " == *" __opentracker_bp_preexec "* ]] || preexec_functions+=(__opentracker_bp_preexec)
+  [[ " ${precmd_functions[*]} " == *" __opentracker_precmd "* ]] || precmd_functions+=(__opentracker_precmd)
+elif [[ "$PROMPT_COMMAND" != *__opentracker_precmd* ]]; then
+  PROMPT_COMMAND="__opentracker_precmd${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+  PROMPT_COMMAND+=';__opentracker_install_debug_trap "$(trap -p DEBUG)";__opentracker_ready=1'
+fi
@@ -0,0 +1,22 @@
+# OpenTracker shell integration for fish. Add to ~/.config/fish/config.fish:
+#   OpenTracker shell-init fish | source
+# Each command's start/end time, exit code and working directory are sent to the local
+# OpenTracker service in the background; nothing is sent if the service is not running.
+
+set -g __opentracker_url "__OPENTRACKER_URL__"
+
+function __opentracker_postexec --on-event fish_postexec
+    set -l exit_code $status
+    test -n "$argv[1]"; or return
+    set -l ended (date +%s)
+    set -l started (math "$ended - $CMD_DURATION / 1000")
+    command curl -fsS -m 2 \
+        --data-urlencode "command=$argv[1]" \
+        --data-urlencode "cwd=$PWD" \
+        --data-urlencode "exit_code=$exit_code" \
+        --data-urlencode "started_at=$started" \
+        --data-urlencode "ended_at=$ended" \
+        --data-urlencode "shell=fish" \
+        $__opentracker_url >/dev/null 2>&1 &
+    disown 2>/dev/null
+end
@@ -1,6 +1,6 @@
 use crate::analyzer::categorizer::CategoryRules;
 use crate::config::Config;
-use crate::db::ChromeVisitInput;
+use crate::db::{ChromeVisitInput, VisitCategoryChange};
 use anyhow::{Context, Result, anyhow, bail};
 use chrono::NaiveDate;
 use reqwest::blocking::Client;
@@ -10,9 +10,10 @@ use serde_json::json;
 use std::collections::HashMap;
 use std::time::Duration;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct AiEnrichment {
-    pub visits: Vec<ChromeVisitInput>,
+    /// Domains the AI put in another category than the rules; empty when enrichment did not run.
+    pub changes: Vec<VisitCategoryChange>,
     pub insights: Vec<String>,
 }
 
@@ -45,22 +46,17 @@ struct DomainCategoryItem {
 
 pub fn enrich_chrome_visits(
     config: &Config,
+    rules: &CategoryRules,
     date: NaiveDate,
     visits: &[ChromeVisitInput],
 ) -> Result<AiEnrichment> {
     if !config.ai_enabled || visits.is_empty() {
-        return Ok(AiEnrichment {
-            visits: visits.to_vec(),
-            insights: Vec::new(),
-        });
+        return Ok(AiEnrichment::default());
     }
 
     let api_key = resolve_api_key(config);
     if api_key.is_none() {
-        return Ok(AiEnrichment {
-            visits: visits.to_vec(),
-            insights: Vec::new(),
-        });
+        return Ok(AiEnrichment::default());
     }
 
     let top_domains = visits
@@ -74,49 +70,60 @@ pub fn enrich_chrome_visits(
         })
         .collect::<Vec<_>>();
 
+    let definitions = rules.definitions();
+    let allowed_categories = definitions
+        .iter()
+        .map(|category| category.id.as_str())
+        .collect::<Vec<_>>();
     let user_payload = json!({
         "date": date.format("%Y-%m-%d").to_string(),
         "domains": top_domains,
-        "allowed_categories": ["development", "research", "communication", "entertainment", "sns", "shopping", "other"],
+        "allowed_categories": allowed_categories,
+        "category_names": definitions
+            .iter()
+            .map(|category| json!({"id": category.id, "name": category.display_name()}))
+            .collect::<Vec<_>>(),
         "instruction": "Unknown domains can be recategorized if confidence is high.",
     });
 
-    let system_prompt = r#"You are a strict activity classifier. Return JSON only: {"domain_categories":[{"domain":"example.com","category":"research"}],"insights":["..."]}. Categories must be one of development,research,communication,entertainment,sns,shopping,other."#;
+    let system_prompt = format!(
+        r#"You are a strict activity classifier. Return JSON only: {{"domain_categories":[{{"domain":"example.com","category":"{}"}}],"insights":["..."]}}. Categories must be one of {}."#,
+        allowed_categories.first().copied().unwrap_or_default(),
+        allowed_categories.join(","),
+    );
 
     let content = chat_completion(
         config,
         api_key.as_deref().unwrap_or_default(),
-        system_prompt,
+        &system_prompt,
         &user_payload.to_string(),
     )?;
 
     let parsed = parse_ai_payload(&content)?;
 
+    // Answers outside the defined categories keep the rule-based category.
     let domain_map = parsed
         .domain_categories
         .unwrap_or_default()
         .into_iter()
-        .map(|item| {
-            (
+        .filter_map(|item| {
+            Some((
                 item.domain.trim().to_lowercase(),
-                CategoryRules::normalize_category(&item.category),
-            )
+                rules.category_id(&item.category)?,
+            ))
         })
         .collect::<HashMap<_, _>>();
 
-    let recategorized_visits = visits
+    let changes = visits
         .iter()
-        .map(|visit| {
-            let mapped_category = domain_map
-                .get(&visit.domain.trim().to_lowercase())
-                .cloned()
-                .unwrap_or_else(|| visit.category.clone());
-
-            ChromeVisitInput {
+        .filter_map(|visit| {
+            let category = domain_map.get(&visit.domain.trim().to_lowercase())?;
+            (*category != visit.category).then(|| VisitCategoryChange {
+                browser: visit.browser.clone(),
                 domain: visit.domain.clone(),
-                duration_sec: visit.duration_sec,
-                category: mapped_category,
-            }
+                from: visit.category.clone(),
+                to: category.clone(),
+            })
         })
         .collect::<Vec<_>>();
 
@@ -129,10 +136,7 @@ pub fn enrich_chrome_visits(
         .take(8)
         .collect::<Vec<_>>();
 
-    Ok(AiEnrichment {
-        visits: recategorized_visits,
-        insights,
-    })
+    Ok(AiEnrichment { changes, insights })
 }
 
 pub fn test_connection(config: &Config) -> Result<String> {
@@ -1,13 +1,346 @@
-use anyhow::{Context, Result};
+use crate::analyzer::project::{ProjectExtractorRule, TitleParser};
+use anyhow::{Context, Result, bail};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
+use std::sync::LazyLock;
+use tracing::warn;
+
+/// Always defined; where anything without a matching rule goes.
+pub const OTHER_CATEGORY: &str = "other";
+
+/// Separates the levels of a hierarchical category, e.g. `development/code-review`.
+pub const CATEGORY_SEPARATOR: char = '/';
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryRules {
+    /// Shorthand: app name (exact, else the longest contained key) to category.
     pub apps: HashMap<String, String>,
+    /// Shorthand: domain, including subdomains, to category; the most specific one wins.
     pub domains: HashMap<String, String>,
+    /// Ordered rules, tried before the `apps`/`domains` shorthand.
+    #[serde(default)]
+    pub rules: Vec<CategoryRule>,
+    /// The categories rules may assign; the built-in seven when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<CategoryDefinition>,
+    /// Regexes deriving a project from window titles, tried before the built-in extractors.
+    #[serde(default)]
+    pub project_extractors: Vec<ProjectExtractorRule>,
+}
+
+/// How time in a category counts in reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryClass {
+    Productive,
+    #[default]
+    Neutral,
+    Distracting,
+}
+
+/// A `categories` entry. Reports and the dashboard list categories in this order. Sub-categories
+/// (`development/code-review`) only need an entry to get their own name, color or class.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryDefinition {
+    pub id: String,
+    /// Display name; the id when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Dashboard color, `#rgb` or `#rrggbb`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Inherited from the parent category when omitted; `neutral` at the top level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<CategoryClass>,
+    /// Other spellings accepted in rules, imports and AI answers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+}
+
+impl CategoryDefinition {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+
+    fn is(&self, normalized: &str) -> bool {
+        self.id.trim().eq_ignore_ascii_case(normalized)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| alias.trim().to_lowercase() == normalized)
+    }
+}
+
+static DEFAULT_CATEGORIES: LazyLock<Vec<CategoryDefinition>> = LazyLock::new(|| {
+    let define = |id: &str, name: &str, color: &str, class, aliases: &[&str]| CategoryDefinition {
+        id: id.to_string(),
+        name: Some(name.to_string()),
+        color: Some(color.to_string()),
+        class: Some(class),
+        aliases: aliases.iter().map(ToString::to_string).collect(),
+    };
+    vec![
+        define(
+            "development",
+            "Development",
+            "#2f6f9f",
+            CategoryClass::Productive,
+            &["dev", "개발"],
+        ),
+        define(
+            "research",
+            "Research",
+            "#3f8f5f",
+            CategoryClass::Productive,
+            &["리서치"],
+        ),
+        define(
+            "communication",
+            "Communication",
+            "#cc6b1b",
+            CategoryClass::Neutral,
+            &["커뮤니케이션"],
+        ),
+        define(
+            "entertainment",
+            "Entertainment",
+            "#a62f2f",
+            CategoryClass::Distracting,
+            &["엔터테인먼트"],
+        ),
+        define("sns", "SNS", "#b0487a", CategoryClass::Distracting, &[]),
+        define(
+            "shopping",
+            "Shopping",
+            "#8a6d1f",
+            CategoryClass::Distracting,
+            &["쇼핑"],
+        ),
+        define(
+            OTHER_CATEGORY,
+            "Other",
+            "#8c7f75",
+            CategoryClass::Neutral,
+            &["기타"],
+        ),
+    ]
+});
+
+/// A `rules` entry. Every matcher given must match; rules are tried by descending `priority`,
+/// then in file order, and the first match wins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryRule {
+    pub category: String,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<Matcher>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<Matcher>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<Matcher>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<Matcher>,
+}
+
+impl CategoryRule {
+    fn matchers(&self) -> [(&'static str, Option<&Matcher>); 4] {
+        [
+            ("app", self.app.as_ref()),
+            ("title", self.title.as_ref()),
+            ("domain", self.domain.as_ref()),
+            ("url", self.url.as_ref()),
+        ]
+    }
+
+    /// `app glob `*code*`, title regex `youtube``.
+    fn describe(&self) -> String {
+        self.matchers()
+            .iter()
+            .filter_map(|(field, matcher)| Some(format!("{field} {}", (*matcher)?.describe())))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Whether every target this rule matches is also matched by `other`, as far as can be told
+    /// from the patterns.
+    fn covered_by(&self, other: &Self) -> bool {
+        self.matchers()
+            .iter()
+            .zip(other.matchers())
+            .all(|((_, own), (_, other))| match (own, other) {
+                (_, None) => true,
+                (None, Some(other)) => other.matches_everything(),
+                (Some(own), Some(other)) => other.covers(own),
+            })
+    }
+
+    fn matches(&self, target: &Target) -> bool {
+        let matchers = [
+            (&self.app, target.app),
+            (&self.title, target.title),
+            (&self.domain, target.domain),
+            (&self.url, target.url),
+        ];
+        matchers.iter().any(|(matcher, _)| matcher.is_some())
+            && matchers.iter().all(|(matcher, value)| {
+                matcher
+                    .as_ref()
+                    .is_none_or(|matcher| matcher.matches(*value))
+            })
+    }
+}
+
+/// `{"exact": ...}`, `{"glob": ...}` (`*` and `?` wildcards) or `{"regex": ...}`. All are
+/// case-insensitive; exact and glob matchers must match the whole value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatcherSpec {
+    Exact(String),
+    Glob(String),
+    Regex(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "MatcherSpec", into = "MatcherSpec")]
+pub struct Matcher {
+    spec: MatcherSpec,
+    regex: Regex,
+}
+
+impl Matcher {
+    fn matches(&self, value: Option<&str>) -> bool {
+        value.is_some_and(|value| self.regex.is_match(value.trim()))
+    }
+
+    fn describe(&self) -> String {
+        match &self.spec {
+            MatcherSpec::Exact(value) => format!("exact `{value}`"),
+            MatcherSpec::Glob(value) => format!("glob `{value}`"),
+            MatcherSpec::Regex(value) => format!("regex `{value}`"),
+        }
+    }
+
+    /// Exact and glob patterns compare case-insensitively.
+    fn same_as(&self, other: &Self) -> bool {
+        match (&self.spec, &other.spec) {
+            (MatcherSpec::Exact(own), MatcherSpec::Exact(other))
+            | (MatcherSpec::Glob(own), MatcherSpec::Glob(other)) => {
+                own.trim().eq_ignore_ascii_case(other.trim())
+            }
+            (own, other) => own == other,
+        }
+    }
+
+    fn matches_everything(&self) -> bool {
+        match &self.spec {
+            MatcherSpec::Exact(_) => false,
+            MatcherSpec::Glob(value) => {
+                value.trim().chars().all(|c| c == '*') && !value.trim().is_empty()
+            }
+            MatcherSpec::Regex(value) => {
+                matches!(value.as_str(), "" | ".*" | "^.*$" | "^.*" | ".*$")
+            }
+        }
+    }
+
+    /// Whether every value `other` matches is matched by `self`. Only provable cases count: an
+    /// exact value matched by `self`, or a glob whose text `self`'s own `*`-only glob matches
+    /// (each wildcard of `other` then falls inside one of `self`'s).
+    fn covers(&self, other: &Self) -> bool {
+        if self.matches_everything() || self.same_as(other) {
+            return true;
+        }
+        match (&self.spec, &other.spec) {
+            (_, MatcherSpec::Exact(value)) => self.matches(Some(value)),
+            (MatcherSpec::Glob(own), MatcherSpec::Glob(value)) => {
+                !own.contains('?') && self.regex.is_match(value.trim())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TryFrom<MatcherSpec> for Matcher {
+    type Error = regex::Error;
+
+    fn try_from(spec: MatcherSpec) -> Result<Self, Self::Error> {
+        let pattern = match &spec {
+            MatcherSpec::Exact(value) => format!("^{}$", regex::escape(value.trim())),
+            MatcherSpec::Glob(value) => glob_pattern(value.trim()),
+            MatcherSpec::Regex(value) => value.clone(),
+        };
+        let regex = RegexBuilder::new(&pattern).case_insensitive(true).build()?;
+        Ok(Self { spec, regex })
+    }
+}
+
+impl From<Matcher> for MatcherSpec {
+    fn from(matcher: Matcher) -> Self {
+        matcher.spec
+    }
+}
+
+/// A rule or shorthand entry that matched a target, in the order they are tried.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleMatch {
+    /// `rules[2]`, `apps.code` or `domains.github.com`.
+    pub rule: String,
+    pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    pub conditions: String,
+}
+
+/// How a target was categorized: the first of `matches` decides, `other` when there is none.
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    pub category: String,
+    pub matches: Vec<RuleMatch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintKind {
+    /// Same conditions as an earlier rule.
+    Duplicate,
+    /// Every target it matches is matched by an earlier rule first.
+    Shadowed,
+    /// Can never match, or is never consulted.
+    Unreachable,
+    /// Assigns a category that is not defined, which counts as `other`.
+    Unknown,
+}
+
+impl LintKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Duplicate => "duplicate",
+            Self::Shadowed => "shadowed",
+            Self::Unreachable => "unreachable",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LintIssue {
+    pub kind: LintKind,
+    pub rule: String,
+    pub message: String,
+}
+
+/// What is being categorized; a window has no domain or URL, a browser visit no app or title.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Target<'a> {
+    pub app: Option<&'a str>,
+    pub title: Option<&'a str>,
+    pub domain: Option<&'a str>,
+    pub url: Option<&'a str>,
 }
 
 impl CategoryRules {
@@ -16,71 +349,468 @@ impl CategoryRules {
             .with_context(|| format!("Failed to read categories file: {}", path.display()))?;
         let parsed: Self = serde_json::from_str(&content)
             .with_context(|| format!("Failed to parse categories file: {}", path.display()))?;
+        parsed
+            .validate()
+            .with_context(|| format!("Invalid categories file: {}", path.display()))?;
+        let unknown = parsed.unknown_categories();
+        if !unknown.is_empty() {
+            warn!(
+                file = %path.display(),
+                categories = %unknown.iter().map(|(_, category)| *category).collect::<Vec<_>>().join(", "),
+                "unknown categories count as `other`; declare them in `categories` to keep them"
+            );
+        }
 
         Ok(parsed.normalized())
     }
 
-    pub fn normalize_category(raw: &str) -> String {
-        match raw.trim().to_lowercase().as_str() {
-            "development" | "dev" | "개발" => "development".to_string(),
-            "communication" | "커뮤니케이션" => "communication".to_string(),
-            "research" | "리서치" => "research".to_string(),
-            "entertainment" | "엔터테인먼트" => "entertainment".to_string(),
-            "sns" => "sns".to_string(),
-            "shopping" | "쇼핑" => "shopping".to_string(),
-            "other" | "기타" => "other".to_string(),
-            _ => "other".to_string(),
+    /// Checks what deserialization cannot: category definitions, categories used by the mappings
+    /// (when the file declares its own), rules without matchers and project extractor regexes.
+    pub fn validate(&self) -> Result<()> {
+        let ids = self
+            .categories
+            .iter()
+            .map(|category| normalize_path(&category.id))
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        for (index, (category, id)) in self.categories.iter().zip(&ids).enumerate() {
+            if id.is_empty() {
+                bail!("categories[{index}] needs an id");
+            }
+            if !seen.insert(id) {
+                bail!("categories[{index}] repeats the id `{}`", category.id);
+            }
+            let root = category_at_depth(id, 1);
+            if root != id && !ids.iter().any(|id| id == root) {
+                bail!("categories[{index}] is a sub-category of `{root}`, which is not defined");
+            }
+            if let Some(color) = &category.color
+                && !is_hex_color(color)
+            {
+                bail!("categories[{index}] color must be #rgb or #rrggbb, got `{color}`");
+            }
+        }
+
+        // Files written before `categories` existed may use other names; those count as `other`.
+        if !self.categories.is_empty()
+            && let Some((_, category)) = self.unknown_categories().first()
+        {
+            bail!("Unknown category `{category}`; declare it in `categories`");
+        }
+
+        if let Some(index) = self.rules.iter().position(|rule| {
+            rule.app.is_none()
+                && rule.title.is_none()
+                && rule.domain.is_none()
+                && rule.url.is_none()
+        }) {
+            bail!("rules[{index}] needs at least one of app, title, domain or url");
+        }
+        self.title_parser().map(|_| ())
+    }
+
+    /// Rules and shorthand entries whose category is not defined, in lint order.
+    pub fn unknown_categories(&self) -> Vec<(String, &str)> {
+        let mut apps = self.apps.iter().collect::<Vec<_>>();
+        apps.sort();
+        let mut domains = self.domains.iter().collect::<Vec<_>>();
+        domains.sort();
+        self.rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| (format!("rules[{index}]"), rule.category.as_str()))
+            .chain(
+                apps.into_iter()
+                    .map(|(key, category)| (format!("apps.{key}"), category.as_str())),
+            )
+            .chain(
+                domains
+                    .into_iter()
+                    .map(|(key, category)| (format!("domains.{key}"), category.as_str())),
+            )
+            .filter(|(_, category)| self.category_id(category).is_none())
+            .collect()
+    }
+
+    /// The declared categories (with `other`), or the built-in ones.
+    pub fn definitions(&self) -> &[CategoryDefinition] {
+        if self.categories.is_empty() {
+            &DEFAULT_CATEGORIES
+        } else {
+            &self.categories
         }
     }
 
-    pub fn categorize_app(&self, app_name: &str) -> String {
+    pub fn title_parser(&self) -> Result<TitleParser> {
+        TitleParser::new(&self.project_extractors)
+    }
+
+    /// The category named by an id or alias, case-insensitive. Any sub-category of a defined
+    /// category is valid; an alias of the top level is resolved (`dev/ci` is `development/ci`).
+    pub fn category_id(&self, raw: &str) -> Option<String> {
+        let normalized = normalize_path(raw);
+        let find = |name: &str| {
+            self.definitions()
+                .iter()
+                .find(|category| category.is(name))
+                .map(|category| category.id.as_str())
+                .or_else(|| (name == OTHER_CATEGORY).then_some(OTHER_CATEGORY))
+        };
+        if let Some(id) = find(&normalized) {
+            return Some(id.to_string());
+        }
+
+        let (root, rest) = normalized.split_once(CATEGORY_SEPARATOR)?;
+        Some(format!("{}{CATEGORY_SEPARATOR}{rest}", find(root)?))
+    }
+
+    /// The id of a defined category; unknown names are kept for `lint` and count as `other` when
+    /// categorizing.
+    fn known_category(&self, raw: &str) -> String {
+        self.category_id(raw).unwrap_or_else(|| raw.to_string())
+    }
+
+    /// Like `category_id`, with unknown names falling back to `other`.
+    pub fn normalize_category(&self, raw: &str) -> String {
+        self.category_id(raw)
+            .unwrap_or_else(|| OTHER_CATEGORY.to_string())
+    }
+
+    pub fn categorize_app(&self, app_name: &str, window_title: Option<&str>) -> String {
+        self.categorize(&Target {
+            app: Some(app_name),
+            title: window_title,
+            ..Target::default()
+        })
+    }
+
+    pub fn categorize_domain(&self, domain: &str, url: Option<&str>) -> String {
+        self.categorize(&Target {
+            domain: Some(domain),
+            url,
+            ..Target::default()
+        })
+    }
+
+    /// The first matching rule, else the `apps` shorthand for the app, else the `domains`
+    /// shorthand for the domain, else `other`.
+    pub fn categorize(&self, target: &Target) -> String {
+        self.rules_in_order()
+            .find(|(_, rule)| rule.matches(target))
+            .map(|(_, rule)| rule.category.as_str())
+            .or_else(|| target.app.and_then(|app| self.app_shorthand(app)))
+            .or_else(|| {
+                target
+                    .domain
+                    .and_then(|domain| self.domain_shorthand(domain))
+            })
+            .map(|category| self.normalize_category(category))
+            .unwrap_or_else(|| OTHER_CATEGORY.to_string())
+    }
+
+    /// Every rule and shorthand entry matching `target`, in the order `categorize` tries them.
+    pub fn explain(&self, target: &Target) -> Explanation {
+        let mut matches = self
+            .rules_in_order()
+            .filter(|(_, rule)| rule.matches(target))
+            .map(|(index, rule)| RuleMatch {
+                rule: format!("rules[{index}]"),
+                category: self.normalize_category(&rule.category),
+                priority: Some(rule.priority),
+                conditions: rule.describe(),
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(app_name) = target.app {
+            let normalized = app_name.trim().to_lowercase();
+            let mut keys = self
+                .apps
+                .keys()
+                .filter(|key| normalized.contains(key.as_str()))
+                .collect::<Vec<_>>();
+            keys.sort_by_key(|key| (**key != normalized, Reverse(key.len()), key.as_str()));
+            matches.extend(keys.into_iter().map(|key| RuleMatch {
+                rule: format!("apps.{key}"),
+                category: self.normalize_category(&self.apps[key]),
+                priority: None,
+                conditions: if *key == normalized {
+                    format!("app is `{key}`")
+                } else {
+                    format!("app contains `{key}`")
+                },
+            }));
+        }
+
+        if let Some(domain) = target.domain {
+            let normalized = domain.trim().to_lowercase();
+            let mut rules = self
+                .domains
+                .keys()
+                .filter(|rule| domain_matches(&normalized, rule))
+                .collect::<Vec<_>>();
+            rules.sort_by_key(|rule| (Reverse(rule.len()), rule.as_str()));
+            matches.extend(rules.into_iter().map(|rule| RuleMatch {
+                rule: format!("domains.{rule}"),
+                category: self.normalize_category(&self.domains[rule]),
+                priority: None,
+                conditions: format!("domain is `{rule}` or a subdomain"),
+            }));
+        }
+
+        Explanation {
+            category: matches
+                .first()
+                .map(|first| first.category.clone())
+                .unwrap_or_else(|| OTHER_CATEGORY.to_string()),
+            matches,
+        }
+    }
+
+    /// Rules that can never decide a category, and shorthand entries rules always pre-empt.
+    pub fn lint(&self) -> Vec<LintIssue> {
+        let ordered = self.rules_in_order().collect::<Vec<_>>();
+        let mut issues = Vec::new();
+
+        for (position, (index, rule)) in ordered.iter().enumerate() {
+            let fields = rule.matchers().map(|(_, matcher)| matcher.is_some());
+            if (fields[0] || fields[1]) && (fields[2] || fields[3]) {
+                issues.push(LintIssue {
+                    kind: LintKind::Unreachable,
+                    rule: format!("rules[{index}]"),
+                    message: "mixes window (app, title) and browser (domain, url) conditions, which never apply together".to_string(),
+                });
+                continue;
+            }
+
+            let earlier = ordered[..position].iter().find_map(|(other_index, other)| {
+                let same = rule.matchers().iter().zip(other.matchers()).all(
+                    |((_, own), (_, other))| match (own, other) {
+                        (None, None) => true,
+                        (Some(own), Some(other)) => own.same_as(other),
+                        _ => false,
+                    },
+                );
+                if same {
+                    Some((LintKind::Duplicate, other_index, other))
+                } else if rule.covered_by(other) {
+                    Some((LintKind::Shadowed, other_index, other))
+                } else {
+                    None
+                }
+            });
+            if let Some((kind, other_index, other)) = earlier {
+                let verb = match kind {
+                    LintKind::Duplicate => "has the same conditions as",
+                    _ => "only matches what is already matched by",
+                };
+                issues.push(LintIssue {
+                    kind,
+                    rule: format!("rules[{index}]"),
+                    message: format!(
+                        "{verb} rules[{other_index}] ({}, category `{}`), which is tried first",
+                        other.describe(),
+                        other.category
+                    ),
+                });
+            }
+        }
+
+        let covering_rule = |field: usize, patterns: &[MatcherSpec]| {
+            ordered.iter().find_map(|(index, rule)| {
+                let matchers = rule.matchers();
+                let only_field = matchers
+                    .iter()
+                    .enumerate()
+                    .all(|(other, (_, matcher))| (other == field) == matcher.is_some());
+                let matcher = matchers[field].1?;
+                let covers = patterns.iter().all(|pattern| {
+                    Matcher::try_from(pattern.clone()).is_ok_and(|pattern| matcher.covers(&pattern))
+                });
+                (only_field && covers).then_some(*index)
+            })
+        };
+        let mut apps = self.apps.keys().collect::<Vec<_>>();
+        apps.sort();
+        for key in apps {
+            if let Some(index) = covering_rule(0, &[MatcherSpec::Glob(format!("*{key}*"))]) {
+                issues.push(LintIssue {
+                    kind: LintKind::Unreachable,
+                    rule: format!("apps.{key}"),
+                    message: format!("rules[{index}] matches every app containing `{key}` first"),
+                });
+            }
+        }
+        let mut domains = self.domains.keys().collect::<Vec<_>>();
+        domains.sort();
+        for key in domains {
+            let patterns = [
+                MatcherSpec::Exact(key.clone()),
+                MatcherSpec::Glob(format!("*.{key}")),
+            ];
+            if let Some(index) = covering_rule(2, &patterns) {
+                issues.push(LintIssue {
+                    kind: LintKind::Unreachable,
+                    rule: format!("domains.{key}"),
+                    message: format!("rules[{index}] matches `{key}` and its subdomains first"),
+                });
+            }
+        }
+
+        for (rule, category) in self.unknown_categories() {
+            issues.push(LintIssue {
+                kind: LintKind::Unknown,
+                rule,
+                message: format!(
+                    "category `{category}` is not defined and counts as `{OTHER_CATEGORY}`; declare it in `categories`"
+                ),
+            });
+        }
+
+        issues
+    }
+
+    /// Rules by descending priority, then file order.
+    fn rules_in_order(&self) -> impl Iterator<Item = (usize, &CategoryRule)> {
+        let mut rules = self.rules.iter().enumerate().collect::<Vec<_>>();
+        rules.sort_by_key(|(index, rule)| (Reverse(rule.priority), *index));
+        rules.into_iter()
+    }
+
+    fn app_shorthand(&self, app_name: &str) -> Option<&str> {
         let normalized = app_name.trim().to_lowercase();
 
         self.apps
             .get(&normalized)
-            .cloned()
             .or_else(|| {
                 self.apps
                     .iter()
-                    .find(|(key, _)| normalized.contains(*key))
-                    .map(|(_, value)| value.clone())
+                    .filter(|(key, _)| normalized.contains(key.as_str()))
+                    .max_by_key(|(key, _)| (key.len(), Reverse(key.as_str())))
+                    .map(|(_, value)| value)
             })
-            .map(|value| Self::normalize_category(&value))
-            .unwrap_or_else(|| "other".to_string())
+            .map(String::as_str)
     }
 
-    pub fn categorize_domain(&self, domain: &str) -> String {
+    fn domain_shorthand(&self, domain: &str) -> Option<&str> {
         let normalized = domain.trim().to_lowercase();
 
         self.domains
             .iter()
-            .find(|(rule, _)| domain_matches(&normalized, rule))
-            .map(|(_, value)| Self::normalize_category(value))
-            .unwrap_or_else(|| "other".to_string())
+            .filter(|(rule, _)| domain_matches(&normalized, rule))
+            .max_by_key(|(rule, _)| (rule.len(), Reverse(rule.as_str())))
+            .map(|(_, value)| value.as_str())
     }
 
-    fn normalized(self) -> Self {
+    fn normalized(mut self) -> Self {
+        self.categories.iter_mut().for_each(|category| {
+            category.id = normalize_path(&category.id);
+        });
+        if !self.categories.is_empty()
+            && !self
+                .categories
+                .iter()
+                .any(|category| category.id == OTHER_CATEGORY)
+        {
+            self.categories.push(CategoryDefinition {
+                id: OTHER_CATEGORY.to_string(),
+                name: Some("Other".to_string()),
+                color: None,
+                class: Some(CategoryClass::Neutral),
+                aliases: Vec::new(),
+            });
+        }
+
         let apps = self
             .apps
-            .into_iter()
-            .map(|(key, value)| (key.trim().to_lowercase(), Self::normalize_category(&value)))
+            .iter()
+            .map(|(key, value)| (key.trim().to_lowercase(), self.known_category(value)))
             .collect::<HashMap<_, _>>();
 
         let domains = self
             .domains
-            .into_iter()
+            .iter()
             .map(|(key, value)| {
                 (
                     key.trim().trim_start_matches("www.").to_lowercase(),
-                    Self::normalize_category(&value),
+                    self.known_category(value),
                 )
             })
             .collect::<HashMap<_, _>>();
 
-        Self { apps, domains }
+        Self {
+            apps,
+            domains,
+            ..self
+        }
+    }
+}
+
+/// The first `depth` levels of a category; the whole category when it is not that deep.
+pub fn category_at_depth(category: &str, depth: usize) -> &str {
+    match category
+        .match_indices(CATEGORY_SEPARATOR)
+        .nth(depth.saturating_sub(1))
+    {
+        Some((index, _)) if depth > 0 => &category[..index],
+        _ => category,
     }
 }
 
+/// The class of the category's own definition, else of its nearest defined ancestor.
+pub fn category_class(definitions: &[CategoryDefinition], category: &str) -> CategoryClass {
+    let depth = category.matches(CATEGORY_SEPARATOR).count() + 1;
+    (1..=depth)
+        .rev()
+        .map(|depth| category_at_depth(category, depth))
+        .find_map(|ancestor| {
+            definitions
+                .iter()
+                .find(|definition| definition.id == ancestor)
+                .and_then(|definition| definition.class)
+        })
+        .unwrap_or_default()
+}
+
+/// The display name of a defined category, else its last level.
+pub fn category_name<'a>(definitions: &'a [CategoryDefinition], category: &'a str) -> &'a str {
+    definitions
+        .iter()
+        .find(|definition| definition.id == category)
+        .map(CategoryDefinition::display_name)
+        .unwrap_or_else(|| {
+            category
+                .rsplit(CATEGORY_SEPARATOR)
+                .next()
+                .unwrap_or(category)
+        })
+}
+
+fn normalize_path(raw: &str) -> String {
+    raw.split(CATEGORY_SEPARATOR)
+        .map(|level| level.trim().to_lowercase())
+        .filter(|level| !level.is_empty())
+        .collect::<Vec<_>>()
+        .join(&CATEGORY_SEPARATOR.to_string())
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn glob_pattern(glob: &str) -> String {
+    let body = glob
+        .chars()
+        .map(|character| match character {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            _ => regex::escape(&character.to_string()),
+        })
+        .collect::<String>();
+    format!("^{body}$")
+}
+
 fn domain_matches(domain: &str, rule: &str) -> bool {
     let normalized_rule = rule.trim().trim_start_matches("www.").to_lowercase();
     domain == normalized_rule || domain.ends_with(&format!(".{normalized_rule}"))
@@ -88,7 +818,7 @@ fn domain_matches(domain: &str, rule: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::CategoryRules;
+    use super::{CategoryRules, LintKind, OTHER_CATEGORY, Target};
     use std::collections::HashMap;
 
     #[test]
@@ -96,8 +826,145 @@ mod tests {
         let rules = CategoryRules {
             apps: HashMap::new(),
             domains: HashMap::from([("github.com".to_string(), "development".to_string())]),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+
+        assert_eq!(
+            rules.categorize_domain("docs.github.com", None),
+            "development"
+        );
+        assert_eq!(rules.category_id("개발").as_deref(), Some("development"));
+
+        let shipped =
+            serde_json::from_str::<CategoryRules>(include_str!("../../assets/categories.json"))
+                .expect("shipped categories");
+        let aliases = |rules: &CategoryRules| {
+            rules
+                .definitions()
+                .iter()
+                .map(|category| (category.id.clone(), category.aliases.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(aliases(&shipped), aliases(&rules));
+    }
+
+    #[test]
+    fn ordered_rules_win_by_priority_then_file_order() {
+        let rules = serde_json::from_str::<CategoryRules>(
+            r#"{
+                "apps": {"code": "development", "xcode": "other", "slack": "communication"},
+                "domains": {"google.com": "research", "mail.google.com": "communication"},
+                "rules": [
+                    {"category": "entertainment", "app": {"glob": "*code*"}, "title": {"regex": "twitch|youtube"}},
+                    {"category": "research", "app": {"exact": "Slack"}, "title": {"glob": "*#papers*"}},
+                    {"category": "communication", "priority": 5, "app": {"exact": "slack"}},
+                    {"category": "shopping", "url": {"regex": "^https://www\\.google\\.com/shopping"}}
+                ]
+            }"#,
+        )
+        .expect("rules");
+        rules.validate().expect("valid");
+
+        assert_eq!(rules.categorize_app("Xcode", None), "other");
+        assert_eq!(
+            rules.categorize_app("Visual Studio Code", None),
+            "development"
+        );
+        assert_eq!(
+            rules.categorize_app("Code", Some("YouTube - stream")),
+            "entertainment"
+        );
+        assert_eq!(
+            rules.categorize_app("Slack", Some("#papers - Lab")),
+            "communication"
+        );
+        assert_eq!(
+            rules.categorize_domain("mail.google.com", None),
+            "communication"
+        );
+        assert_eq!(
+            rules.categorize_domain(
+                "www.google.com",
+                Some("https://www.google.com/shopping?q=desk")
+            ),
+            "shopping"
+        );
+        assert_eq!(
+            rules.categorize_domain("www.google.com", Some("https://www.google.com/search")),
+            "research"
+        );
+
+        let invalid = serde_json::from_str::<CategoryRules>(
+            r#"{"apps": {}, "domains": {}, "rules": [{"category": "research"}]}"#,
+        )
+        .expect("rules");
+        assert!(invalid.validate().is_err());
+        assert!(
+            serde_json::from_str::<CategoryRules>(
+                r#"{"apps": {}, "domains": {}, "rules": [{"category": "research", "title": {"regex": "("}}]}"#,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn explains_matches_and_lints_unusable_rules() {
+        let rules = serde_json::from_str::<CategoryRules>(
+            r#"{
+                "apps": {"code": "development", "visual studio code": "development", "slack": "communication"},
+                "domains": {"github.com": "development", "youtube.com": "entertainment"},
+                "rules": [
+                    {"category": "entertainment", "app": {"glob": "*code*"}, "title": {"regex": "youtube"}},
+                    {"category": "research", "app": {"exact": "Slack"}, "title": {"glob": "*#papers*"}},
+                    {"category": "communication", "priority": 5, "app": {"glob": "*slack*"}},
+                    {"category": "research", "app": {"glob": "*CODE*"}, "title": {"regex": "youtube"}},
+                    {"category": "other", "app": {"exact": "Code"}, "domain": {"exact": "github.com"}},
+                    {"category": "entertainment", "domain": {"glob": "*youtube.com"}}
+                ]
+            }"#,
+        )
+        .expect("rules");
+        rules.validate().expect("valid");
+
+        let target = Target {
+            app: Some("Visual Studio Code"),
+            title: Some("YouTube - main.rs"),
+            ..Target::default()
         };
+        let explanation = rules.explain(&target);
+        assert_eq!(explanation.category, rules.categorize(&target));
+        let matched = explanation
+            .matches
+            .iter()
+            .map(|matched| (matched.rule.as_str(), matched.category.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            matched,
+            vec![
+                ("rules[0]", "entertainment"),
+                ("rules[3]", "research"),
+                ("apps.visual studio code", "development"),
+                ("apps.code", "development"),
+            ]
+        );
+        assert_eq!(rules.explain(&Target::default()).category, OTHER_CATEGORY);
 
-        assert_eq!(rules.categorize_domain("docs.github.com"), "development");
+        let issues = rules
+            .lint()
+            .into_iter()
+            .map(|issue| (issue.kind, issue.rule))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            vec![
+                (LintKind::Shadowed, "rules[1]".to_string()),
+                (LintKind::Duplicate, "rules[3]".to_string()),
+                (LintKind::Unreachable, "rules[4]".to_string()),
+                (LintKind::Unreachable, "apps.slack".to_string()),
+                (LintKind::Unreachable, "domains.youtube.com".to_string()),
+            ]
+        );
     }
 }
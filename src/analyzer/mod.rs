@@ -1,23 +1,43 @@
 pub mod categorizer;
+pub mod project;
+pub mod recategorize;
+pub mod reconcile;
 pub mod report;
 
+use crate::analyzer::categorizer::CategoryRules;
 use crate::analyzer::report::{DailyReport, SavedReport};
 use crate::config::Config;
 use crate::db::Database;
 use anyhow::Result;
 use chrono::{NaiveDate, Utc};
+use serde_json::Value;
 use std::collections::HashSet;
+use std::fs;
+
+/// Prefix of the anomalies that hold AI insights.
+const AI_INSIGHT_PREFIX: &str = "AI insight: ";
 
 pub fn generate_and_store_report(
     config: &Config,
+    rules: &CategoryRules,
     date: NaiveDate,
     ai_insights: Vec<String>,
 ) -> Result<(DailyReport, SavedReport)> {
     let database = Database::open(&config.db_path)?;
     let activities = database.activities_for_date(date)?;
-    let domains = database.chrome_visits_for_date(date)?;
+    let domains = database.web_visits_between(date, date)?;
+
+    let heartbeats = database.heartbeats_for_date(date)?;
+    let shell_commands = database.shell_commands_for_date(date)?;
+    let commits = database.git_commits_for_date(date)?;
+    let sessions = database.sessions_for_date(date)?;
+    let calendar_events = database.calendar_events_for_date(date)?;
 
-    let mut report = report::build_daily_report(date, &activities, &domains);
+    let mut report = report::build_daily_report(date, &activities, &domains, rules.definitions());
+    report.meetings = report::meeting_summary(&calendar_events, &activities, rules.definitions());
+    report.coding = report::coding_summary(&heartbeats);
+    report.shell = report::shell_summary(&shell_commands);
+    report.commits = report::commit_summary(&commits, &sessions, rules.definitions());
 
     let mut seen = HashSet::new();
     report.anomalies = report
@@ -26,7 +46,7 @@ pub fn generate_and_store_report(
         .chain(
             ai_insights
                 .into_iter()
-                .map(|entry| format!("AI insight: {entry}")),
+                .map(|entry| format!("{AI_INSIGHT_PREFIX}{entry}")),
         )
         .filter(|entry| seen.insert(entry.clone()))
         .collect::<Vec<_>>();
@@ -42,3 +62,78 @@ pub fn generate_and_store_report(
 
     Ok((report, saved))
 }
+
+/// Rebuilds the report of `date` from the database. AI enrichment is not run again, so the AI
+/// insights of the report it replaces are kept.
+pub fn regenerate_report(
+    config: &Config,
+    rules: &CategoryRules,
+    date: NaiveDate,
+) -> Result<(DailyReport, SavedReport)> {
+    let insights = stored_ai_insights(&Database::open(&config.db_path)?, date)?;
+    generate_and_store_report(config, rules, date, insights)
+}
+
+fn stored_ai_insights(database: &Database, date: NaiveDate) -> Result<Vec<String>> {
+    let Some(meta) = database.report_meta(date)? else {
+        return Ok(Vec::new());
+    };
+    // A report file that was deleted or cannot be parsed has no insights to keep.
+    let Some(report) = fs::read_to_string(&meta.json_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+    else {
+        return Ok(Vec::new());
+    };
+
+    Ok(report["anomalies"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter_map(|anomaly| anomaly.strip_prefix(AI_INSIGHT_PREFIX))
+        .map(ToOwned::to_owned)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_and_store_report, regenerate_report};
+    use crate::analyzer::categorizer::CategoryRules;
+    use crate::config::Config;
+    use chrono::NaiveDate;
+    use std::collections::HashMap;
+
+    #[test]
+    fn regenerating_a_report_keeps_its_ai_insights() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let config = Config {
+            db_path: dir.path().join("activity.db"),
+            report_dir: dir.path().join("reports"),
+            ..Config::default()
+        };
+        let rules = CategoryRules {
+            apps: HashMap::new(),
+            domains: HashMap::new(),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+
+        generate_and_store_report(
+            &config,
+            &rules,
+            date,
+            vec!["Long research streak".to_string()],
+        )
+        .expect("report");
+        let (report, _) = regenerate_report(&config, &rules, date).expect("regenerate");
+
+        assert!(
+            report
+                .anomalies
+                .contains(&"AI insight: Long research streak".to_string())
+        );
+    }
+}
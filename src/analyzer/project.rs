@@ -0,0 +1,262 @@
+use crate::analyzer::reconcile;
+use crate::config::expand_home;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A user-defined rule in `categories.json` (`project_extractors`). The project is the `project`
+/// named group, else the first capture group, else the whole match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectExtractorRule {
+    /// Only applies when the app name contains this (case-insensitive); all apps when omitted.
+    #[serde(default)]
+    pub app: Option<String>,
+    pub pattern: String,
+}
+
+const JETBRAINS_APPS: [&str; 13] = [
+    "jetbrains",
+    "intellij",
+    "pycharm",
+    "webstorm",
+    "goland",
+    "rustrover",
+    "clion",
+    "phpstorm",
+    "rider",
+    "rubymine",
+    "datagrip",
+    "android studio",
+    "fleet",
+];
+
+const CODE_EDITOR_APPS: [&str; 5] = ["code", "cursor", "vscodium", "windsurf", "zed"];
+
+const TERMINAL_APPS: [&str; 12] = [
+    "terminal",
+    "iterm",
+    "alacritty",
+    "kitty",
+    "wezterm",
+    "konsole",
+    "ghostty",
+    "foot",
+    "tilix",
+    "xterm",
+    "hyper",
+    "warp",
+];
+
+/// Titles VS Code-style editors show when no folder is open.
+const EDITOR_PLACEHOLDER_TITLES: [&str; 4] = ["welcome", "get started", "settings", "extensions"];
+
+/// Derives a project name from a window title: user rules first, then built-in extractors for
+/// VS Code-style editors, JetBrains IDEs, terminals and GitHub/GitLab pages in browsers.
+#[derive(Debug, Clone)]
+pub struct TitleParser {
+    rules: Vec<(Option<String>, Regex)>,
+    repo_page: Regex,
+}
+
+impl TitleParser {
+    pub fn new(rules: &[ProjectExtractorRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                let regex = Regex::new(&rule.pattern).with_context(|| {
+                    format!(
+                        "Invalid project_extractors[{index}] pattern: {}",
+                        rule.pattern
+                    )
+                })?;
+                let app = rule
+                    .app
+                    .as_deref()
+                    .map(|app| app.trim().to_lowercase())
+                    .filter(|app| !app.is_empty());
+                Ok((app, regex))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            rules,
+            repo_page: Regex::new(
+                r"(?:^|[\s·|])(?:[A-Za-z0-9_.-]+)/(?P<project>[A-Za-z0-9_.-]+)(?:[:\s·|]|$)",
+            )
+            .expect("valid repository page pattern"),
+        })
+    }
+
+    pub fn project(&self, app_name: &str, window_title: Option<&str>) -> Option<String> {
+        let title = window_title
+            .map(str::trim)
+            .filter(|title| !title.is_empty())?;
+        let app = app_name.trim().to_lowercase();
+
+        self.rules
+            .iter()
+            .filter(|(rule_app, _)| {
+                rule_app
+                    .as_ref()
+                    .is_none_or(|rule_app| app.contains(rule_app))
+            })
+            .find_map(|(_, regex)| {
+                let captures = regex.captures(title)?;
+                captures
+                    .name("project")
+                    .or_else(|| captures.get(1))
+                    .or_else(|| captures.get(0))
+                    .map(|found| found.as_str().to_string())
+            })
+            .or_else(|| self.built_in_project(&app, title))
+            .map(|project| project.trim().to_string())
+            .filter(|project| !project.is_empty())
+    }
+
+    fn built_in_project(&self, app: &str, title: &str) -> Option<String> {
+        if JETBRAINS_APPS.iter().any(|name| app.contains(name)) {
+            jetbrains_project(title)
+        } else if app.contains("visual studio code")
+            || CODE_EDITOR_APPS
+                .iter()
+                .any(|name| app == *name || app.starts_with(&format!("{name} ")))
+        {
+            code_editor_project(title)
+        } else if TERMINAL_APPS.iter().any(|name| app.contains(name)) {
+            terminal_project(title)
+        } else if reconcile::browser_for_app(app).is_some()
+            && (title.contains("GitHub") || title.contains("GitLab"))
+        {
+            self.repo_page
+                .captures(title)
+                .and_then(|captures| captures.name("project"))
+                .map(|found| found.as_str().to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// `● main.rs — opentracker — Visual Studio Code`: the segment before the app name, ignoring
+/// `(Workspace)` and remote markers such as `[SSH: host]`.
+fn code_editor_project(title: &str) -> Option<String> {
+    let segments = split_title(title, &[" — ", " - "]);
+    let segments = match segments.split_last() {
+        Some((last, rest)) if !rest.is_empty() && is_editor_name(last) => rest,
+        _ => &segments[..],
+    };
+
+    let candidate = match segments {
+        [] => return None,
+        [only] if only.contains('.') => return None,
+        [.., last] => *last,
+    };
+    let candidate = candidate
+        .split(" [")
+        .next()
+        .unwrap_or(candidate)
+        .trim_end_matches(" (Workspace)")
+        .trim();
+
+    (!EDITOR_PLACEHOLDER_TITLES.contains(&candidate.to_lowercase().as_str()))
+        .then(|| candidate.to_string())
+}
+
+/// `opentracker [~/src/opentracker] – main.rs`: the first segment, without the path.
+fn jetbrains_project(title: &str) -> Option<String> {
+    split_title(title, &[" – ", " - "])
+        .first()
+        .map(|first| first.split(" [").next().unwrap_or(first).trim().to_string())
+}
+
+/// `dev@host: ~/src/opentracker/src` or `~/src/opentracker — zsh`: the first path in the title,
+/// resolved to its Git repository when it exists locally. The home directory alone is not a project.
+fn terminal_project(title: &str) -> Option<String> {
+    let path = title
+        .split(|ch: char| ch.is_whitespace() || ch == ':')
+        .find(|word| word.starts_with('/') || word.starts_with("~/"))?;
+    let path = expand_home(path.trim_end_matches('/'));
+
+    repository_root(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// The nearest ancestor holding a `.git` entry, or the path itself outside a repository.
+pub fn repository_root(path: &Path) -> &Path {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(path)
+}
+
+fn split_title<'a>(title: &'a str, separators: &[&str]) -> Vec<&'a str> {
+    separators
+        .iter()
+        .find(|separator| title.contains(*separator))
+        .map(|separator| title.split(*separator).map(str::trim).collect())
+        .unwrap_or_else(|| vec![title.trim()])
+}
+
+fn is_editor_name(segment: &str) -> bool {
+    let segment = segment.to_lowercase();
+    [
+        "visual studio code",
+        "code - oss",
+        "vscodium",
+        "cursor",
+        "windsurf",
+        "zed",
+    ]
+    .iter()
+    .any(|name| segment.starts_with(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProjectExtractorRule, TitleParser};
+
+    #[test]
+    fn extracts_projects_from_editor_terminal_and_browser_titles() {
+        let parser = TitleParser::new(&[ProjectExtractorRule {
+            app: Some("obsidian".to_string()),
+            pattern: r"- (?P<project>[^-]+) - Obsidian".to_string(),
+        }])
+        .expect("parser");
+        let project = |app: &str, title: &str| parser.project(app, Some(title));
+
+        assert_eq!(
+            project("Code", "● main.rs — opentracker — Visual Studio Code").as_deref(),
+            Some("opentracker")
+        );
+        assert_eq!(
+            project("Code", "lib.rs - api [SSH: devbox] - Visual Studio Code").as_deref(),
+            Some("api")
+        );
+        assert_eq!(project("Code", "Welcome — Visual Studio Code"), None);
+        assert_eq!(
+            project("RustRover", "opentracker [~/src/opentracker] – report.rs").as_deref(),
+            Some("opentracker")
+        );
+        assert_eq!(
+            project("iTerm2", "dev@box: /srv/opentracker").as_deref(),
+            Some("opentracker")
+        );
+        assert_eq!(project("Terminal", "~ — -zsh — 80×24"), None);
+        assert_eq!(
+            project(
+                "Google Chrome",
+                "Fix parser · Pull Request #12 · acme/opentracker · GitHub - Google Chrome"
+            )
+            .as_deref(),
+            Some("opentracker")
+        );
+        assert_eq!(project("Google Chrome", "Rust Programming Language"), None);
+        assert_eq!(
+            project("Obsidian", "Daily note - research - Obsidian v1.5").as_deref(),
+            Some("research")
+        );
+        assert_eq!(project("Slack", "general - Acme"), None);
+    }
+}
@@ -0,0 +1,303 @@
+use crate::analyzer::categorizer::CategoryRules;
+use crate::db::{Database, EXPLICIT_CATEGORY_KEY};
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Time that changed category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CategoryMove {
+    pub from: String,
+    pub to: String,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct RecategorizeSummary {
+    pub activities_changed: usize,
+    pub sessions_changed: usize,
+    pub visits_changed: usize,
+    /// Activity time moved between categories, largest first.
+    pub activity_moves: Vec<CategoryMove>,
+    /// Browser visit time moved between categories, largest first.
+    pub visit_moves: Vec<CategoryMove>,
+    /// Dates whose reports include a changed activity or visit.
+    pub dates: Vec<NaiveDate>,
+    pub dry_run: bool,
+}
+
+/// Re-applies the current rules to the stored activities, sessions and browser visits of
+/// `from..=to`. Spans given an explicit category and visits categorized by AI enrichment keep
+/// their category. With `dry_run`, only reports what would change.
+pub fn recategorize(
+    database: &mut Database,
+    rules: &CategoryRules,
+    from: NaiveDate,
+    to: NaiveDate,
+    dry_run: bool,
+) -> Result<RecategorizeSummary> {
+    if from > to {
+        bail!("`from` ({from}) is after `to` ({to})");
+    }
+
+    let mut dates = BTreeSet::new();
+    let mut activities = BTreeMap::new();
+    let mut activity_moves = BTreeMap::new();
+    let mut sessions = BTreeMap::new();
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        for activity in database.activities_for_date(date)? {
+            let explicit = activity
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get(EXPLICIT_CATEGORY_KEY))
+                .is_some_and(|explicit| explicit.as_bool() == Some(true));
+            if explicit {
+                continue;
+            }
+            let category =
+                rules.categorize_app(&activity.app_name, activity.window_title.as_deref());
+            if category == activity.category {
+                continue;
+            }
+            dates.insert(date);
+            // Samples at midnight belong to two days.
+            if activities.insert(activity.id, category.clone()).is_none() {
+                *activity_moves
+                    .entry((activity.category, category))
+                    .or_insert(0) += activity.duration_sec;
+            }
+        }
+        for session in database.sessions_for_date(date)? {
+            let category = rules.categorize_app(&session.app_name, session.window_title.as_deref());
+            if category != session.category {
+                sessions.insert(session.id, category);
+            }
+        }
+    }
+
+    let mut visits = Vec::new();
+    let mut visit_moves = BTreeMap::new();
+    for visit in database.rule_categorized_visits_between(from, to)? {
+        let category = rules.categorize_domain(&visit.domain, visit.url.as_deref());
+        if category == visit.category {
+            continue;
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(&visit.date, "%Y-%m-%d") {
+            dates.insert(date);
+        }
+        *visit_moves
+            .entry((visit.category, category.clone()))
+            .or_insert(0) += visit.duration_sec;
+        visits.push((visit.id, category));
+    }
+
+    let activities = activities.into_iter().collect::<Vec<_>>();
+    let sessions = sessions.into_iter().collect::<Vec<_>>();
+    if !dry_run {
+        database.update_categories(&activities, &sessions, &visits)?;
+    }
+
+    Ok(RecategorizeSummary {
+        activities_changed: activities.len(),
+        sessions_changed: sessions.len(),
+        visits_changed: visits.len(),
+        activity_moves: sorted_moves(activity_moves),
+        visit_moves: sorted_moves(visit_moves),
+        dates: dates.into_iter().collect(),
+        dry_run,
+    })
+}
+
+fn sorted_moves(moves: BTreeMap<(String, String), i64>) -> Vec<CategoryMove> {
+    let mut moves = moves
+        .into_iter()
+        .map(|((from, to), seconds)| CategoryMove { from, to, seconds })
+        .collect::<Vec<_>>();
+    moves.sort_by_key(|category_move| Reverse(category_move.seconds));
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CategoryMove, recategorize};
+    use crate::analyzer::categorizer::CategoryRules;
+    use crate::db::{ActivityInput, BrowserVisitInput, Database, VisitCategoryChange};
+    use chrono::{Local, NaiveDate, TimeZone};
+    use std::collections::HashMap;
+
+    #[test]
+    fn reapplies_rules_to_window_activities_and_visits() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let mut database = Database::open(&dir.path().join("activity.db")).expect("open db");
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+        let at = |hour| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(hour, 0, 0).expect("time"))
+                .single()
+                .expect("local time")
+                .timestamp()
+        };
+        let activity = |hour, app: &str, source: &str| ActivityInput {
+            recorded_at: at(hour),
+            app_name: app.to_string(),
+            window_title: None,
+            category: "other".to_string(),
+            project: None,
+            duration_sec: 600,
+            state: "active".to_string(),
+            source: source.to_string(),
+            external_id: (source != "window").then(|| format!("{app}-{hour}")),
+            metadata: None,
+        };
+        for activity in [
+            activity(9, "Code", "window"),
+            activity(10, "Code", "window"),
+            activity(11, "Slack", "window"),
+            activity(12, "Code", "csv"),
+            ActivityInput {
+                metadata: Some(r#"{"category_explicit": true}"#.to_string()),
+                ..activity(13, "Code", "csv")
+            },
+        ] {
+            database.insert_activity(&activity).expect("activity");
+            if activity.source == "window" {
+                database.record_session_sample(&activity).expect("session");
+            }
+        }
+        database
+            .upsert_browser_visits(
+                "Default",
+                &[BrowserVisitInput {
+                    source_visit_id: 1,
+                    date,
+                    visited_at: at(9),
+                    browser: "chrome".to_string(),
+                    domain: "github.com".to_string(),
+                    url: "https://github.com/rust-lang/rust".to_string(),
+                    transition: "link".to_string(),
+                    category: "research".to_string(),
+                    duration_sec: 120,
+                }],
+                None,
+            )
+            .expect("visits");
+        database
+            .upsert_browser_visits(
+                "Default",
+                &[BrowserVisitInput {
+                    source_visit_id: 2,
+                    date,
+                    visited_at: at(10),
+                    browser: "chrome".to_string(),
+                    domain: "docs.rs".to_string(),
+                    url: "https://docs.rs/serde".to_string(),
+                    transition: "link".to_string(),
+                    category: "other".to_string(),
+                    duration_sec: 60,
+                }],
+                None,
+            )
+            .expect("visits");
+        // Put elsewhere by a URL rule, so the AI's per-domain answer does not apply to it.
+        database
+            .upsert_browser_visits(
+                "Default",
+                &[BrowserVisitInput {
+                    source_visit_id: 3,
+                    date,
+                    visited_at: at(11),
+                    browser: "chrome".to_string(),
+                    domain: "docs.rs".to_string(),
+                    url: "https://docs.rs/releases".to_string(),
+                    transition: "link".to_string(),
+                    category: "development".to_string(),
+                    duration_sec: 30,
+                }],
+                None,
+            )
+            .expect("visits");
+        database
+            .update_chrome_visit_categories(
+                date,
+                &[VisitCategoryChange {
+                    browser: "chrome".to_string(),
+                    domain: "docs.rs".to_string(),
+                    from: "other".to_string(),
+                    to: "research".to_string(),
+                }],
+            )
+            .expect("AI categories");
+        let visit_categories = database
+            .web_visits_between(date, date)
+            .expect("visits")
+            .into_iter()
+            .filter(|visit| visit.domain == "docs.rs")
+            .map(|visit| visit.category)
+            .collect::<Vec<_>>();
+        assert_eq!(visit_categories, vec!["research", "development"]);
+
+        let rules = CategoryRules {
+            apps: HashMap::from([
+                ("code".to_string(), "development".to_string()),
+                ("slack".to_string(), "other".to_string()),
+            ]),
+            domains: HashMap::from([
+                ("github.com".to_string(), "development".to_string()),
+                ("docs.rs".to_string(), "development".to_string()),
+            ]),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+
+        let preview = recategorize(&mut database, &rules, date, date, true).expect("dry run");
+        assert_eq!(preview.activities_changed, 3);
+        assert_eq!(
+            preview.activity_moves,
+            vec![CategoryMove {
+                from: "other".to_string(),
+                to: "development".to_string(),
+                seconds: 1800,
+            }]
+        );
+        assert_eq!(
+            database.activities_for_date(date).expect("activities")[0].category,
+            "other"
+        );
+
+        let summary = recategorize(&mut database, &rules, date, date, false).expect("apply");
+        assert_eq!(summary.sessions_changed, 2);
+        assert_eq!(summary.visits_changed, 1);
+        assert_eq!(summary.visit_moves[0].seconds, 120);
+        assert_eq!(summary.dates, vec![date]);
+
+        let categories = database
+            .activities_for_date(date)
+            .expect("activities")
+            .into_iter()
+            .map(|activity| (activity.source, activity.category))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            categories,
+            vec![
+                ("window".to_string(), "development".to_string()),
+                ("window".to_string(), "development".to_string()),
+                ("window".to_string(), "other".to_string()),
+                ("csv".to_string(), "development".to_string()),
+                ("csv".to_string(), "other".to_string()),
+            ]
+        );
+        assert_eq!(
+            database.sessions_for_date(date).expect("sessions")[0].category,
+            "development"
+        );
+        assert_eq!(
+            recategorize(&mut database, &rules, date, date, false)
+                .expect("again")
+                .activities_changed,
+            0
+        );
+    }
+}
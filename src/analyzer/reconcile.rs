@@ -0,0 +1,190 @@
+use crate::db::{ActivityRow, WINDOW_SOURCE, WebVisitRow};
+use std::collections::HashMap;
+
+/// Window app names (macOS names and Linux WM classes) mapped to the browser ids used for visits.
+const BROWSER_APPS: [(&str, &str); 8] = [
+    ("firefox", "firefox"),
+    ("brave", "brave"),
+    ("microsoft edge", "edge"),
+    ("microsoft-edge", "edge"),
+    ("vivaldi", "vivaldi"),
+    ("chromium", "chromium"),
+    ("chrome", "chrome"),
+    ("arc", "arc"),
+];
+
+pub fn browser_for_app(app_name: &str) -> Option<&'static str> {
+    let name = app_name.trim().to_lowercase();
+    BROWSER_APPS
+        .iter()
+        .find(|(pattern, _)| {
+            if *pattern == "arc" {
+                name == "arc"
+            } else {
+                name.contains(pattern)
+            }
+        })
+        .map(|(_, browser)| *browser)
+}
+
+/// Activities as foreground time: window samples as stored, and spans of other sources (editor
+/// plugins, scripts) cut to the parts no window sample covers, so time recorded by both counts once.
+pub fn foreground_activities(activities: &[ActivityRow]) -> Vec<ActivityRow> {
+    let mut windows = activities
+        .iter()
+        .filter(|activity| activity.source == WINDOW_SOURCE)
+        .map(|activity| {
+            (
+                activity.recorded_at - activity.duration_sec.max(0),
+                activity.recorded_at,
+            )
+        })
+        .collect::<Vec<_>>();
+    windows.sort_unstable();
+
+    activities
+        .iter()
+        .flat_map(|activity| {
+            if activity.source == WINDOW_SOURCE {
+                return vec![activity.clone()];
+            }
+            let start = activity.recorded_at - activity.duration_sec.max(0);
+            uncovered_spans(start, activity.recorded_at, &windows)
+                .into_iter()
+                .map(|(from, to)| ActivityRow {
+                    recorded_at: to,
+                    duration_sec: to - from,
+                    ..activity.clone()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The parts of `start..end` outside `covered`, which is sorted by start.
+fn uncovered_spans(start: i64, end: i64, covered: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut spans = Vec::new();
+    let mut cursor = start;
+    for &(from, to) in covered {
+        if from >= end {
+            break;
+        }
+        if from > cursor {
+            spans.push((cursor, from));
+        }
+        cursor = cursor.max(to);
+    }
+    if cursor < end {
+        spans.push((cursor, end));
+    }
+    spans
+}
+
+/// Attributes the time a browser was frontmost to the domains visited in it. Each browser sample
+/// is split across that browser's visits overlapping the sample, in proportion to the overlap;
+/// with no overlap the whole sample goes to the page opened most recently before it ended.
+pub fn foreground_domain_seconds(
+    activities: &[&ActivityRow],
+    visits: &[WebVisitRow],
+) -> HashMap<String, i64> {
+    let timed_visits = visits
+        .iter()
+        .filter_map(|visit| {
+            visit
+                .visited_at
+                .map(|at| (visit, at, at + visit.duration_sec.max(0)))
+        })
+        .collect::<Vec<_>>();
+
+    activities
+        .iter()
+        .filter(|activity| activity.duration_sec > 0)
+        .filter_map(|activity| browser_for_app(&activity.app_name).map(|id| (activity, id)))
+        .fold(HashMap::new(), |mut acc, (activity, browser)| {
+            let end = activity.recorded_at;
+            let start = end - activity.duration_sec;
+            let candidates = timed_visits
+                .iter()
+                .filter(|(visit, ..)| visit.browser == browser)
+                .collect::<Vec<_>>();
+
+            let overlaps = candidates
+                .iter()
+                .map(|(visit, from, to)| (visit, (*to).min(end) - (*from).max(start)))
+                .filter(|(_, overlap)| *overlap > 0)
+                .collect::<Vec<_>>();
+            let total_overlap = overlaps.iter().map(|(_, overlap)| overlap).sum::<i64>();
+
+            if total_overlap > 0 {
+                overlaps.iter().for_each(|(visit, overlap)| {
+                    *acc.entry(visit.domain.clone()).or_insert(0) +=
+                        activity.duration_sec * overlap / total_overlap;
+                });
+            } else if let Some((visit, ..)) = candidates
+                .iter()
+                .filter(|(_, from, _)| *from < end)
+                .max_by_key(|(_, from, _)| *from)
+            {
+                *acc.entry(visit.domain.clone()).or_insert(0) += activity.duration_sec;
+            }
+
+            acc
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{browser_for_app, foreground_domain_seconds};
+    use crate::db::{ActivityRow, WebVisitRow};
+
+    #[test]
+    fn splits_browser_samples_across_overlapping_visits() {
+        assert_eq!(browser_for_app("Google Chrome"), Some("chrome"));
+        assert_eq!(browser_for_app("firefox"), Some("firefox"));
+        assert_eq!(browser_for_app("Code"), None);
+
+        let sample = |id, app: &str, recorded_at| ActivityRow {
+            id,
+            recorded_at,
+            app_name: app.to_string(),
+            window_title: None,
+            category: "other".to_string(),
+            project: None,
+            duration_sec: 300,
+            state: "active".to_string(),
+            source: "window".to_string(),
+            external_id: None,
+            metadata: None,
+        };
+        let visit = |id, domain: &str, visited_at, duration_sec| WebVisitRow {
+            id,
+            date: "2026-02-18".to_string(),
+            visited_at: Some(visited_at),
+            browser: "chrome".to_string(),
+            domain: domain.to_string(),
+            url: None,
+            transition: None,
+            category: "other".to_string(),
+            duration_sec,
+        };
+
+        let activities = [
+            sample(1, "Google Chrome", 1_300),
+            sample(2, "Code", 1_600),
+            sample(3, "Google Chrome", 1_900),
+        ];
+        // Two background tabs overlap the first sample; the second browser sample has no visit
+        // inside it, so it goes to the page opened last.
+        let visits = [
+            visit(1, "github.com", 1_000, 200),
+            visit(2, "youtube.com", 1_100, 500),
+            visit(3, "docs.rs", 1_500, 0),
+        ];
+
+        let seconds = foreground_domain_seconds(&activities.iter().collect::<Vec<_>>(), &visits);
+
+        assert_eq!(seconds["github.com"], 150);
+        assert_eq!(seconds["youtube.com"], 150);
+        assert_eq!(seconds["docs.rs"], 300);
+    }
+}
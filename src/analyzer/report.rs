@@ -1,8 +1,17 @@
-use crate::db::{ActivityRow, ChromeVisitRow};
+use crate::analyzer::categorizer::{
+    CATEGORY_SEPARATOR, CategoryClass, CategoryDefinition, category_at_depth, category_class,
+    category_name,
+};
+use crate::analyzer::reconcile;
+use crate::db::{
+    ActivityRow, CalendarEventRow, GitCommitRow, HeartbeatRow, SessionRow, ShellCommandRow,
+    WebVisitRow,
+};
 use anyhow::{Context, Result};
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -13,6 +22,117 @@ pub struct ReportMetric {
     pub minutes: u64,
 }
 
+/// Active window time and browser visit time that fall within one local hour.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HourlyActivity {
+    pub hour: u32,
+    pub window_seconds: u64,
+    pub web_seconds: u64,
+}
+
+/// Editor time from WakaTime-compatible heartbeats.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CodingSummary {
+    pub total_seconds: u64,
+    pub total_minutes: u64,
+    pub projects: Vec<ReportMetric>,
+    pub languages: Vec<ReportMetric>,
+    pub files: Vec<ReportMetric>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandMetric {
+    pub name: String,
+    pub count: u64,
+    pub seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellProjectSummary {
+    pub directory: String,
+    pub command_count: u64,
+    pub seconds: u64,
+    pub top_commands: Vec<CommandMetric>,
+}
+
+/// Terminal commands reported by the shell hooks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShellSummary {
+    pub command_count: u64,
+    pub failed_count: u64,
+    pub total_seconds: u64,
+    /// Commands that ran for at least `LONG_RUNNING_COMMAND_SECONDS` (builds, test suites, ...).
+    pub long_running_count: u64,
+    pub long_running_seconds: u64,
+    pub long_running: Vec<CommandMetric>,
+    pub projects: Vec<ShellProjectSummary>,
+}
+
+/// The development session a commit was made in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommitSession {
+    pub app_name: String,
+    pub window_title: Option<String>,
+    pub started_at: i64,
+    pub ended_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitEntry {
+    pub repo: String,
+    pub sha: String,
+    pub branch: String,
+    pub committed_at: i64,
+    pub summary: String,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    pub files_changed: u64,
+    pub session: Option<CommitSession>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitSummary {
+    pub count: u64,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    pub commits: Vec<CommitEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingEntry {
+    pub calendar: String,
+    pub summary: String,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub seconds: u64,
+    /// Active window time outside communication apps while the meeting was scheduled.
+    pub other_activity_seconds: u64,
+    pub other_apps: Vec<ReportMetric>,
+}
+
+/// Meetings imported from calendar files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingSummary {
+    pub count: u64,
+    /// Overlapping meetings are counted once.
+    pub total_seconds: u64,
+    pub total_minutes: u64,
+    pub other_activity_seconds: u64,
+    pub other_activity_minutes: u64,
+    pub meetings: Vec<MeetingEntry>,
+}
+
+/// A category with the time of its sub-categories rolled up into `seconds`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CategoryNode {
+    pub id: String,
+    pub name: String,
+    pub seconds: u64,
+    /// Time recorded on the category itself rather than on a sub-category.
+    pub own_seconds: u64,
+    pub children: Vec<CategoryNode>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyReport {
     pub date: String,
@@ -21,17 +141,62 @@ pub struct DailyReport {
     pub total_minutes: u64,
     pub active_window_seconds: u64,
     pub active_window_minutes: u64,
+    #[serde(default)]
+    pub idle_seconds: u64,
+    #[serde(default)]
+    pub idle_minutes: u64,
     pub chrome_history_seconds: u64,
     pub chrome_history_minutes: u64,
+    /// Time a browser window was frontmost, attributed to domains by `reconcile`.
+    #[serde(default)]
+    pub web_foreground_seconds: u64,
+    #[serde(default)]
+    pub web_foreground_minutes: u64,
     pub categories_seconds: BTreeMap<String, u64>,
     pub categories: BTreeMap<String, u64>,
     pub chrome_categories_seconds: BTreeMap<String, u64>,
     pub chrome_categories: BTreeMap<String, u64>,
+    /// Active window time at every category level; `categories_seconds` holds the top level.
+    #[serde(default)]
+    pub category_tree: Vec<CategoryNode>,
+    /// The `categories.json` definitions the report was built with, in display order.
+    #[serde(default)]
+    pub category_definitions: Vec<CategoryDefinition>,
+    /// Active window time in categories classed productive or distracting.
+    #[serde(default)]
+    pub productive_seconds: u64,
+    #[serde(default)]
+    pub distracting_seconds: u64,
     pub top_apps: Vec<ReportMetric>,
+    /// Active window time by the project parsed from window titles.
+    #[serde(default)]
+    pub projects: Vec<ReportMetric>,
     pub top_domains: Vec<ReportMetric>,
+    /// Raw per-domain history durations; tabs can overlap, so these may exceed foreground time.
+    #[serde(default)]
+    pub top_domains_history: Vec<ReportMetric>,
+    #[serde(default)]
+    pub hourly: Vec<HourlyActivity>,
+    #[serde(default)]
+    pub meetings: MeetingSummary,
+    #[serde(default)]
+    pub coding: CodingSummary,
+    #[serde(default)]
+    pub shell: ShellSummary,
+    #[serde(default)]
+    pub commits: CommitSummary,
     pub anomalies: Vec<String>,
 }
 
+/// Heartbeats further apart than this are treated as separate coding stretches.
+const CODING_TIMEOUT_SECONDS: i64 = 15 * 60;
+
+const LONG_RUNNING_COMMAND_SECONDS: i64 = 60;
+
+/// A commit shortly after a development session ended (e.g. from a terminal sampled as another
+/// app) still belongs to it; one default polling interval.
+const COMMIT_SESSION_TOLERANCE_SECONDS: i64 = 300;
+
 #[derive(Debug)]
 pub struct SavedReport {
     pub markdown_path: PathBuf,
@@ -41,9 +206,22 @@ pub struct SavedReport {
 pub fn build_daily_report(
     date: NaiveDate,
     activities: &[ActivityRow],
-    domains: &[ChromeVisitRow],
+    domains: &[WebVisitRow],
+    definitions: &[CategoryDefinition],
 ) -> DailyReport {
     let generated_at: DateTime<Utc> = Utc::now();
+    let activities = reconcile::foreground_activities(activities);
+
+    // Idle samples count toward tracked time but never toward apps or categories.
+    let idle_seconds = activities
+        .iter()
+        .filter(|activity| activity.state == "idle")
+        .map(|activity| activity.duration_sec.max(0))
+        .sum::<i64>();
+    let activities = activities
+        .iter()
+        .filter(|activity| activity.state != "idle")
+        .collect::<Vec<_>>();
 
     let activity_total_seconds = activities
         .iter()
@@ -77,54 +255,87 @@ pub fn build_daily_report(
         acc
     });
 
-    let categories = canonical_categories()
-        .into_iter()
-        .map(|category| {
-            let seconds = activity_category_seconds
-                .get(category)
-                .copied()
-                .unwrap_or_default();
-            (category.to_string(), sec_to_min(seconds))
-        })
-        .collect::<BTreeMap<_, _>>();
-    let chrome_categories = canonical_categories()
-        .into_iter()
-        .map(|category| {
-            let seconds = domain_category_seconds
-                .get(category)
-                .copied()
-                .unwrap_or_default();
-            (category.to_string(), sec_to_min(seconds))
-        })
-        .collect::<BTreeMap<_, _>>();
-    let categories_seconds = canonical_categories()
-        .into_iter()
-        .map(|category| {
-            let seconds = activity_category_seconds
-                .get(category)
-                .copied()
-                .unwrap_or_default()
-                .max(0) as u64;
-            (category.to_string(), seconds)
-        })
-        .collect::<BTreeMap<_, _>>();
-    let chrome_categories_seconds = canonical_categories()
-        .into_iter()
-        .map(|category| {
-            let seconds = domain_category_seconds
-                .get(category)
-                .copied()
-                .unwrap_or_default()
-                .max(0) as u64;
-            (category.to_string(), seconds)
-        })
-        .collect::<BTreeMap<_, _>>();
+    let project_seconds = activities
+        .iter()
+        .filter_map(|activity| Some((activity.project.clone()?, activity.duration_sec.max(0))))
+        .fold(HashMap::new(), |mut acc, (project, seconds)| {
+            *acc.entry(project).or_insert(0_i64) += seconds;
+            acc
+        });
+
+    // Categories are stored at full depth (`development/code-review`); the flat maps hold the top
+    // level. Categories stored before being removed from `categories.json` are still listed.
+    let top_level = |source: &HashMap<String, i64>| {
+        source
+            .iter()
+            .fold(HashMap::new(), |mut acc, (category, seconds)| {
+                *acc.entry(category_at_depth(category, 1).to_string())
+                    .or_insert(0_i64) += seconds;
+                acc
+            })
+    };
+    let activity_top_seconds = top_level(&activity_category_seconds);
+    let domain_top_seconds = top_level(&domain_category_seconds);
+    let category_ids = definitions
+        .iter()
+        .filter(|category| !category.id.contains(CATEGORY_SEPARATOR))
+        .map(|category| category.id.clone())
+        .collect::<Vec<_>>();
+    let category_ids = category_ids
+        .iter()
+        .cloned()
+        .chain(
+            activity_top_seconds
+                .keys()
+                .chain(domain_top_seconds.keys())
+                .filter(|id| !category_ids.contains(id))
+                .cloned()
+                .collect::<BTreeSet<_>>(),
+        )
+        .collect::<Vec<_>>();
+    let category_totals = |source: &HashMap<String, i64>| {
+        category_ids
+            .iter()
+            .map(|category| {
+                let seconds = source.get(category).copied().unwrap_or_default().max(0);
+                (category.clone(), seconds as u64)
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+    let categories_seconds = category_totals(&activity_top_seconds);
+    let chrome_categories_seconds = category_totals(&domain_top_seconds);
+    let categories = minutes_by_category(&categories_seconds);
+    let chrome_categories = minutes_by_category(&chrome_categories_seconds);
+    let category_tree = category_ids
+        .iter()
+        .map(|category| category_node(category, &activity_category_seconds, definitions))
+        .filter(|node| node.seconds > 0)
+        .collect::<Vec<_>>();
+    let class_seconds = |class: CategoryClass| {
+        activity_category_seconds
+            .iter()
+            .filter(|(category, _)| category_class(definitions, category) == class)
+            .map(|(_, seconds)| (*seconds).max(0) as u64)
+            .sum::<u64>()
+    };
+    let productive_seconds = class_seconds(CategoryClass::Productive);
+    let distracting_seconds = class_seconds(CategoryClass::Distracting);
 
     let top_apps = top_n_metrics(app_seconds, 5);
-    let top_domains = top_n_metrics(domain_seconds, 10);
+    let projects = top_n_metrics(project_seconds, 10);
+    // Days with only day-level history totals (older data) cannot be reconciled.
+    let foreground_seconds = reconcile::foreground_domain_seconds(&activities, domains);
+    let web_foreground_seconds = foreground_seconds.values().sum::<i64>();
+    let top_domains = if foreground_seconds.is_empty() {
+        top_n_metrics(domain_seconds.clone(), 10)
+    } else {
+        top_n_metrics(foreground_seconds, 10)
+    };
+    let top_domains_history = top_n_metrics(domain_seconds, 10);
+    let hourly = hourly_activity(date, &activities, domains);
 
     let anomalies = detect_anomalies(
-        &categories_seconds,
+        distracting_seconds,
         &top_domains,
         activity_total_seconds.max(0) as u64,
         domain_total_seconds.max(0) as u64,
@@ -133,38 +344,340 @@ pub fn build_daily_report(
     DailyReport {
         date: date.format("%Y-%m-%d").to_string(),
         generated_at: generated_at.to_rfc3339(),
-        total_seconds: activity_total_seconds.max(0) as u64,
-        total_minutes: sec_to_min(activity_total_seconds),
+        total_seconds: (activity_total_seconds + idle_seconds).max(0) as u64,
+        total_minutes: sec_to_min(activity_total_seconds + idle_seconds),
         active_window_seconds: activity_total_seconds.max(0) as u64,
         active_window_minutes: sec_to_min(activity_total_seconds),
+        idle_seconds: idle_seconds.max(0) as u64,
+        idle_minutes: sec_to_min(idle_seconds),
         chrome_history_seconds: domain_total_seconds.max(0) as u64,
         chrome_history_minutes: sec_to_min(domain_total_seconds),
+        web_foreground_seconds: web_foreground_seconds.max(0) as u64,
+        web_foreground_minutes: sec_to_min(web_foreground_seconds),
         categories_seconds,
         categories,
         chrome_categories_seconds,
         chrome_categories,
+        category_tree,
+        category_definitions: definitions.to_vec(),
+        productive_seconds,
+        distracting_seconds,
         top_apps,
+        projects,
         top_domains,
+        top_domains_history,
+        hourly,
+        meetings: MeetingSummary::default(),
+        coding: CodingSummary::default(),
+        shell: ShellSummary::default(),
+        commits: CommitSummary::default(),
         anomalies,
     }
 }
 
-pub fn render_markdown(report: &DailyReport) -> String {
-    let productivity_seconds = report
-        .categories_seconds
-        .get("development")
-        .copied()
-        .unwrap_or_default()
-        + report
-            .categories_seconds
-            .get("research")
-            .copied()
-            .unwrap_or_default();
+/// Credits the gap between consecutive heartbeats to the earlier one's project, language and
+/// file, as WakaTime does. Gaps longer than `CODING_TIMEOUT_SECONDS` count as a break.
+pub fn coding_summary(heartbeats: &[HeartbeatRow]) -> CodingSummary {
+    let heartbeats = heartbeats
+        .iter()
+        .filter(|heartbeat| heartbeat.category.as_deref() != Some("browsing"))
+        .collect::<Vec<_>>();
+
+    let (projects, languages, files) = heartbeats.windows(2).fold(
+        (HashMap::new(), HashMap::new(), HashMap::new()),
+        |(mut projects, mut languages, mut files), pair| {
+            let (heartbeat, next) = (pair[0], pair[1]);
+            let gap = (next.time - heartbeat.time) as i64;
+            if gap > 0 && gap <= CODING_TIMEOUT_SECONDS {
+                let name =
+                    |value: &Option<String>| value.clone().unwrap_or_else(|| "Unknown".to_string());
+                *projects.entry(name(&heartbeat.project)).or_insert(0) += gap;
+                *languages.entry(name(&heartbeat.language)).or_insert(0) += gap;
+                *files.entry(heartbeat.entity.clone()).or_insert(0) += gap;
+            }
+            (projects, languages, files)
+        },
+    );
+    let total_seconds = projects.values().sum::<i64>();
+
+    CodingSummary {
+        total_seconds: total_seconds.max(0) as u64,
+        total_minutes: sec_to_min(total_seconds),
+        projects: top_n_metrics(projects, 10),
+        languages: top_n_metrics(languages, 10),
+        files: top_n_metrics(files, 10),
+    }
+}
+
+pub fn shell_summary(commands: &[ShellCommandRow]) -> ShellSummary {
+    let seconds =
+        |command: &ShellCommandRow| (command.ended_at - command.started_at).max(0.0) as i64;
+    let long_running = commands
+        .iter()
+        .filter(|command| seconds(command) >= LONG_RUNNING_COMMAND_SECONDS)
+        .collect::<Vec<_>>();
+
+    let by_directory = commands.iter().fold(
+        BTreeMap::<&str, Vec<&ShellCommandRow>>::new(),
+        |mut acc, command| {
+            acc.entry(command.project_dir.as_str())
+                .or_default()
+                .push(command);
+            acc
+        },
+    );
+    let mut projects = by_directory
+        .into_iter()
+        .map(|(directory, commands)| ShellProjectSummary {
+            directory: directory.to_string(),
+            command_count: commands.len() as u64,
+            seconds: commands.iter().map(|command| seconds(command)).sum::<i64>() as u64,
+            top_commands: command_metrics(&commands, seconds, |left, right| {
+                right.count.cmp(&left.count)
+            })
+            .into_iter()
+            .take(5)
+            .collect(),
+        })
+        .collect::<Vec<_>>();
+    projects.sort_by(|left, right| {
+        right
+            .seconds
+            .cmp(&left.seconds)
+            .then_with(|| right.command_count.cmp(&left.command_count))
+    });
+    projects.truncate(10);
+
+    ShellSummary {
+        command_count: commands.len() as u64,
+        failed_count: commands
+            .iter()
+            .filter(|command| command.exit_code != 0)
+            .count() as u64,
+        total_seconds: commands.iter().map(seconds).sum::<i64>() as u64,
+        long_running_count: long_running.len() as u64,
+        long_running_seconds: long_running
+            .iter()
+            .map(|command| seconds(command))
+            .sum::<i64>() as u64,
+        long_running: command_metrics(&long_running, seconds, |left, right| {
+            right.seconds.cmp(&left.seconds)
+        })
+        .into_iter()
+        .take(10)
+        .collect(),
+        projects,
+    }
+}
+
+/// Lists the day's commits, each linked to the session in a productive category (development, by
+/// default) it was made in.
+pub fn commit_summary(
+    commits: &[GitCommitRow],
+    sessions: &[SessionRow],
+    definitions: &[CategoryDefinition],
+) -> CommitSummary {
+    let entries = commits
+        .iter()
+        .map(|commit| CommitEntry {
+            repo: commit.repo.clone(),
+            sha: commit.sha.clone(),
+            branch: commit.branch.clone(),
+            committed_at: commit.committed_at,
+            summary: commit.summary.clone(),
+            lines_added: commit.lines_added.max(0) as u64,
+            lines_removed: commit.lines_removed.max(0) as u64,
+            files_changed: commit.files_changed.max(0) as u64,
+            session: sessions
+                .iter()
+                .filter(|session| {
+                    category_class(definitions, &session.category) == CategoryClass::Productive
+                        && session.state != "idle"
+                })
+                .filter(|session| {
+                    session.started_at <= commit.committed_at
+                        && commit.committed_at
+                            <= session.ended_at + COMMIT_SESSION_TOLERANCE_SECONDS
+                })
+                .max_by_key(|session| session.started_at)
+                .map(|session| CommitSession {
+                    app_name: session.app_name.clone(),
+                    window_title: session.window_title.clone(),
+                    started_at: session.started_at,
+                    ended_at: session.ended_at,
+                }),
+        })
+        .collect::<Vec<_>>();
+
+    CommitSummary {
+        count: entries.len() as u64,
+        lines_added: entries.iter().map(|entry| entry.lines_added).sum(),
+        lines_removed: entries.iter().map(|entry| entry.lines_removed).sum(),
+        commits: entries,
+    }
+}
+
+/// Meeting load for the day and what else was in focus during each meeting: non-idle window time
+/// in productive or distracting categories, so calls and chat (neutral) are not counted.
+pub fn meeting_summary(
+    events: &[CalendarEventRow],
+    activities: &[ActivityRow],
+    definitions: &[CategoryDefinition],
+) -> MeetingSummary {
+    let other_activities = activities
+        .iter()
+        .filter(|activity| {
+            activity.state != "idle"
+                && category_class(definitions, &activity.category) != CategoryClass::Neutral
+        })
+        .collect::<Vec<_>>();
+    let other_apps_during = |from: i64, to: i64| {
+        other_activities
+            .iter()
+            .fold(HashMap::new(), |mut acc, activity| {
+                let overlap = activity.recorded_at.min(to)
+                    - (activity.recorded_at - activity.duration_sec.max(0)).max(from);
+                if overlap > 0 {
+                    *acc.entry(activity.app_name.clone()).or_insert(0_i64) += overlap;
+                }
+                acc
+            })
+    };
+
+    let meetings = events
+        .iter()
+        .map(|event| {
+            let apps = other_apps_during(event.starts_at, event.ends_at);
+            MeetingEntry {
+                calendar: event.calendar.clone(),
+                summary: event.summary.clone(),
+                starts_at: event.starts_at,
+                ends_at: event.ends_at,
+                seconds: (event.ends_at - event.starts_at).max(0) as u64,
+                other_activity_seconds: apps.values().sum::<i64>().max(0) as u64,
+                other_apps: top_n_metrics(apps, 3),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut spans = events
+        .iter()
+        .map(|event| (event.starts_at, event.ends_at))
+        .collect::<Vec<_>>();
+    spans.sort();
+    let merged = spans
+        .into_iter()
+        .fold(Vec::<(i64, i64)>::new(), |mut acc, (from, to)| {
+            match acc.last_mut() {
+                Some(last) if from <= last.1 => last.1 = last.1.max(to),
+                _ => acc.push((from, to)),
+            }
+            acc
+        });
+    let total_seconds = merged.iter().map(|(from, to)| to - from).sum::<i64>();
+    let other_activity_seconds = merged
+        .iter()
+        .map(|(from, to)| other_apps_during(*from, *to).values().sum::<i64>())
+        .sum::<i64>();
+
+    MeetingSummary {
+        count: meetings.len() as u64,
+        total_seconds: total_seconds.max(0) as u64,
+        total_minutes: sec_to_min(total_seconds),
+        other_activity_seconds: other_activity_seconds.max(0) as u64,
+        other_activity_minutes: sec_to_min(other_activity_seconds),
+        meetings,
+    }
+}
+
+/// Groups commands by their short name, ordered by `order` and then by name.
+fn command_metrics(
+    commands: &[&ShellCommandRow],
+    seconds: impl Fn(&ShellCommandRow) -> i64,
+    order: impl Fn(&CommandMetric, &CommandMetric) -> std::cmp::Ordering,
+) -> Vec<CommandMetric> {
+    let mut metrics = commands
+        .iter()
+        .fold(BTreeMap::<&str, (u64, i64)>::new(), |mut acc, command| {
+            let entry = acc.entry(command.program.as_str()).or_default();
+            entry.0 += 1;
+            entry.1 += seconds(command);
+            acc
+        })
+        .into_iter()
+        .map(|(name, (count, seconds))| CommandMetric {
+            name: name.to_string(),
+            count,
+            seconds: seconds.max(0) as u64,
+        })
+        .collect::<Vec<_>>();
+    metrics.sort_by(|left, right| order(left, right).then_with(|| left.name.cmp(&right.name)));
+    metrics
+}
 
+/// Splits active window samples and timestamped visits across the local hours they overlap.
+/// Day-level aggregates without a visit time are left out.
+fn hourly_activity(
+    date: NaiveDate,
+    activities: &[&ActivityRow],
+    visits: &[WebVisitRow],
+) -> Vec<HourlyActivity> {
+    let boundaries = (0..=24)
+        .map(|hour| {
+            let time = date.and_time(NaiveTime::MIN) + chrono::Duration::hours(hour);
+            Local
+                .from_local_datetime(&time)
+                .earliest()
+                .map_or_else(|| time.and_utc().timestamp(), |local| local.timestamp())
+        })
+        .collect::<Vec<_>>();
+    let spread = |start: i64, seconds: i64| {
+        boundaries
+            .windows(2)
+            .map(|hour| {
+                let overlap = (start + seconds.max(0)).min(hour[1]) - start.max(hour[0]);
+                overlap.max(0) as u64
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let window = activities.iter().fold(vec![0_u64; 24], |acc, activity| {
+        let start = activity.recorded_at - activity.duration_sec.max(0);
+        add_hours(acc, spread(start, activity.duration_sec))
+    });
+    let web = visits
+        .iter()
+        .filter_map(|visit| visit.visited_at.map(|at| (at, visit.duration_sec)))
+        .fold(vec![0_u64; 24], |acc, (start, seconds)| {
+            add_hours(acc, spread(start, seconds))
+        });
+
+    window
+        .into_iter()
+        .zip(web)
+        .enumerate()
+        .filter(|(_, (window_seconds, web_seconds))| window_seconds + web_seconds > 0)
+        .map(|(hour, (window_seconds, web_seconds))| HourlyActivity {
+            hour: hour as u32,
+            window_seconds,
+            web_seconds,
+        })
+        .collect()
+}
+
+fn add_hours(mut totals: Vec<u64>, seconds: Vec<u64>) -> Vec<u64> {
+    totals
+        .iter_mut()
+        .zip(seconds)
+        .for_each(|(total, value)| *total += value);
+    totals
+}
+
+pub fn render_markdown(report: &DailyReport) -> String {
     let productivity_ratio = if report.active_window_seconds == 0 {
         0.0
     } else {
-        (productivity_seconds as f64 / report.active_window_seconds as f64) * 100.0
+        (report.productive_seconds as f64 / report.active_window_seconds as f64) * 100.0
     };
 
     let most_used_app = report
@@ -179,12 +692,12 @@ pub fn render_markdown(report: &DailyReport) -> String {
         })
         .unwrap_or_else(|| "None".to_string());
 
-    let category_rows = canonical_categories()
-        .iter()
-        .map(|category| {
+    let category_rows = ordered_categories(report)
+        .into_iter()
+        .map(|(category, name)| {
             let seconds = report
                 .categories_seconds
-                .get(*category)
+                .get(category)
                 .copied()
                 .unwrap_or_default();
             let ratio = if report.active_window_seconds == 0 {
@@ -195,7 +708,7 @@ pub fn render_markdown(report: &DailyReport) -> String {
 
             format!(
                 "| {} | {} | {:.0}% |",
-                localized_category_name(category),
+                name,
                 format_duration_seconds(seconds),
                 ratio
             )
@@ -203,8 +716,192 @@ pub fn render_markdown(report: &DailyReport) -> String {
         .collect::<Vec<_>>()
         .join("\n");
 
+    let hourly_rows = if report.hourly.is_empty() {
+        "| - | - | - |".to_string()
+    } else {
+        report
+            .hourly
+            .iter()
+            .map(|entry| {
+                format!(
+                    "| {:02}:00 | {} | {} |",
+                    entry.hour,
+                    format_duration_seconds(entry.window_seconds),
+                    format_duration_seconds(entry.web_seconds)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let coding_rows = if report.coding.total_seconds == 0 {
+        "- No editor heartbeats".to_string()
+    } else {
+        format!(
+            "- Total coding time: {}\n\n### Projects\n{}\n\n### Languages\n{}\n\n### Files\n{}",
+            format_duration_seconds(report.coding.total_seconds),
+            list_metrics(&report.coding.projects),
+            list_metrics(&report.coding.languages),
+            list_metrics(&report.coding.files)
+        )
+    };
+
+    let shell_rows = if report.shell.command_count == 0 {
+        "- No shell commands recorded".to_string()
+    } else {
+        let long_running_rows = if report.shell.long_running.is_empty() {
+            "- None".to_string()
+        } else {
+            report
+                .shell
+                .long_running
+                .iter()
+                .enumerate()
+                .map(|(index, metric)| {
+                    format!(
+                        "{}. {} - {} ({} runs)",
+                        index + 1,
+                        metric.name,
+                        format_duration_seconds(metric.seconds),
+                        metric.count
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let project_rows = report
+            .shell
+            .projects
+            .iter()
+            .map(|project| {
+                format!(
+                    "- `{}`: {} commands, {} ({})",
+                    project.directory,
+                    project.command_count,
+                    format_duration_seconds(project.seconds),
+                    project
+                        .top_commands
+                        .iter()
+                        .map(|metric| format!("{} x{}", metric.name, metric.count))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "- Commands run: {} ({} failed), {} in total\n- Long-running commands (1m or more): {}, {}\n\n### Long-Running Commands\n{}\n\n### By Project Directory\n{}",
+            report.shell.command_count,
+            report.shell.failed_count,
+            format_duration_seconds(report.shell.total_seconds),
+            report.shell.long_running_count,
+            format_duration_seconds(report.shell.long_running_seconds),
+            long_running_rows,
+            project_rows
+        )
+    };
+
+    let meeting_rows = if report.meetings.meetings.is_empty() {
+        "- No meetings".to_string()
+    } else {
+        let local_time = |timestamp: i64| {
+            Local
+                .timestamp_opt(timestamp, 0)
+                .single()
+                .map(|time| time.format("%H:%M").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let rows = report
+            .meetings
+            .meetings
+            .iter()
+            .map(|meeting| {
+                let other_activity = if meeting.other_activity_seconds == 0 {
+                    "-".to_string()
+                } else {
+                    format!(
+                        "{} ({})",
+                        format_duration_seconds(meeting.other_activity_seconds),
+                        meeting
+                            .other_apps
+                            .iter()
+                            .map(|app| app.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                };
+                format!(
+                    "| {}-{} | {} | {} | {} | {} |",
+                    local_time(meeting.starts_at),
+                    local_time(meeting.ends_at),
+                    meeting.summary.replace('|', "\\|"),
+                    meeting.calendar,
+                    format_duration_seconds(meeting.seconds),
+                    other_activity
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "- Meeting load: {} meetings, {}\n- Other activity during meetings: {}\n\n| Time | Meeting | Calendar | Duration | Other activity |\n|------|---------|----------|----------|----------------|\n{}",
+            report.meetings.count,
+            format_duration_seconds(report.meetings.total_seconds),
+            format_duration_seconds(report.meetings.other_activity_seconds),
+            rows
+        )
+    };
+
+    let commit_rows = if report.commits.commits.is_empty() {
+        "- No commits".to_string()
+    } else {
+        let local_time = |timestamp: i64| {
+            Local
+                .timestamp_opt(timestamp, 0)
+                .single()
+                .map(|time| time.format("%H:%M").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let rows = report
+            .commits
+            .commits
+            .iter()
+            .map(|commit| {
+                format!(
+                    "| {} | {} | {} | `{}` {} | +{} / -{} | {} |",
+                    local_time(commit.committed_at),
+                    commit.repo,
+                    commit.branch,
+                    commit.sha.chars().take(7).collect::<String>(),
+                    commit.summary.replace('|', "\\|"),
+                    commit.lines_added,
+                    commit.lines_removed,
+                    commit
+                        .session
+                        .as_ref()
+                        .map(|session| format!(
+                            "{} {}-{}",
+                            session.app_name,
+                            local_time(session.started_at),
+                            local_time(session.ended_at)
+                        ))
+                        .unwrap_or_else(|| "-".to_string())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "- {} commits, +{} / -{} lines\n\n| Time | Repository | Branch | Commit | Lines | Development session |\n|------|------------|--------|--------|-------|---------------------|\n{}",
+            report.commits.count, report.commits.lines_added, report.commits.lines_removed, rows
+        )
+    };
+
     let app_rows = list_metrics(&report.top_apps);
+    let project_rows = list_metrics(&report.projects);
     let domain_rows = list_metrics(&report.top_domains);
+    let history_domain_rows = list_metrics(&report.top_domains_history);
     let anomaly_rows = if report.anomalies.is_empty() {
         "- No notable anomaly detected".to_string()
     } else {
@@ -217,15 +914,28 @@ pub fn render_markdown(report: &DailyReport) -> String {
     };
 
     format!(
-        "# Daily Activity Report - {}\n\n## Summary\n- Active window tracked time: {}\n- Chrome history tracked time: {}\n- Productivity ratio (development + research): {:.0}%\n- Most used app: {}\n\n## Time by Category (Active Window Tracking)\n| Category | Time | Ratio |\n|----------|------|-------|\n{}\n\n## Top Apps (5)\n{}\n\n## Top Domains (10, Chrome History)\n{}\n\n## Anomalies\n{}\n",
+        "# Daily Activity Report - {}\n\n## Summary\n- Active window tracked time: {}\n- Idle time (away from keyboard): {}\n- Browser foreground time: {}\n- Browser history tracked time (raw, tabs may overlap): {}\n- Productivity ratio ({}): {:.0}%\n- Distracting time ({}): {}\n- Most used app: {}\n\n## Time by Category (Active Window Tracking)\n| Category | Time | Ratio |\n|----------|------|-------|\n{}\n\n## Category Tree\n{}\n\n## Top Apps (5)\n{}\n\n## Time by Project (10)\n{}\n\n## Top Domains (10, Browser Foreground Time)\n{}\n\n## Top Domains (10, Raw Browser History)\n{}\n\n## Activity by Hour\n| Hour | Active window | Browser |\n|------|---------------|---------|\n{}\n\n## Meetings\n{}\n\n## Commits\n{}\n\n## Coding by Project/Language\n{}\n\n## Terminal\n{}\n\n## Anomalies\n{}\n",
         report.date,
         format_duration_seconds(report.active_window_seconds),
+        format_duration_seconds(report.idle_seconds),
+        format_duration_seconds(report.web_foreground_seconds),
         format_duration_seconds(report.chrome_history_seconds),
+        class_names(report, CategoryClass::Productive),
         productivity_ratio,
+        class_names(report, CategoryClass::Distracting),
+        format_duration_seconds(report.distracting_seconds),
         most_used_app,
         category_rows,
+        category_tree_markdown(report),
         app_rows,
+        project_rows,
         domain_rows,
+        history_domain_rows,
+        hourly_rows,
+        meeting_rows,
+        commit_rows,
+        coding_rows,
+        shell_rows,
         anomaly_rows
     )
 }
@@ -260,27 +970,134 @@ pub fn save_report_files(report: &DailyReport, report_dir: &Path) -> Result<Save
     })
 }
 
-fn canonical_categories() -> Vec<&'static str> {
-    vec![
-        "development",
-        "research",
-        "communication",
-        "entertainment",
-        "sns",
-        "shopping",
-        "other",
-    ]
+fn minutes_by_category(seconds: &BTreeMap<String, u64>) -> BTreeMap<String, u64> {
+    seconds
+        .iter()
+        .map(|(category, seconds)| (category.clone(), sec_to_min(*seconds as i64)))
+        .collect()
+}
+
+/// Report categories in display order with their display names: the definitions, then
+/// categories in the data without one.
+fn ordered_categories(report: &DailyReport) -> Vec<(&str, &str)> {
+    report
+        .category_definitions
+        .iter()
+        .map(|category| (category.id.as_str(), category.display_name()))
+        .chain(
+            report
+                .categories_seconds
+                .keys()
+                .filter(|id| {
+                    !report
+                        .category_definitions
+                        .iter()
+                        .any(|category| &category.id == *id)
+                })
+                .map(|id| (id.as_str(), id.as_str())),
+        )
+        .collect()
 }
 
-fn localized_category_name(raw: &str) -> &'static str {
-    match raw {
-        "development" => "Development",
-        "research" => "Research",
-        "communication" => "Communication",
-        "entertainment" => "Entertainment",
-        "sns" => "SNS",
-        "shopping" => "Shopping",
-        _ => "Other",
+/// `id` with the time of every sub-category found in `seconds`, largest first.
+fn category_node(
+    id: &str,
+    seconds: &HashMap<String, i64>,
+    definitions: &[CategoryDefinition],
+) -> CategoryNode {
+    let prefix = format!("{id}{CATEGORY_SEPARATOR}");
+    let mut children = seconds
+        .keys()
+        .filter_map(|category| category.strip_prefix(&prefix))
+        .map(|rest| format!("{prefix}{}", category_at_depth(rest, 1)))
+        .collect::<BTreeSet<_>>()
+        .iter()
+        .map(|child| category_node(child, seconds, definitions))
+        .collect::<Vec<_>>();
+    children.sort_by_key(|child| Reverse(child.seconds));
+    let own_seconds = seconds.get(id).copied().unwrap_or_default().max(0) as u64;
+
+    CategoryNode {
+        id: id.to_string(),
+        name: category_name(definitions, id).to_string(),
+        seconds: own_seconds + children.iter().map(|child| child.seconds).sum::<u64>(),
+        own_seconds,
+        children,
+    }
+}
+
+/// Top-level categories with sub-categories as collapsible blocks, each level rolled up.
+fn category_tree_markdown(report: &DailyReport) -> String {
+    let total = report.active_window_seconds;
+    let blocks = report
+        .category_tree
+        .iter()
+        .filter(|node| !node.children.is_empty())
+        .map(|node| {
+            let mut lines = Vec::new();
+            push_sub_category_lines(node, 0, total, &mut lines);
+            format!(
+                "<details>\n<summary>{}: {}</summary>\n\n{}\n\n</details>",
+                node.name,
+                category_share(node.seconds, total),
+                lines.join("\n")
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if blocks.is_empty() {
+        "- No sub-categories".to_string()
+    } else {
+        blocks.join("\n\n")
+    }
+}
+
+fn push_sub_category_lines(node: &CategoryNode, depth: usize, total: u64, lines: &mut Vec<String>) {
+    if node.children.is_empty() {
+        return;
+    }
+    let indent = "  ".repeat(depth);
+    for child in &node.children {
+        lines.push(format!(
+            "{indent}- {}: {}",
+            child.name,
+            category_share(child.seconds, total)
+        ));
+        push_sub_category_lines(child, depth + 1, total, lines);
+    }
+    if node.own_seconds > 0 {
+        lines.push(format!(
+            "{indent}- {} (no sub-category): {}",
+            node.name,
+            category_share(node.own_seconds, total)
+        ));
+    }
+}
+
+fn category_share(seconds: u64, total: u64) -> String {
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        (seconds as f64 / total as f64) * 100.0
+    };
+    format!("{} ({ratio:.0}%)", format_duration_seconds(seconds))
+}
+
+/// Top-level categories of `class`, for the summary.
+fn class_names(report: &DailyReport, class: CategoryClass) -> String {
+    let names = report
+        .category_definitions
+        .iter()
+        .filter(|category| {
+            !category.id.contains(CATEGORY_SEPARATOR)
+                && category_class(&report.category_definitions, &category.id) == class
+        })
+        .map(CategoryDefinition::display_name)
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        "none defined".to_string()
+    } else {
+        names.join(" + ")
     }
 }
 
@@ -304,19 +1121,14 @@ fn top_n_metrics(source: HashMap<String, i64>, n: usize) -> Vec<ReportMetric> {
 }
 
 fn detect_anomalies(
-    categories_seconds: &BTreeMap<String, u64>,
+    distracting_seconds: u64,
     top_domains: &[ReportMetric],
     active_window_seconds: u64,
     chrome_history_seconds: u64,
 ) -> Vec<String> {
-    let entertainment_seconds = categories_seconds
-        .get("entertainment")
-        .copied()
-        .unwrap_or_default();
-
-    let entertainment_alert = (entertainment_seconds >= 90 * 60).then_some(format!(
-        "Entertainment usage is high: {}",
-        format_duration_seconds(entertainment_seconds)
+    let distracting_alert = (distracting_seconds >= 90 * 60).then_some(format!(
+        "Time in distracting categories is high: {}",
+        format_duration_seconds(distracting_seconds)
     ));
 
     let youtube_alert = top_domains
@@ -334,11 +1146,11 @@ fn detect_anomalies(
 
     let overlap_hint =
         (chrome_history_seconds > active_window_seconds && chrome_history_seconds > 0).then_some(
-            "Chrome history durations can overlap across tabs, so web time may exceed active window time".to_string(),
+            "Raw browser history durations overlap across tabs; domain rankings use foreground time instead".to_string(),
         );
 
     [
-        entertainment_alert,
+        distracting_alert,
         youtube_alert,
         low_activity_alert,
         overlap_hint,
@@ -373,7 +1185,7 @@ fn sec_to_min(seconds: i64) -> u64 {
     safe_seconds / 60
 }
 
-fn format_duration_seconds(seconds: u64) -> String {
+pub fn format_duration_seconds(seconds: u64) -> String {
     let hours = seconds / 3600;
     let minutes = (seconds % 3600) / 60;
     let remain_seconds = seconds % 60;
@@ -394,3 +1206,397 @@ fn format_duration_seconds(seconds: u64) -> String {
         format!("{remain_seconds}s")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        HourlyActivity, build_daily_report, commit_summary, meeting_summary, render_markdown,
+    };
+    use crate::analyzer::categorizer::{CategoryRules, LintKind};
+    use crate::db::{ActivityRow, CalendarEventRow, GitCommitRow, SessionRow, WebVisitRow};
+    use chrono::{Local, NaiveDate, TimeZone};
+    use std::path::PathBuf;
+
+    fn default_categories_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/categories.json")
+    }
+
+    #[test]
+    fn rolls_sub_categories_up_to_their_parents() {
+        let rules = serde_json::from_str::<CategoryRules>(
+            r#"{
+                "categories": [
+                    {"id": "development", "name": "Development", "class": "productive"},
+                    {"id": "development/docs", "name": "Docs", "class": "neutral"},
+                    {"id": "research", "class": "productive"}
+                ],
+                "apps": {},
+                "domains": {}
+            }"#,
+        )
+        .expect("rules");
+        rules.validate().expect("valid");
+        assert_eq!(
+            rules.category_id(" Development/CI /").as_deref(),
+            Some("development/ci")
+        );
+        assert_eq!(rules.category_id("design/figma"), None);
+
+        let activity = |id, category: &str, duration_sec| ActivityRow {
+            id,
+            recorded_at: 0,
+            app_name: "Code".to_string(),
+            window_title: None,
+            category: category.to_string(),
+            project: None,
+            duration_sec,
+            state: "active".to_string(),
+            source: "window".to_string(),
+            external_id: None,
+            metadata: None,
+        };
+        let report = build_daily_report(
+            NaiveDate::from_ymd_opt(2026, 2, 18).expect("date"),
+            &[
+                activity(1, "development", 600),
+                activity(2, "development/code-review", 1_200),
+                activity(3, "development/code-review/github", 600),
+                activity(4, "development/docs", 600),
+                activity(5, "research", 600),
+            ],
+            &[],
+            rules.definitions(),
+        );
+
+        assert_eq!(report.categories_seconds["development"], 3_000);
+        assert_eq!(report.productive_seconds, 3_000);
+        let development = &report.category_tree[0];
+        assert_eq!((development.seconds, development.own_seconds), (3_000, 600));
+        let children = development
+            .children
+            .iter()
+            .map(|child| (child.name.as_str(), child.seconds))
+            .collect::<Vec<_>>();
+        assert_eq!(children, vec![("code-review", 1_800), ("Docs", 600)]);
+        assert_eq!(
+            development.children[0].children[0].id,
+            "development/code-review/github"
+        );
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains(
+            "<details>\n<summary>Development: 50m (83%)</summary>\n\n- code-review: 30m (50%)\n  - github: 10m (17%)\n  - code-review (no sub-category): 20m (33%)\n- Docs: 10m (17%)\n- Development (no sub-category): 10m (17%)\n\n</details>"
+        ));
+        assert!(!markdown.contains("<summary>research"));
+    }
+
+    #[test]
+    fn reports_user_defined_categories_and_classes() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("categories.json");
+        std::fs::write(
+            &path,
+            r##"{
+                "categories": [
+                    {"id": "Design", "name": "UI Design", "color": "#6b5bd2", "class": "productive"},
+                    {"id": "meetings", "name": "Meetings", "aliases": ["calls"]},
+                    {"id": "gaming", "name": "Games", "class": "distracting"}
+                ],
+                "apps": {"figma": "design", "zoom": "calls", "steam": "gaming"},
+                "domains": {}
+            }"##,
+        )
+        .expect("write categories");
+        let rules = CategoryRules::load(&path).expect("rules");
+        assert_eq!(rules.categorize_app("Zoom", None), "meetings");
+        assert_eq!(rules.definitions().last().expect("other").id, "other");
+
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+        let activity = |id, app: &str, category: &str, duration_sec| ActivityRow {
+            id,
+            recorded_at: 0,
+            app_name: app.to_string(),
+            window_title: None,
+            category: category.to_string(),
+            project: None,
+            duration_sec,
+            state: "active".to_string(),
+            source: "window".to_string(),
+            external_id: None,
+            metadata: None,
+        };
+        let report = build_daily_report(
+            date,
+            &[
+                activity(1, "Figma", "design", 3_000),
+                activity(2, "Steam", "gaming", 600),
+                activity(3, "YouTube", "entertainment", 600),
+                activity(4, "Zoom", "meetings", 1_800),
+            ],
+            &[],
+            rules.definitions(),
+        );
+
+        assert_eq!(
+            report.categories_seconds.keys().collect::<Vec<_>>(),
+            vec!["design", "entertainment", "gaming", "meetings", "other"]
+        );
+        assert_eq!(report.productive_seconds, 3_000);
+        assert_eq!(report.distracting_seconds, 600);
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("- Productivity ratio (UI Design): 50%"));
+        assert!(markdown.contains("- Distracting time (Games): 10m"));
+        assert!(markdown.contains("| UI Design | 50m | 50% |\n| Meetings |"));
+        assert!(markdown.contains("| Other | 0s | 0% |\n| entertainment | 10m | 10% |"));
+
+        std::fs::write(
+            &path,
+            r#"{"categories": [{"id": "design"}], "apps": {"figma": "desing"}, "domains": {}}"#,
+        )
+        .expect("write categories");
+        assert!(CategoryRules::load(&path).is_err());
+
+        // Without declared categories, unknown names still load and count as `other`.
+        std::fs::write(&path, r#"{"apps": {"figma": "design"}, "domains": {}}"#)
+            .expect("write categories");
+        let rules = CategoryRules::load(&path).expect("undeclared rules");
+        assert_eq!(rules.categorize_app("Figma", None), "other");
+        let issues = rules
+            .lint()
+            .into_iter()
+            .map(|issue| (issue.kind, issue.rule))
+            .collect::<Vec<_>>();
+        assert_eq!(issues, vec![(LintKind::Unknown, "apps.figma".to_string())]);
+    }
+
+    #[test]
+    fn splits_window_and_web_time_by_hour() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+        let at = |hour, minute| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(hour, minute, 0).expect("time"))
+                .single()
+                .expect("local time")
+                .timestamp()
+        };
+        let activity = ActivityRow {
+            id: 1,
+            recorded_at: at(10, 10),
+            app_name: "Code".to_string(),
+            window_title: None,
+            category: "development".to_string(),
+            project: None,
+            duration_sec: 1_200,
+            state: "active".to_string(),
+            source: "window".to_string(),
+            external_id: None,
+            metadata: None,
+        };
+        let visit = |id, visited_at, duration_sec| WebVisitRow {
+            id,
+            date: "2026-02-18".to_string(),
+            visited_at,
+            browser: "chrome".to_string(),
+            domain: "github.com".to_string(),
+            url: None,
+            transition: None,
+            category: "development".to_string(),
+            duration_sec,
+        };
+
+        let report = build_daily_report(
+            date,
+            &[activity],
+            &[visit(1, Some(at(9, 55)), 600), visit(2, None, 300)],
+            CategoryRules::load(&default_categories_path())
+                .expect("rules")
+                .definitions(),
+        );
+
+        assert_eq!(report.chrome_history_seconds, 900);
+        assert_eq!(report.top_domains_history[0].seconds, 900);
+        assert_eq!(
+            report.hourly,
+            vec![
+                HourlyActivity {
+                    hour: 9,
+                    window_seconds: 600,
+                    web_seconds: 300,
+                },
+                HourlyActivity {
+                    hour: 10,
+                    window_seconds: 600,
+                    web_seconds: 300,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_external_spans_only_where_no_window_sample_was_taken() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+        let at = |hour, minute| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(hour, minute, 0).expect("time"))
+                .single()
+                .expect("local time")
+                .timestamp()
+        };
+        let activity = |id, source: &str, app: &str, recorded_at, duration_sec| ActivityRow {
+            id,
+            recorded_at,
+            app_name: app.to_string(),
+            window_title: None,
+            category: "development".to_string(),
+            project: None,
+            duration_sec,
+            state: "active".to_string(),
+            source: source.to_string(),
+            external_id: None,
+            metadata: None,
+        };
+
+        // The editor plugin reports 09:50-10:20; the collector sampled 10:00-10:10 itself.
+        let report = build_daily_report(
+            date,
+            &[
+                activity(1, "window", "Code", at(10, 10), 600),
+                activity(2, "vscode", "Visual Studio Code", at(10, 20), 1_800),
+            ],
+            &[],
+            CategoryRules::load(&default_categories_path())
+                .expect("rules")
+                .definitions(),
+        );
+
+        assert_eq!(report.active_window_seconds, 1_800);
+        assert_eq!(report.categories_seconds["development"], 1_800);
+        assert_eq!(report.top_apps[0].name, "Visual Studio Code");
+        assert_eq!(report.top_apps[0].seconds, 1_200);
+        assert_eq!(
+            report.hourly,
+            vec![
+                HourlyActivity {
+                    hour: 9,
+                    window_seconds: 600,
+                    web_seconds: 0,
+                },
+                HourlyActivity {
+                    hour: 10,
+                    window_seconds: 1_200,
+                    web_seconds: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn links_commits_to_the_development_session_they_were_made_in() {
+        let session = |id, app: &str, category: &str, started_at, ended_at| SessionRow {
+            id,
+            app_name: app.to_string(),
+            window_title: None,
+            category: category.to_string(),
+            state: "active".to_string(),
+            started_at,
+            ended_at,
+            duration_sec: ended_at - started_at,
+            sample_count: 1,
+        };
+        let commit = |id, committed_at| GitCommitRow {
+            id,
+            repo: "opentracker".to_string(),
+            sha: format!("{id:040}"),
+            branch: "main".to_string(),
+            author_email: "dev@example.com".to_string(),
+            committed_at,
+            summary: "Fix parser".to_string(),
+            lines_added: 10,
+            lines_removed: 4,
+            files_changed: 2,
+        };
+        let sessions = [
+            session(1, "Code", "development", 1_000, 2_000),
+            session(2, "Slack", "communication", 2_000, 3_000),
+            session(3, "Code", "development", 5_000, 6_000),
+        ];
+
+        let summary = commit_summary(
+            &[commit(1, 1_500), commit(2, 2_200), commit(3, 4_000)],
+            &sessions,
+            CategoryRules::load(&default_categories_path())
+                .expect("rules")
+                .definitions(),
+        );
+
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.lines_added, 30);
+        let linked = summary
+            .commits
+            .iter()
+            .map(|entry| entry.session.as_ref().map(|session| session.started_at))
+            .collect::<Vec<_>>();
+        // The second commit lands within the tolerance after the first session ended.
+        assert_eq!(linked, vec![Some(1_000), Some(1_000), None]);
+    }
+
+    #[test]
+    fn measures_meeting_load_and_other_activity_during_meetings() {
+        let event = |id, summary: &str, starts_at, ends_at| CalendarEventRow {
+            id,
+            calendar: "work".to_string(),
+            uid: format!("event-{id}"),
+            summary: summary.to_string(),
+            location: None,
+            starts_at,
+            ends_at,
+        };
+        let activity = |id, app: &str, category: &str, recorded_at| ActivityRow {
+            id,
+            recorded_at,
+            app_name: app.to_string(),
+            window_title: None,
+            category: category.to_string(),
+            project: None,
+            duration_sec: 300,
+            state: "active".to_string(),
+            source: "window".to_string(),
+            external_id: None,
+            metadata: None,
+        };
+
+        // A team's own categories: only their classes matter.
+        let rules = serde_json::from_str::<CategoryRules>(
+            r#"{
+                "categories": [
+                    {"id": "coding", "class": "productive"},
+                    {"id": "meetings", "class": "neutral"}
+                ],
+                "apps": {},
+                "domains": {}
+            }"#,
+        )
+        .expect("rules");
+
+        let summary = meeting_summary(
+            &[
+                event(1, "Standup", 1_000, 2_800),
+                event(2, "Planning", 2_200, 4_000),
+            ],
+            &[
+                activity(1, "Zoom", "meetings", 1_600),
+                activity(2, "Code", "coding", 2_500),
+                activity(3, "Code", "coding", 4_300),
+            ],
+            rules.definitions(),
+        );
+
+        assert_eq!(summary.count, 2);
+        // Overlapping meetings are counted once.
+        assert_eq!(summary.total_seconds, 3_000);
+        assert_eq!(summary.other_activity_seconds, 300);
+        assert_eq!(summary.meetings[0].other_activity_seconds, 300);
+        assert_eq!(summary.meetings[0].other_apps[0].name, "Code");
+        assert_eq!(summary.meetings[1].other_activity_seconds, 300);
+    }
+}
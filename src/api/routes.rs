@@ -1,18 +1,28 @@
-use crate::analyzer::categorizer::CategoryRules;
+use crate::analyzer;
+use crate::analyzer::categorizer::{CategoryRules, Explanation, Target, category_at_depth};
+use crate::analyzer::recategorize::{self, RecategorizeSummary};
+use crate::analyzer::reconcile;
+use crate::analyzer::report;
 use crate::api::get_embedded_asset;
+use crate::collector::chrome::extract_domain;
+use crate::collector::extension::{self, BrowserEventBatch, IngestSummary};
+use crate::collector::external::{self, ExternalPayload, IngestResult};
+use crate::collector::shell::{self, ShellCommandReport};
+use crate::collector::wakatime::{self, Heartbeat};
 use crate::config::Config;
 use crate::daemon;
-use crate::db::{ActivityRow, Database};
+use crate::db::{ActivityRow, Database, SessionRow, WebVisitRow};
 use crate::scheduler;
 use anyhow::{Context, Result};
 use axum::extract::{Path, Query, State};
 use axum::http::{HeaderValue, StatusCode, Uri, header};
 use axum::response::{IntoResponse, Response};
-use axum::routing::get;
-use axum::{Json, Router};
+use axum::routing::{get, post};
+use axum::{Form, Json, Router};
 use chrono::{Duration, Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path as FsPath;
 use std::sync::Arc;
@@ -37,7 +47,20 @@ pub fn router(state: ApiState) -> Router {
             "/api/v1/report/:date/download/json",
             get(report_download_json),
         )
-        .route("/api/v1/activities", get(activities))
+        .route("/api/v1/activities", get(activities).post(activities_post))
+        .route("/api/v1/sessions", get(sessions))
+        .route("/api/v1/ingest/browser", post(ingest_browser))
+        .route("/api/v1/ingest/shell", post(ingest_shell))
+        // WakaTime-compatible endpoints; editor plugins use `api_url = http://<host>/api/v1`.
+        .route("/api/v1/users/current/heartbeats", post(heartbeat_post))
+        .route(
+            "/api/v1/users/current/heartbeats.bulk",
+            post(heartbeats_bulk_post),
+        )
+        .route(
+            "/api/v1/users/current/statusbar/today",
+            get(statusbar_today),
+        )
         .route(
             "/api/v1/settings/report-schedule",
             get(report_schedule_get).put(report_schedule_put),
@@ -46,6 +69,11 @@ pub fn router(state: ApiState) -> Router {
             "/api/v1/categories",
             get(categories_get).put(categories_put),
         )
+        .route("/api/v1/categories/explain", post(categories_explain))
+        .route(
+            "/api/v1/categories/recategorize",
+            post(categories_recategorize),
+        )
         .fallback(get(static_assets))
         .with_state(state)
 }
@@ -54,6 +82,28 @@ pub fn router(state: ApiState) -> Router {
 struct ActivitiesQuery {
     from: Option<String>,
     to: Option<String>,
+    /// Category levels to aggregate to (`1` for `development`, not `development/code-review`).
+    depth: Option<usize>,
+}
+
+/// A window (`app`, `title`) or browser visit (`domain`, `url`) to categorize.
+#[derive(Debug, Deserialize)]
+struct ExplainPayload {
+    app: Option<String>,
+    title: Option<String>,
+    domain: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecategorizePayload {
+    from: String,
+    /// Last date to update; defaults to today, like the CLI's `--to`.
+    to: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    regenerate_reports: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -67,6 +117,18 @@ struct ActivitiesPayload {
     to: String,
     count: usize,
     activities: Vec<ActivityRow>,
+    web_visits: Vec<WebVisitRow>,
+    categories_seconds: BTreeMap<String, i64>,
+    web_categories_seconds: BTreeMap<String, i64>,
+    projects_seconds: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct SessionsPayload {
+    from: String,
+    to: String,
+    count: usize,
+    sessions: Vec<SessionRow>,
 }
 
 #[derive(Debug, Serialize)]
@@ -252,50 +314,203 @@ async fn activities(
     State(state): State<ApiState>,
     Query(query): Query<ActivitiesQuery>,
 ) -> ApiResult<Json<ActivitiesPayload>> {
-    let from_date = query
-        .from
-        .as_deref()
-        .map(parse_date)
-        .transpose()?
-        .unwrap_or_else(|| Local::now().date_naive());
-
-    let to_date = query
-        .to
-        .as_deref()
-        .map(parse_date)
-        .transpose()?
-        .unwrap_or(from_date);
-
-    let from_ts = from_date
-        .and_hms_opt(0, 0, 0)
-        .context("Failed to create from timestamp")?
-        .and_local_timezone(Local)
-        .single()
-        .context("Failed to convert from timestamp to local time")?
-        .timestamp();
-
-    let to_ts = (to_date + Duration::days(1))
-        .and_hms_opt(0, 0, 0)
-        .context("Failed to create to timestamp")?
-        .and_local_timezone(Local)
-        .single()
-        .context("Failed to convert to timestamp to local time")?
-        .timestamp()
-        - 1;
+    let (from_date, to_date) = parse_date_range(&query)?;
+    let (from_ts, to_ts) = date_range_bounds(from_date, to_date)?;
+    if query.depth == Some(0) {
+        return Err(ApiError::BadRequest("depth must be at least 1".to_string()));
+    }
+    let at_depth = |category: &str| {
+        query
+            .depth
+            .map_or(category, |depth| category_at_depth(category, depth))
+            .to_string()
+    };
 
     let database = Database::open(&state.config.db_path)?;
     let records = database.activities_between(from_ts, to_ts)?;
+    let web_visits = database.web_visits_between(from_date, to_date)?;
+    let foreground = reconcile::foreground_activities(&records);
+    let categories_seconds = foreground
+        .iter()
+        .filter(|activity| activity.state != "idle")
+        .fold(BTreeMap::new(), |mut acc, activity| {
+            *acc.entry(at_depth(&activity.category)).or_insert(0) += activity.duration_sec.max(0);
+            acc
+        });
+    let web_categories_seconds = web_visits.iter().fold(BTreeMap::new(), |mut acc, visit| {
+        *acc.entry(at_depth(&visit.category)).or_insert(0) += visit.duration_sec.max(0);
+        acc
+    });
+    let projects_seconds = foreground
+        .iter()
+        .filter(|activity| activity.state != "idle")
+        .filter_map(|activity| Some((activity.project.clone()?, activity.duration_sec.max(0))))
+        .fold(BTreeMap::new(), |mut acc, (project, seconds)| {
+            *acc.entry(project).or_insert(0) += seconds;
+            acc
+        });
 
     let payload = ActivitiesPayload {
         from: from_date.format("%Y-%m-%d").to_string(),
         to: to_date.format("%Y-%m-%d").to_string(),
         count: records.len(),
         activities: records,
+        web_visits,
+        categories_seconds,
+        web_categories_seconds,
+        projects_seconds,
     };
 
     Ok(Json(payload))
 }
 
+#[derive(Debug, Serialize)]
+struct ActivitiesIngestPayload {
+    created: usize,
+    duplicates: usize,
+    results: Vec<external::SpanResult>,
+}
+
+async fn activities_post(
+    State(state): State<ApiState>,
+    Json(payload): Json<Value>,
+) -> ApiResult<Json<ActivitiesIngestPayload>> {
+    let spans = serde_json::from_value::<ExternalPayload>(payload)
+        .map_err(|error| ApiError::BadRequest(format!("Invalid activity payload: {error}")))?
+        .into_spans();
+    let rules = CategoryRules::load(&state.config.categories_path)?;
+    let inputs = external::prepare_spans(&spans, &rules)
+        .map_err(|error| ApiError::BadRequest(error.to_string()))?;
+
+    let mut database = Database::open(&state.config.db_path)?;
+    match external::store_spans(&mut database, &spans, &inputs)? {
+        IngestResult::Stored(results) => Ok(Json(ActivitiesIngestPayload {
+            created: results
+                .iter()
+                .filter(|result| result.status == "created")
+                .count(),
+            duplicates: results
+                .iter()
+                .filter(|result| result.status == "duplicate")
+                .count(),
+            results,
+        })),
+        IngestResult::Conflicts(conflicts) => Err(ApiError::Conflict(format!(
+            "Spans overlap existing activities from the same source; nothing was stored: {}",
+            conflicts
+                .iter()
+                .map(|conflict| format!(
+                    "{} (overlaps activity {})",
+                    conflict.id, conflict.activity_id
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+async fn sessions(
+    State(state): State<ApiState>,
+    Query(query): Query<ActivitiesQuery>,
+) -> ApiResult<Json<SessionsPayload>> {
+    let (from_date, to_date) = parse_date_range(&query)?;
+    let (from_ts, to_ts) = date_range_bounds(from_date, to_date)?;
+
+    let database = Database::open(&state.config.db_path)?;
+    let records = database.sessions_between(from_ts, to_ts)?;
+
+    Ok(Json(SessionsPayload {
+        from: from_date.format("%Y-%m-%d").to_string(),
+        to: to_date.format("%Y-%m-%d").to_string(),
+        count: records.len(),
+        sessions: records,
+    }))
+}
+
+async fn ingest_browser(
+    State(state): State<ApiState>,
+    Json(payload): Json<Value>,
+) -> ApiResult<Json<IngestSummary>> {
+    let batch = serde_json::from_value::<BrowserEventBatch>(payload)
+        .map_err(|error| ApiError::BadRequest(format!("Invalid browser event batch: {error}")))?;
+    extension::validate_batch(&batch).map_err(|error| ApiError::BadRequest(error.to_string()))?;
+
+    let rules = CategoryRules::load(&state.config.categories_path)?;
+    let mut database = Database::open(&state.config.db_path)?;
+    let summary = extension::ingest_events(&mut database, &rules, &batch)?;
+
+    Ok(Json(summary))
+}
+
+async fn ingest_shell(
+    State(state): State<ApiState>,
+    Form(report): Form<ShellCommandReport>,
+) -> ApiResult<Json<Value>> {
+    shell::validate_report(&report).map_err(|error| ApiError::BadRequest(error.to_string()))?;
+
+    let database = Database::open(&state.config.db_path)?;
+    let id = shell::store_command(&database, &report)?;
+
+    Ok(Json(json!({ "id": id })))
+}
+
+async fn heartbeat_post(
+    State(state): State<ApiState>,
+    Json(payload): Json<Value>,
+) -> ApiResult<(StatusCode, Json<Value>)> {
+    let heartbeat = serde_json::from_value::<Heartbeat>(payload)
+        .map_err(|error| ApiError::BadRequest(format!("Invalid heartbeat: {error}")))?;
+    let heartbeats = vec![heartbeat];
+    wakatime::validate_heartbeats(&heartbeats)
+        .map_err(|error| ApiError::BadRequest(error.to_string()))?;
+
+    let mut database = Database::open(&state.config.db_path)?;
+    wakatime::store_heartbeats(&mut database, &heartbeats)?;
+
+    Ok((StatusCode::CREATED, Json(json!({ "data": heartbeats[0] }))))
+}
+
+/// Answers in WakaTime's bulk shape: one `[body, status]` pair per heartbeat. Resent heartbeats
+/// are acknowledged like new ones so plugins drop them from their offline queue.
+async fn heartbeats_bulk_post(
+    State(state): State<ApiState>,
+    Json(payload): Json<Value>,
+) -> ApiResult<(StatusCode, Json<Value>)> {
+    let heartbeats = serde_json::from_value::<Vec<Heartbeat>>(payload)
+        .map_err(|error| ApiError::BadRequest(format!("Invalid heartbeats: {error}")))?;
+    wakatime::validate_heartbeats(&heartbeats)
+        .map_err(|error| ApiError::BadRequest(error.to_string()))?;
+
+    let mut database = Database::open(&state.config.db_path)?;
+    wakatime::store_heartbeats(&mut database, &heartbeats)?;
+
+    let responses = heartbeats
+        .iter()
+        .map(|heartbeat| json!([{ "data": heartbeat }, StatusCode::CREATED.as_u16()]))
+        .collect::<Vec<_>>();
+
+    Ok((StatusCode::CREATED, Json(json!({ "responses": responses }))))
+}
+
+async fn statusbar_today(State(state): State<ApiState>) -> ApiResult<Json<Value>> {
+    let database = Database::open(&state.config.db_path)?;
+    let heartbeats = database.heartbeats_for_date(Local::now().date_naive())?;
+    let coding = report::coding_summary(&heartbeats);
+    let (hours, minutes) = (coding.total_minutes / 60, coding.total_minutes % 60);
+
+    Ok(Json(json!({
+        "data": {
+            "grand_total": {
+                "total_seconds": coding.total_seconds,
+                "hours": hours,
+                "minutes": minutes,
+                "digital": format!("{hours}:{minutes:02}"),
+                "text": format!("{hours} hrs {minutes} mins"),
+            }
+        }
+    })))
+}
+
 async fn report_schedule_get(
     State(state): State<ApiState>,
 ) -> ApiResult<Json<ReportSchedulePayload>> {
@@ -336,8 +551,19 @@ async fn categories_get(State(state): State<ApiState>) -> ApiResult<Json<Value>>
             state.config.categories_path.display()
         )
     })?;
-    let categories: Value =
+    let mut categories: Value =
         serde_json::from_str(&content).context("Failed to parse categories JSON")?;
+    // Files without their own definitions use the built-in ones; list them for the dashboard.
+    if let Some(object) = categories.as_object_mut()
+        && !object.contains_key("categories")
+    {
+        let rules = CategoryRules::load(&state.config.categories_path)?;
+        object.insert(
+            "categories".to_string(),
+            serde_json::to_value(rules.definitions())
+                .context("Failed to serialize category definitions")?,
+        );
+    }
 
     Ok(Json(categories))
 }
@@ -347,7 +573,9 @@ async fn categories_put(
     Json(payload): Json<Value>,
 ) -> ApiResult<Json<Value>> {
     serde_json::from_value::<CategoryRules>(payload.clone())
-        .map_err(|error| ApiError::BadRequest(format!("Invalid categories schema: {error}")))?;
+        .map_err(|error| ApiError::BadRequest(format!("Invalid categories schema: {error}")))?
+        .validate()
+        .map_err(|error| ApiError::BadRequest(error.to_string()))?;
 
     let pretty =
         serde_json::to_string_pretty(&payload).context("Failed to serialize categories JSON")?;
@@ -364,6 +592,59 @@ async fn categories_put(
     })))
 }
 
+async fn categories_explain(
+    State(state): State<ApiState>,
+    Json(payload): Json<ExplainPayload>,
+) -> ApiResult<Json<Explanation>> {
+    let domain = payload
+        .domain
+        .or_else(|| payload.url.as_deref().and_then(extract_domain));
+    if payload.app.is_none() && domain.is_none() {
+        return Err(ApiError::BadRequest(
+            "Either `app` or `domain`/`url` is required".to_string(),
+        ));
+    }
+
+    let rules = CategoryRules::load(&state.config.categories_path)?;
+    Ok(Json(rules.explain(&Target {
+        app: payload.app.as_deref(),
+        title: payload.title.as_deref(),
+        domain: domain.as_deref(),
+        url: payload.url.as_deref(),
+    })))
+}
+
+async fn categories_recategorize(
+    State(state): State<ApiState>,
+    Json(payload): Json<RecategorizePayload>,
+) -> ApiResult<Json<RecategorizeSummary>> {
+    let bad_request = |error: anyhow::Error| ApiError::BadRequest(error.to_string());
+    let from = parse_date(&payload.from).map_err(bad_request)?;
+    let to = payload
+        .to
+        .as_deref()
+        .map(parse_date)
+        .transpose()
+        .map_err(bad_request)?
+        .unwrap_or_else(|| Local::now().date_naive());
+    if from > to {
+        return Err(ApiError::BadRequest(format!(
+            "`from` ({from}) is after `to` ({to})"
+        )));
+    }
+
+    let rules = CategoryRules::load(&state.config.categories_path)?;
+    let mut database = Database::open(&state.config.db_path)?;
+    let summary = recategorize::recategorize(&mut database, &rules, from, to, payload.dry_run)?;
+
+    if payload.regenerate_reports && !payload.dry_run {
+        for date in &summary.dates {
+            analyzer::regenerate_report(&state.config, &rules, *date)?;
+        }
+    }
+    Ok(Json(summary))
+}
+
 async fn static_assets(uri: Uri) -> ApiResult<Response> {
     let path = uri.path();
 
@@ -379,6 +660,45 @@ async fn static_assets(uri: Uri) -> ApiResult<Response> {
     }
 }
 
+fn parse_date_range(query: &ActivitiesQuery) -> Result<(NaiveDate, NaiveDate)> {
+    let from_date = query
+        .from
+        .as_deref()
+        .map(parse_date)
+        .transpose()?
+        .unwrap_or_else(|| Local::now().date_naive());
+
+    let to_date = query
+        .to
+        .as_deref()
+        .map(parse_date)
+        .transpose()?
+        .unwrap_or(from_date);
+
+    Ok((from_date, to_date))
+}
+
+fn date_range_bounds(from_date: NaiveDate, to_date: NaiveDate) -> Result<(i64, i64)> {
+    let from_ts = from_date
+        .and_hms_opt(0, 0, 0)
+        .context("Failed to create from timestamp")?
+        .and_local_timezone(Local)
+        .single()
+        .context("Failed to convert from timestamp to local time")?
+        .timestamp();
+
+    let to_ts = (to_date + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .context("Failed to create to timestamp")?
+        .and_local_timezone(Local)
+        .single()
+        .context("Failed to convert to timestamp to local time")?
+        .timestamp()
+        - 1;
+
+    Ok((from_ts, to_ts))
+}
+
 fn parse_date(input: &str) -> Result<NaiveDate> {
     NaiveDate::parse_from_str(input, "%Y-%m-%d")
         .with_context(|| format!("Invalid date format: {input}. Example: 2026-02-18"))
@@ -400,6 +720,7 @@ type ApiResult<T> = std::result::Result<T, ApiError>;
 enum ApiError {
     BadRequest(String),
     NotFound(String),
+    Conflict(String),
     Internal(anyhow::Error),
 }
 
@@ -424,6 +745,9 @@ impl IntoResponse for ApiError {
             ApiError::NotFound(message) => {
                 (StatusCode::NOT_FOUND, Json(json!({ "error": message }))).into_response()
             }
+            ApiError::Conflict(message) => {
+                (StatusCode::CONFLICT, Json(json!({ "error": message }))).into_response()
+            }
             ApiError::Internal(error) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({ "error": error.to_string() })),
@@ -432,3 +756,401 @@ impl IntoResponse for ApiError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiState, router};
+    use crate::analyzer::report;
+    use crate::config::Config;
+    use crate::db::Database;
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode};
+    use chrono::{Local, NaiveDate, TimeZone};
+    use serde_json::{Value, json};
+    use std::fs;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    async fn get_json(state: &ApiState, uri: &str) -> (StatusCode, Value) {
+        let response = router(state.clone())
+            .oneshot(Request::get(uri).body(Body::empty()).expect("request"))
+            .await
+            .expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+
+        (status, serde_json::from_slice(&bytes).expect("json body"))
+    }
+
+    async fn post_json(state: &ApiState, uri: &str, body: Value) -> (StatusCode, Value) {
+        let response = router(state.clone())
+            .oneshot(
+                Request::post(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .expect("request"),
+            )
+            .await
+            .expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+
+        (status, serde_json::from_slice(&bytes).expect("json body"))
+    }
+
+    #[tokio::test]
+    async fn browser_events_become_deduplicated_visits() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let categories_path = dir.path().join("categories.json");
+        fs::write(
+            &categories_path,
+            r#"{"apps":{},"domains":{"github.com":"development"}}"#,
+        )
+        .expect("categories");
+        let state = ApiState {
+            config: Arc::new(Config {
+                db_path: dir.path().join("activity.db"),
+                categories_path,
+                ..Config::default()
+            }),
+        };
+
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+        let at = |hour, minute| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(hour, minute, 0).expect("time"))
+                .single()
+                .expect("local time")
+                .timestamp_millis()
+        };
+        let batch = json!({
+            "events": [
+                {"id": "e1", "type": "tab_focus", "timestamp_ms": at(9, 0), "tab_id": 7, "url": "https://github.com/rust-lang"},
+                {"id": "e2", "type": "url_change", "timestamp_ms": at(9, 10), "tab_id": 7, "url": "https://www.youtube.com/watch"},
+                {"id": "e3", "type": "window_blur", "timestamp_ms": at(9, 15)}
+            ]
+        });
+
+        let (status, summary) = post_json(&state, "/api/v1/ingest/browser", batch.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(summary["accepted"], 3);
+        assert_eq!(summary["visits"], 2);
+
+        let (_, summary) = post_json(&state, "/api/v1/ingest/browser", batch).await;
+        assert_eq!(summary["accepted"], 0);
+        assert_eq!(summary["duplicates"], 3);
+
+        let (status, error) = post_json(
+            &state,
+            "/api/v1/ingest/browser",
+            json!({"events": [{"type": "tab_focus", "timestamp_ms": at(9, 20)}]}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            error["error"]
+                .as_str()
+                .expect("message")
+                .contains("events[0]")
+        );
+
+        let visits = Database::open(&state.config.db_path)
+            .expect("open db")
+            .web_visits_between(date, date)
+            .expect("visits");
+        let spans = visits
+            .iter()
+            .map(|visit| {
+                (
+                    visit.domain.as_str(),
+                    visit.category.as_str(),
+                    visit.duration_sec,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                ("github.com", "development", 600),
+                ("youtube.com", "other", 300)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn external_activities_are_idempotent_and_reject_overlaps() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let categories_path = dir.path().join("categories.json");
+        fs::write(
+            &categories_path,
+            r#"{"apps":{"Code":"development"},"domains":{}}"#,
+        )
+        .expect("categories");
+        let state = ApiState {
+            config: Arc::new(Config {
+                db_path: dir.path().join("activity.db"),
+                categories_path,
+                ..Config::default()
+            }),
+        };
+
+        let span = json!({
+            "id": "vscode-1",
+            "source": "vscode",
+            "app": "Code",
+            "title": "main.rs",
+            "start": "2026-02-18T09:00:00Z",
+            "end": 1_771_405_800,
+            "metadata": {"project": "opentracker"}
+        });
+
+        let (status, body) = post_json(&state, "/api/v1/activities", span.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["created"], 1);
+        let activity_id = body["results"][0]["activity_id"].clone();
+
+        let (status, body) =
+            post_json(&state, "/api/v1/activities", json!({"activities": [span]})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["duplicates"], 1);
+        assert_eq!(body["results"][0]["activity_id"], activity_id);
+
+        let (status, body) = post_json(
+            &state,
+            "/api/v1/activities",
+            json!([
+                {"id": "vscode-2", "source": "vscode", "app": "Code", "start": 1_771_405_800, "end": 1_771_406_100},
+                {"id": "vscode-3", "source": "vscode", "app": "Code", "start": 1_771_405_500, "end": 1_771_405_900}
+            ]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(
+            body["error"]
+                .as_str()
+                .expect("message")
+                .contains("vscode-3")
+        );
+
+        let (status, _) = post_json(
+            &state,
+            "/api/v1/activities",
+            json!({"id": "x", "source": "window", "app": "Code", "start": 10, "end": 20}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let activities = Database::open(&state.config.db_path)
+            .expect("open db")
+            .activities_between(0, i64::MAX)
+            .expect("activities");
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].category, "development");
+        assert_eq!(activities[0].duration_sec, 600);
+        assert_eq!(
+            activities[0].metadata,
+            Some(json!({"project": "opentracker"}))
+        );
+
+        let (status, _) = post_json(
+            &state,
+            "/api/v1/activities",
+            json!({"id": "review-1", "source": "github", "app": "Code", "category": "Dev / Code-Review", "start": 1_771_406_100, "end": 1_771_406_400}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let range = "/api/v1/activities?from=2026-02-17&to=2026-02-19";
+        let (_, body) = get_json(&state, range).await;
+        assert_eq!(
+            body["categories_seconds"],
+            json!({"development": 600, "development/code-review": 300})
+        );
+        let (_, body) = get_json(&state, &format!("{range}&depth=1")).await;
+        assert_eq!(body["categories_seconds"], json!({"development": 900}));
+        let (status, _) = get_json(&state, &format!("{range}&depth=0")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn wakatime_heartbeats_are_stored_once_and_summarized() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let state = ApiState {
+            config: Arc::new(Config {
+                db_path: dir.path().join("activity.db"),
+                ..Config::default()
+            }),
+        };
+
+        let heartbeat = |time: f64, entity: &str, project: &str, language: &str| {
+            json!({
+                "entity": entity,
+                "type": "file",
+                "category": "coding",
+                "time": time,
+                "project": project,
+                "language": language,
+                "is_write": false,
+                "dependencies": ["serde"],
+                "cursorpos": 12
+            })
+        };
+        let batch = json!([
+            heartbeat(
+                1_771_405_200.5,
+                "/src/opentracker/src/main.rs",
+                "opentracker",
+                "Rust"
+            ),
+            heartbeat(
+                1_771_405_500.5,
+                "/src/opentracker/README.md",
+                "opentracker",
+                "Markdown"
+            ),
+            heartbeat(1_771_405_620.5, "/src/site/index.ts", "site", "TypeScript"),
+            // Over the 15-minute timeout: the previous heartbeat ends its stretch.
+            heartbeat(1_771_407_000.0, "/src/site/index.ts", "site", "TypeScript")
+        ]);
+
+        let (status, body) = post_json(
+            &state,
+            "/api/v1/users/current/heartbeats.bulk",
+            batch.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["responses"].as_array().expect("responses").len(), 4);
+        assert_eq!(body["responses"][0][1], 201);
+
+        let (status, _) = post_json(&state, "/api/v1/users/current/heartbeats.bulk", batch).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, _) = post_json(
+            &state,
+            "/api/v1/users/current/heartbeats",
+            json!({"entity": "", "time": 1_771_405_200.0}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let heartbeats = Database::open(&state.config.db_path)
+            .expect("open db")
+            .heartbeats_between(1_771_405_000, 1_771_408_000)
+            .expect("heartbeats");
+        assert_eq!(heartbeats.len(), 4);
+
+        let coding = report::coding_summary(&heartbeats);
+        assert_eq!(coding.total_seconds, 420);
+        assert_eq!(coding.projects[0].name, "opentracker");
+        assert_eq!(coding.projects[0].seconds, 420);
+        assert_eq!(coding.languages[0].name, "Rust");
+        assert_eq!(coding.languages[0].seconds, 300);
+        assert_eq!(coding.files[1].name, "/src/opentracker/README.md");
+    }
+
+    #[tokio::test]
+    async fn shell_commands_are_summarized_per_project() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).expect("git dir");
+        fs::create_dir_all(repo.join("src")).expect("src dir");
+        let state = ApiState {
+            config: Arc::new(Config {
+                db_path: dir.path().join("activity.db"),
+                ..Config::default()
+            }),
+        };
+
+        let post_form = |body: String| {
+            router(state.clone()).oneshot(
+                Request::post("/api/v1/ingest/shell")
+                    .header("content-type", "application/x-www-form-urlencoded")
+                    .body(Body::from(body))
+                    .expect("request"),
+            )
+        };
+        let form = |command: &str, cwd: &std::path::Path, exit_code, started_at: f64, ended_at| {
+            format!(
+                "command={}&cwd={}&exit_code={exit_code}&started_at={started_at}&ended_at={ended_at}&shell=zsh",
+                url::form_urlencoded::byte_serialize(command.as_bytes()).collect::<String>(),
+                url::form_urlencoded::byte_serialize(cwd.display().to_string().as_bytes())
+                    .collect::<String>(),
+            )
+        };
+
+        for (command, cwd, exit_code, started_at, ended_at) in [
+            (
+                "cargo build --release",
+                repo.join("src"),
+                0,
+                1_000.5,
+                1_180.5,
+            ),
+            ("cargo build", repo.clone(), 101, 2_000.0, 2_090.0),
+            ("git status", repo.clone(), 0, 3_000.0, 3_000.2),
+            ("ls -la", dir.path().to_path_buf(), 0, 4_000.0, 4_000.1),
+        ] {
+            let response = post_form(form(command, &cwd, exit_code, started_at, ended_at))
+                .await
+                .expect("response");
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = post_form(form("  ", &repo, 0, 5_000.0, 4_000.0))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let commands = Database::open(&state.config.db_path)
+            .expect("open db")
+            .shell_commands_between(0, 10_000)
+            .expect("commands");
+        let summary = report::shell_summary(&commands);
+
+        assert_eq!(summary.command_count, 4);
+        assert_eq!(summary.failed_count, 1);
+        assert_eq!(summary.long_running_count, 2);
+        assert_eq!(summary.long_running_seconds, 270);
+        assert_eq!(summary.long_running[0].name, "cargo build");
+        assert_eq!(summary.long_running[0].count, 2);
+        assert_eq!(summary.projects[0].directory, repo.display().to_string());
+        assert_eq!(summary.projects[0].command_count, 3);
+        assert_eq!(summary.projects[0].top_commands[0].name, "cargo build");
+        assert_eq!(summary.projects[0].top_commands[1].name, "git status");
+    }
+
+    #[tokio::test]
+    async fn explains_how_a_visit_is_categorized() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let categories_path = dir.path().join("categories.json");
+        fs::write(
+            &categories_path,
+            r#"{"apps":{},"domains":{"google.com":"research"},"rules":[{"category":"shopping","url":{"glob":"*/shopping*"}}]}"#,
+        )
+        .expect("categories");
+        let state = ApiState {
+            config: Arc::new(Config {
+                categories_path,
+                ..Config::default()
+            }),
+        };
+
+        let (status, explanation) = post_json(
+            &state,
+            "/api/v1/categories/explain",
+            json!({"url": "https://www.google.com/shopping?q=desk"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(explanation["category"], "shopping");
+        assert_eq!(explanation["matches"][0]["rule"], "rules[0]");
+        assert_eq!(explanation["matches"][1]["rule"], "domains.google.com");
+
+        let (status, _) = post_json(&state, "/api/v1/categories/explain", json!({})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
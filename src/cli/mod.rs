@@ -1,6 +1,9 @@
 pub mod onboard;
 
+use crate::collector::shell::Shell;
+use crate::collector::timesheet::CsvFormat;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -36,7 +39,36 @@ pub enum Commands {
         #[command(subcommand)]
         command: AiCommands,
     },
+    /// Import history recorded by other trackers
+    Import {
+        #[command(subcommand)]
+        command: ImportCommands,
+    },
+    /// Check categories.json rules
+    Categories {
+        #[command(subcommand)]
+        command: CategoriesCommands,
+    },
+    /// Re-apply the current category rules to stored activities and browser visits
+    Recategorize {
+        #[arg(long)]
+        from: String,
+        /// Last date to update (default: today, as in the API)
+        #[arg(long)]
+        to: Option<String>,
+        /// Show what would move between categories without storing anything
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Regenerate the daily reports of every changed date
+        #[arg(long, default_value_t = false, conflicts_with = "dry_run")]
+        regenerate_reports: bool,
+    },
     Service,
+    /// Print the shell hook that reports terminal commands, e.g. `eval "$(OpenTracker shell-init zsh)"`
+    ShellInit {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
     Update,
     Uninstall,
 }
@@ -58,3 +90,48 @@ pub enum AiCommands {
         model: Option<String>,
     },
 }
+
+#[derive(Debug, Subcommand)]
+pub enum CategoriesCommands {
+    /// Show which rules match a window or browser visit and the category it gets
+    Test {
+        #[arg(long, required_unless_present_any = ["domain", "url"])]
+        app: Option<String>,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        domain: Option<String>,
+        /// Also sets the domain when `--domain` is omitted
+        #[arg(long)]
+        url: Option<String>,
+    },
+    /// Report duplicate, shadowed and unreachable rules
+    Lint,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportCommands {
+    /// Import an ActivityWatch export (window, afk and web buckets)
+    #[command(name = "activitywatch")]
+    ActivityWatch {
+        file: PathBuf,
+        /// Regenerate the daily reports of every imported date
+        #[arg(long, default_value_t = false)]
+        regenerate_reports: bool,
+    },
+    /// Import a time tracker CSV export (Toggl, Clockify, RescueTime or any CSV with a mapping)
+    Csv {
+        file: PathBuf,
+        #[arg(long, value_enum)]
+        format: CsvFormat,
+        /// JSON file mapping columns; required for `generic`, overrides the preset otherwise
+        #[arg(long)]
+        mapping: Option<PathBuf>,
+        /// Show what would be imported per day without storing anything
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Regenerate the daily reports of every imported date
+        #[arg(long, default_value_t = false, conflicts_with = "dry_run")]
+        regenerate_reports: bool,
+    },
+}
@@ -1,9 +1,10 @@
-use crate::collector::chrome;
+use crate::collector::{chrome, firefox};
 use crate::config::{Config, default_report_dir, expand_home, parse_hhmm};
 use crate::daemon;
 use crate::db::Database;
 use anyhow::{Context, Result};
-use dialoguer::{Confirm, Input, Select, theme::ColorfulTheme};
+use dialoguer::{Confirm, Input, MultiSelect, theme::ColorfulTheme};
+#[cfg(target_os = "macos")]
 use std::process::Command;
 
 pub fn run_onboarding(install_daemon_flag: bool) -> Result<Config> {
@@ -39,27 +40,53 @@ pub fn run_onboarding(install_daemon_flag: bool) -> Result<Config> {
         println!("  ! Continuing without permission (app name only)");
     }
 
-    println!("\n[2/5] Select Chrome profile");
-    let profiles = {
-        let mut detected = chrome::detect_chrome_profiles();
-        if detected.is_empty() {
-            detected.push("Default".to_string());
-        }
-        detected
-    };
-
-    let selected_index = Select::with_theme(&theme)
-        .with_prompt("  Select profile to track")
-        .default(0)
-        .items(&profiles)
-        .interact()
-        .context("Failed to select Chrome profile")?;
+    println!("\n[2/5] Select browser profiles");
+    let chromium_profiles = chrome::detect_browser_profiles();
+    let firefox_profiles = firefox::detect_firefox_profiles()
+        .into_iter()
+        .map(|profile| profile.name)
+        .collect::<Vec<_>>();
 
-    let selected_profile = profiles
-        .get(selected_index)
+    let items = chromium_profiles
+        .iter()
         .cloned()
-        .unwrap_or_else(|| "Default".to_string());
-    println!("  ✓ Selected profile: {}", selected_profile);
+        .chain(
+            firefox_profiles
+                .iter()
+                .map(|name| format!("{}:{name}", firefox::BROWSER_NAME)),
+        )
+        .collect::<Vec<_>>();
+
+    let (chrome_profiles, firefox_profiles) = if items.is_empty() {
+        println!("  ! No browser profiles detected. Using Chrome `Default`.");
+        (vec!["Default".to_string()], Vec::new())
+    } else {
+        let defaults = vec![true; items.len()];
+        let selected = MultiSelect::with_theme(&theme)
+            .with_prompt("  Select profiles to track (space to toggle)")
+            .items(&items)
+            .defaults(&defaults)
+            .interact()
+            .context("Failed to select browser profiles")?;
+
+        let chrome_selected = selected
+            .iter()
+            .filter(|index| **index < chromium_profiles.len())
+            .map(|index| chromium_profiles[*index].clone())
+            .collect::<Vec<_>>();
+        let firefox_selected = selected
+            .iter()
+            .filter_map(|index| index.checked_sub(chromium_profiles.len()))
+            .map(|index| firefox_profiles[index].clone())
+            .collect::<Vec<_>>();
+
+        (chrome_selected, firefox_selected)
+    };
+
+    chrome_profiles
+        .iter()
+        .chain(firefox_profiles.iter())
+        .for_each(|profile| println!("  ✓ Selected profile: {profile}"));
 
     println!("\n[3/5] Set report generation time");
     let report_time: String = Input::with_theme(&theme)
@@ -101,7 +128,8 @@ pub fn run_onboarding(install_daemon_flag: bool) -> Result<Config> {
     let config = Config {
         report_time,
         report_dir,
-        chrome_profiles: vec![selected_profile],
+        chrome_profiles,
+        firefox_profiles,
         ..Config::default()
     };
 
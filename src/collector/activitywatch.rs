@@ -0,0 +1,436 @@
+use crate::analyzer::categorizer::CategoryRules;
+use crate::analyzer::project::TitleParser;
+use crate::analyzer::reconcile;
+use crate::collector::chrome::extract_domain;
+use crate::collector::extension::PROFILE_PREFIX;
+use crate::db::{ActivityInput, BrowserVisitInput, Database, ImportCounts, ImportOutcome};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+
+/// `activities.source` of imported window events.
+pub const SOURCE: &str = "activitywatch";
+
+/// An `aw-server` export, of all buckets (`/api/0/export`) or of one (`/api/0/buckets/<id>/export`).
+#[derive(Debug, Deserialize)]
+struct Export {
+    buckets: BTreeMap<String, Bucket>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Bucket {
+    #[serde(rename = "type", default)]
+    bucket_type: String,
+    #[serde(default)]
+    hostname: Option<String>,
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Event {
+    #[serde(default)]
+    id: Option<i64>,
+    timestamp: String,
+    /// Seconds, fractional.
+    #[serde(default)]
+    duration: f64,
+    #[serde(default)]
+    data: Map<String, Value>,
+}
+
+impl Event {
+    /// Start and end in Unix seconds; `None` for unparsable or empty events.
+    fn span(&self) -> Option<(i64, i64)> {
+        let start_ms = DateTime::parse_from_rfc3339(self.timestamp.trim())
+            .ok()?
+            .timestamp_millis();
+        let end_ms = start_ms + (self.duration.max(0.0) * 1_000.0).round() as i64;
+        let (start, end) = (round_seconds(start_ms), round_seconds(end_ms));
+        (end > start).then_some((start, end))
+    }
+
+    fn text(&self, key: &str) -> Option<String> {
+        self.data
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToOwned::to_owned)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BucketKind {
+    Window,
+    Afk,
+    Web,
+}
+
+impl BucketKind {
+    fn of(id: &str, bucket: &Bucket) -> Option<Self> {
+        match bucket.bucket_type.as_str() {
+            "currentwindow" => Some(Self::Window),
+            "afkstatus" => Some(Self::Afk),
+            "web.tab.current" => Some(Self::Web),
+            _ if id.starts_with("aw-watcher-window") => Some(Self::Window),
+            _ if id.starts_with("aw-watcher-afk") => Some(Self::Afk),
+            _ if id.starts_with("aw-watcher-web") => Some(Self::Web),
+            _ => None,
+        }
+    }
+}
+
+/// Window events and browser visits mapped from an export, before storing.
+#[derive(Debug, Default)]
+struct ParsedExport {
+    activities: Vec<ActivityInput>,
+    /// Visits per `chrome_visits.profile`, one per web bucket.
+    visits: BTreeMap<String, Vec<BrowserVisitInput>>,
+    skipped_buckets: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub activities: ImportCounts,
+    pub visits: usize,
+    /// Buckets of other watchers (editors, input, ...), which have no OpenTracker equivalent.
+    pub skipped_buckets: Vec<String>,
+    /// Window and web events older than `retention_days`, which the collector would delete again.
+    pub older_than_retention: usize,
+    /// Local dates covered by the imported events.
+    pub dates: Option<(NaiveDate, NaiveDate)>,
+}
+
+/// Imports window, AFK and web buckets. Window events become `activitywatch` activities (idle
+/// when mostly inside an AFK period) and web events become browser visits; both are keyed by
+/// bucket and event id, so re-importing an export only adds what is new. Events older than
+/// `retention_days` are skipped and counted. New window events also become sessions.
+pub fn import_export(
+    database: &mut Database,
+    rules: &CategoryRules,
+    content: &str,
+    retention_days: u32,
+) -> Result<ImportSummary> {
+    let export = serde_json::from_str::<Export>(content)
+        .context("Failed to parse ActivityWatch export (expected a `buckets` object)")?;
+    let mut parsed = parse_export(export, rules, &rules.title_parser()?);
+    if parsed.activities.is_empty() && parsed.visits.is_empty() {
+        bail!("The export contains no window or web events");
+    }
+    let older_than_retention = drop_older_than(&mut parsed, retention_threshold(retention_days));
+
+    let dates = parsed
+        .activities
+        .iter()
+        .map(|activity| activity.recorded_at - activity.duration_sec)
+        .chain(
+            parsed
+                .visits
+                .values()
+                .flatten()
+                .map(|visit| visit.visited_at),
+        )
+        .filter_map(|timestamp| Local.timestamp_opt(timestamp, 0).single())
+        .map(|time| time.date_naive())
+        .fold(None, |range: Option<(NaiveDate, NaiveDate)>, date| {
+            Some(range.map_or((date, date), |(from, to)| (from.min(date), to.max(date))))
+        });
+
+    let outcomes = database.import_activities(&parsed.activities, false)?;
+    let mut created = parsed
+        .activities
+        .iter()
+        .zip(&outcomes)
+        .filter(|(_, outcome)| **outcome == ImportOutcome::Created)
+        .map(|(activity, _)| activity)
+        .collect::<Vec<_>>();
+    created.sort_by_key(|activity| activity.recorded_at);
+    for activity in created {
+        database.record_session_sample(activity)?;
+    }
+
+    let activities = ImportCounts::tally(&outcomes);
+    let mut visits = 0;
+    for (profile, profile_visits) in &parsed.visits {
+        visits += database.upsert_browser_visits(profile, profile_visits, None)?;
+    }
+
+    Ok(ImportSummary {
+        activities,
+        visits,
+        skipped_buckets: parsed.skipped_buckets,
+        older_than_retention,
+        dates,
+    })
+}
+
+/// The collector deletes activities older than this on every tick.
+fn retention_threshold(retention_days: u32) -> i64 {
+    (Local::now() - Duration::days(i64::from(retention_days))).timestamp()
+}
+
+/// Removes window events that end, and visits made, before `threshold`; returns how many.
+fn drop_older_than(parsed: &mut ParsedExport, threshold: i64) -> usize {
+    let before = parsed.activities.len();
+    parsed
+        .activities
+        .retain(|activity| activity.recorded_at >= threshold);
+    let mut dropped = before - parsed.activities.len();
+    for visits in parsed.visits.values_mut() {
+        let before = visits.len();
+        visits.retain(|visit| visit.visited_at >= threshold);
+        dropped += before - visits.len();
+    }
+    parsed.visits.retain(|_, visits| !visits.is_empty());
+    dropped
+}
+
+fn parse_export(export: Export, rules: &CategoryRules, titles: &TitleParser) -> ParsedExport {
+    let mut parsed = ParsedExport::default();
+    let afk_periods = export
+        .buckets
+        .iter()
+        .filter(|(id, bucket)| BucketKind::of(id, bucket) == Some(BucketKind::Afk))
+        .flat_map(|(_, bucket)| &bucket.events)
+        .filter(|event| event.text("status").as_deref() == Some("afk"))
+        .filter_map(Event::span)
+        .collect::<Vec<_>>();
+
+    for (id, bucket) in &export.buckets {
+        match BucketKind::of(id, bucket) {
+            Some(BucketKind::Window) => {
+                parsed
+                    .activities
+                    .extend(bucket.events.iter().filter_map(|event| {
+                        window_activity(id, bucket, event, &afk_periods, rules, titles)
+                    }))
+            }
+            Some(BucketKind::Web) => {
+                let visits = web_visits(id, bucket, rules);
+                if !visits.is_empty() {
+                    parsed
+                        .visits
+                        .insert(format!("{PROFILE_PREFIX}{SOURCE}:{id}"), visits);
+                }
+            }
+            Some(BucketKind::Afk) => {}
+            None => parsed.skipped_buckets.push(id.clone()),
+        }
+    }
+
+    parsed
+}
+
+fn window_activity(
+    bucket_id: &str,
+    bucket: &Bucket,
+    event: &Event,
+    afk_periods: &[(i64, i64)],
+    rules: &CategoryRules,
+    titles: &TitleParser,
+) -> Option<ActivityInput> {
+    let (start, end) = event.span()?;
+    let app_name = event.text("app")?;
+    let window_title = event.text("title");
+
+    let afk_seconds = afk_periods
+        .iter()
+        .map(|(from, to)| (end.min(*to) - start.max(*from)).max(0))
+        .sum::<i64>();
+    let state = if afk_seconds * 2 > end - start {
+        "idle"
+    } else {
+        "active"
+    };
+
+    Some(ActivityInput {
+        recorded_at: end,
+        category: rules.categorize_app(&app_name, window_title.as_deref()),
+        project: titles.project(&app_name, window_title.as_deref()),
+        app_name,
+        window_title,
+        duration_sec: end - start,
+        state: state.to_string(),
+        source: SOURCE.to_string(),
+        external_id: Some(format!("{bucket_id}:{}", event_key(event))),
+        metadata: Some(
+            json!({
+                "bucket": bucket_id,
+                "hostname": bucket.hostname,
+            })
+            .to_string(),
+        ),
+    })
+}
+
+fn web_visits(bucket_id: &str, bucket: &Bucket, rules: &CategoryRules) -> Vec<BrowserVisitInput> {
+    // Web watcher buckets are named `aw-watcher-web-<browser>[_<hostname>]`.
+    let name = bucket_id
+        .strip_prefix("aw-watcher-web-")
+        .and_then(|rest| rest.split('_').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("chrome");
+    let browser = reconcile::browser_for_app(name).unwrap_or(name);
+
+    bucket
+        .events
+        .iter()
+        .filter_map(|event| {
+            let (visited_at, end) = event.span()?;
+            let url = event.text("url")?;
+            let domain = extract_domain(&url)?;
+            Some(BrowserVisitInput {
+                source_visit_id: event_key(event),
+                date: Local.timestamp_opt(visited_at, 0).single()?.date_naive(),
+                visited_at,
+                browser: browser.to_string(),
+                category: rules.categorize_domain(&domain, Some(&url)),
+                url,
+                transition: SOURCE.to_string(),
+                domain,
+                duration_sec: end - visited_at,
+            })
+        })
+        .collect()
+}
+
+/// The event id, or its start in milliseconds for exports without ids.
+fn event_key(event: &Event) -> i64 {
+    event.id.unwrap_or_else(|| {
+        DateTime::parse_from_rfc3339(event.timestamp.trim())
+            .map(|time| time.timestamp_millis())
+            .unwrap_or_default()
+    })
+}
+
+fn round_seconds(milliseconds: i64) -> i64 {
+    (milliseconds + 500).div_euclid(1_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Export, import_export, parse_export};
+    use crate::analyzer::categorizer::CategoryRules;
+    use crate::db::Database;
+    use chrono::{Duration, Local, SecondsFormat};
+    use std::collections::HashMap;
+
+    #[test]
+    fn maps_window_afk_and_web_buckets() {
+        let rules = CategoryRules {
+            apps: HashMap::from([("code".to_string(), "development".to_string())]),
+            domains: HashMap::from([("github.com".to_string(), "development".to_string())]),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+        let export = serde_json::from_str::<Export>(
+            r#"{"buckets": {
+                "aw-watcher-window_laptop": {"type": "currentwindow", "hostname": "laptop", "events": [
+                    {"id": 2, "timestamp": "2026-02-18T09:10:00.400000+00:00", "duration": 599.8, "data": {"app": "Code", "title": "main.rs - opentracker - Visual Studio Code"}},
+                    {"id": 1, "timestamp": "2026-02-18T09:00:00Z", "duration": 600.0, "data": {"app": "Slack", "title": "general"}},
+                    {"id": 3, "timestamp": "2026-02-18T09:20:00Z", "duration": 0.0, "data": {"app": "Code", "title": ""}}
+                ]},
+                "aw-watcher-afk_laptop": {"type": "afkstatus", "events": [
+                    {"id": 7, "timestamp": "2026-02-18T09:00:00Z", "duration": 420.0, "data": {"status": "afk"}}
+                ]},
+                "aw-watcher-web-firefox": {"type": "web.tab.current", "events": [
+                    {"id": 9, "timestamp": "2026-02-18T09:12:00Z", "duration": 90.0, "data": {"url": "https://github.com/rust-lang/rust", "title": "rust", "audible": false}}
+                ]},
+                "aw-watcher-vscode_laptop": {"type": "app.editor.activity", "events": []}
+            }}"#,
+        )
+        .expect("export");
+
+        let parsed = parse_export(export, &rules, &rules.title_parser().expect("parser"));
+
+        let activities = parsed
+            .activities
+            .iter()
+            .map(|activity| {
+                (
+                    activity.app_name.as_str(),
+                    activity.category.as_str(),
+                    activity.state.as_str(),
+                    activity.duration_sec,
+                    activity.external_id.as_deref(),
+                    activity.project.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            activities,
+            vec![
+                (
+                    "Code",
+                    "development",
+                    "active",
+                    600,
+                    Some("aw-watcher-window_laptop:2"),
+                    Some("opentracker")
+                ),
+                (
+                    "Slack",
+                    "other",
+                    "idle",
+                    600,
+                    Some("aw-watcher-window_laptop:1"),
+                    None
+                ),
+            ]
+        );
+
+        let visits = &parsed.visits["extension:activitywatch:aw-watcher-web-firefox"];
+        assert_eq!(visits.len(), 1);
+        assert_eq!(visits[0].browser, "firefox");
+        assert_eq!(visits[0].domain, "github.com");
+        assert_eq!(visits[0].category, "development");
+        assert_eq!(visits[0].duration_sec, 90);
+        assert_eq!(parsed.skipped_buckets, vec!["aw-watcher-vscode_laptop"]);
+    }
+
+    #[test]
+    fn skips_events_past_retention_and_records_sessions() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let mut database = Database::open(&dir.path().join("activity.db")).expect("open db");
+        let rules = CategoryRules {
+            apps: HashMap::from([("code".to_string(), "development".to_string())]),
+            domains: HashMap::new(),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+        let recent = Local::now() - Duration::hours(2);
+        let timestamp =
+            |time: chrono::DateTime<Local>| time.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let export = format!(
+            r#"{{"buckets": {{
+                "aw-watcher-window_laptop": {{"type": "currentwindow", "events": [
+                    {{"id": 1, "timestamp": "{}", "duration": 600.0, "data": {{"app": "Code", "title": "main.rs"}}}},
+                    {{"id": 2, "timestamp": "{}", "duration": 600.0, "data": {{"app": "Code", "title": "main.rs"}}}}
+                ]}},
+                "aw-watcher-web-chrome": {{"type": "web.tab.current", "events": [
+                    {{"id": 3, "timestamp": "{}", "duration": 60.0, "data": {{"url": "https://github.com/"}}}}
+                ]}}
+            }}}}"#,
+            timestamp(recent),
+            timestamp(recent - Duration::days(120)),
+            timestamp(recent - Duration::days(120)),
+        );
+
+        let summary = import_export(&mut database, &rules, &export, 90).expect("import");
+
+        assert_eq!(summary.activities.created, 1);
+        assert_eq!(summary.visits, 0);
+        assert_eq!(summary.older_than_retention, 2);
+        let sessions = database
+            .sessions_for_date(recent.date_naive())
+            .expect("sessions");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].category, "development");
+        assert_eq!(sessions[0].started_at, recent.timestamp());
+    }
+}
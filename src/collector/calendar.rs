@@ -0,0 +1,802 @@
+use crate::config::Config;
+use crate::db::{CalendarEventInput, Database};
+use anyhow::{Context, Result};
+use chrono::{
+    DateTime, Datelike, Duration, Local, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Upper bound on recurrence periods walked per event, for rules whose end lies far in the future.
+const MAX_RECURRENCE_PERIODS: u32 = 100_000;
+
+/// Expands every event in the configured `.ics` files for `date` and replaces that day's stored
+/// events. Files that cannot be read are skipped with a warning; if none can be read, the stored
+/// events are kept.
+pub fn sync_calendar_events(
+    config: &Config,
+    database: &mut Database,
+    date: NaiveDate,
+) -> Result<usize> {
+    if config.calendar_paths.is_empty() {
+        return Ok(0);
+    }
+
+    let from = local_midnight(date)?;
+    let to = local_midnight(date + Duration::days(1))?;
+
+    let mut events = Vec::new();
+    let mut read_any = false;
+    for (file, fallback_name) in discover_calendars(&config.calendar_paths) {
+        match fs::read_to_string(&file) {
+            Ok(content) => {
+                read_any = true;
+                events.extend(events_between(&content, &fallback_name, from, to));
+            }
+            Err(error) => {
+                warn!(file = %file.display(), error = %error, "failed to read calendar file")
+            }
+        }
+    }
+    if !read_any {
+        warn!(%date, "no calendar file could be read; keeping the stored events");
+        return Ok(0);
+    }
+
+    database.replace_calendar_events(date, &events)
+}
+
+/// Each configured path is an `.ics` file or a folder searched recursively for them. Files inside
+/// a subfolder (one file per event, as synced calendars store them) are named after that folder.
+fn discover_calendars(paths: &[PathBuf]) -> Vec<(PathBuf, String)> {
+    fn walk(dir: &Path, name: Option<&str>, found: &mut Vec<(PathBuf, String)>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        for path in entries {
+            if path.is_dir() {
+                let folder = file_stem(&path);
+                walk(&path, Some(name.unwrap_or(&folder)), found);
+            } else if is_ics(&path) {
+                let calendar = name.map(str::to_string).unwrap_or_else(|| file_stem(&path));
+                found.push((path, calendar));
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            walk(path, None, &mut found);
+        } else {
+            found.push((path.clone(), file_stem(path)));
+        }
+    }
+    found
+}
+
+fn is_ics(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"))
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Occurrences of the timed events in `content` overlapping `[from, to)`. Cancelled, all-day and
+/// free (`TRANSP:TRANSPARENT`) events are not meetings and are left out.
+fn events_between(
+    content: &str,
+    fallback_name: &str,
+    from: i64,
+    to: i64,
+) -> Vec<CalendarEventInput> {
+    let (calendar_name, events) = parse_calendar(content);
+    let calendar = calendar_name.unwrap_or_else(|| fallback_name.to_string());
+
+    // Modified occurrences replace the occurrence of the recurring event they were moved from.
+    let overridden = events
+        .iter()
+        .filter_map(|event| Some((event.uid.clone(), event.recurrence_id?.timestamp()?)))
+        .collect::<HashSet<_>>();
+
+    let mut found = Vec::new();
+    for event in &events {
+        let Some(start) = event.start else {
+            continue;
+        };
+        if start.all_day || event.cancelled || event.transparent {
+            continue;
+        }
+        let Some(start_ts) = start.timestamp() else {
+            continue;
+        };
+        let length = match (event.end.and_then(Stamp::timestamp), event.duration) {
+            (Some(end_ts), _) => end_ts - start_ts,
+            (None, Some(duration)) => duration.num_seconds(),
+            (None, None) => 0,
+        };
+        if length <= 0 {
+            continue;
+        }
+
+        let rule = event
+            .recurrence_id
+            .is_none()
+            .then(|| event.rrule.as_deref().and_then(Rule::parse))
+            .flatten();
+        let starts = match rule {
+            Some(rule) => rule.occurrences(start, to),
+            None => vec![start_ts],
+        };
+        let excluded = event
+            .exdates
+            .iter()
+            .filter_map(|stamp| stamp.timestamp())
+            .collect::<HashSet<_>>();
+
+        found.extend(
+            starts
+                .into_iter()
+                .filter(|starts_at| *starts_at < to && starts_at + length > from)
+                .filter(|starts_at| !excluded.contains(starts_at))
+                .filter(|starts_at| {
+                    event.recurrence_id.is_some()
+                        || !overridden.contains(&(event.uid.clone(), *starts_at))
+                })
+                .map(|starts_at| CalendarEventInput {
+                    calendar: calendar.clone(),
+                    uid: event.uid.clone(),
+                    summary: event.summary.clone(),
+                    location: event.location.clone(),
+                    starts_at,
+                    ends_at: starts_at + length,
+                }),
+        );
+    }
+
+    found.sort_by_key(|event| event.starts_at);
+    found
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    Utc,
+    Named(Tz),
+    /// No zone given (or one chrono-tz does not know): the machine's local time.
+    Floating,
+}
+
+/// A `DTSTART`-style value: wall-clock time in its zone, or a whole day.
+#[derive(Debug, Clone, Copy)]
+struct Stamp {
+    local: NaiveDateTime,
+    zone: Zone,
+    all_day: bool,
+}
+
+impl Stamp {
+    fn parse_list(value: &str, params: &HashMap<String, String>) -> Vec<Self> {
+        let zone = match params.get("TZID") {
+            Some(tzid) => parse_tzid(tzid).map_or(Zone::Floating, Zone::Named),
+            None => Zone::Floating,
+        };
+
+        value
+            .split(',')
+            .filter_map(|part| {
+                let part = part.trim();
+                if let Some(utc) = part.strip_suffix('Z') {
+                    let local = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+                    Some(Self {
+                        local,
+                        zone: Zone::Utc,
+                        all_day: false,
+                    })
+                } else if part.contains('T') {
+                    let local = NaiveDateTime::parse_from_str(part, "%Y%m%dT%H%M%S").ok()?;
+                    Some(Self {
+                        local,
+                        zone,
+                        all_day: false,
+                    })
+                } else {
+                    let day = NaiveDate::parse_from_str(part, "%Y%m%d").ok()?;
+                    Some(Self {
+                        local: day.and_time(NaiveTime::MIN),
+                        zone,
+                        all_day: true,
+                    })
+                }
+            })
+            .collect()
+    }
+
+    fn at(self, local: NaiveDateTime) -> Self {
+        Self { local, ..self }
+    }
+
+    fn timestamp(self) -> Option<i64> {
+        match self.zone {
+            Zone::Utc => Some(Utc.from_utc_datetime(&self.local).timestamp()),
+            Zone::Named(tz) => resolve_local(&tz, self.local),
+            Zone::Floating => resolve_local(&Local, self.local),
+        }
+    }
+}
+
+/// Wall-clock times skipped by a DST change move forward by an hour, as calendar apps do.
+fn resolve_local<Z: TimeZone>(zone: &Z, local: NaiveDateTime) -> Option<i64> {
+    match zone.from_local_datetime(&local) {
+        LocalResult::Single(at) => Some(at.timestamp()),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest.timestamp()),
+        LocalResult::None => zone
+            .from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+            .map(|at| at.timestamp()),
+    }
+}
+
+/// IANA names, also when prefixed the way some exporters do (`/mozilla.org/20050126_1/Europe/Berlin`).
+fn parse_tzid(tzid: &str) -> Option<Tz> {
+    let tzid = tzid.trim_matches('"');
+    tzid.parse().ok().or_else(|| {
+        tzid.match_indices('/')
+            .find_map(|(index, _)| tzid[index + 1..].parse().ok())
+    })
+}
+
+#[derive(Debug, Default)]
+struct Event {
+    uid: String,
+    summary: String,
+    location: Option<String>,
+    start: Option<Stamp>,
+    end: Option<Stamp>,
+    duration: Option<Duration>,
+    rrule: Option<String>,
+    exdates: Vec<Stamp>,
+    recurrence_id: Option<Stamp>,
+    cancelled: bool,
+    transparent: bool,
+}
+
+/// Returns the calendar's `X-WR-CALNAME` and its `VEVENT`s. Nested components such as alarms
+/// are skipped.
+fn parse_calendar(content: &str) -> (Option<String>, Vec<Event>) {
+    let mut name = None;
+    let mut events = Vec::new();
+    let mut current: Option<Event> = None;
+    let mut nested = 0_usize;
+
+    for line in unfold(content) {
+        let Some((property, params, value)) = parse_line(&line) else {
+            continue;
+        };
+
+        match (property.as_str(), value.to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") if current.is_none() => {
+                current = Some(Event::default());
+                continue;
+            }
+            ("END", "VEVENT") if nested == 0 => {
+                events.extend(current.take());
+                continue;
+            }
+            ("BEGIN", _) if current.is_some() => {
+                nested += 1;
+                continue;
+            }
+            ("END", _) if current.is_some() => {
+                nested = nested.saturating_sub(1);
+                continue;
+            }
+            _ => {}
+        }
+
+        let Some(event) = current.as_mut().filter(|_| nested == 0) else {
+            if property == "X-WR-CALNAME" && current.is_none() {
+                name = Some(unescape(&value)).filter(|name| !name.is_empty());
+            }
+            continue;
+        };
+        match property.as_str() {
+            "UID" => event.uid = value,
+            "SUMMARY" => event.summary = unescape(&value),
+            "LOCATION" => event.location = Some(unescape(&value)).filter(|place| !place.is_empty()),
+            "DTSTART" => event.start = Stamp::parse_list(&value, &params).into_iter().next(),
+            "DTEND" => event.end = Stamp::parse_list(&value, &params).into_iter().next(),
+            "DURATION" => event.duration = parse_duration(&value),
+            "RRULE" => event.rrule = Some(value),
+            "EXDATE" => event.exdates.extend(Stamp::parse_list(&value, &params)),
+            "RECURRENCE-ID" => {
+                event.recurrence_id = Stamp::parse_list(&value, &params).into_iter().next()
+            }
+            "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "TRANSP" => event.transparent = value.eq_ignore_ascii_case("TRANSPARENT"),
+            _ => {}
+        }
+    }
+
+    (name, events)
+}
+
+/// Joins folded lines (continuations start with a space or tab).
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Splits `NAME;PARAM=value;PARAM="quoted:value":VALUE`.
+fn parse_line(line: &str) -> Option<(String, HashMap<String, String>, String)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(index, ch)| {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => return Some(index),
+            _ => {}
+        }
+        None
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let property = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            Some((key.trim().to_ascii_uppercase(), value.trim().to_string()))
+        })
+        .collect();
+
+    Some((property, params, value.trim().to_string()))
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+        .trim()
+        .to_string()
+}
+
+/// `P1W`, `P1DT2H`, `PT45M`, ...; negative durations are not meaningful for events.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().strip_prefix('+').unwrap_or(value.trim());
+    let body = value.strip_prefix('P')?;
+
+    let mut seconds = 0_i64;
+    let mut number = String::new();
+    let mut in_time = false;
+    for ch in body.chars() {
+        match ch {
+            'T' => in_time = true,
+            '0'..='9' => number.push(ch),
+            unit => {
+                let amount = number.parse::<i64>().ok()?;
+                number.clear();
+                seconds += amount
+                    * match (unit, in_time) {
+                        ('W', false) => 7 * 86_400,
+                        ('D', false) => 86_400,
+                        ('H', true) => 3_600,
+                        ('M', true) => 60,
+                        ('S', true) => 1,
+                        _ => return None,
+                    };
+            }
+        }
+    }
+
+    number.is_empty().then(|| Duration::seconds(seconds))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// The subset of RFC 5545 `RRULE`s calendar apps produce for meetings: `FREQ` (daily to yearly),
+/// `INTERVAL`, `COUNT`, `UNTIL`, `BYDAY` (with `2TU`/`-1FR` ordinals), `BYMONTHDAY` and `BYMONTH`.
+#[derive(Debug, Clone)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    until: Option<String>,
+    by_day: Vec<(Option<i32>, Weekday)>,
+    by_month_day: Vec<i32>,
+    by_month: Vec<u32>,
+}
+
+impl Rule {
+    /// `None` for frequencies that are not supported; such events keep only their first occurrence.
+    fn parse(value: &str) -> Option<Self> {
+        let parts = value
+            .split(';')
+            .filter_map(|part| part.split_once('='))
+            .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim()))
+            .collect::<HashMap<_, _>>();
+        let list = |key: &str| {
+            parts
+                .get(key)
+                .map(|value| value.split(',').map(str::trim).collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+
+        Some(Self {
+            frequency: match parts.get("FREQ")?.to_ascii_uppercase().as_str() {
+                "DAILY" => Frequency::Daily,
+                "WEEKLY" => Frequency::Weekly,
+                "MONTHLY" => Frequency::Monthly,
+                "YEARLY" => Frequency::Yearly,
+                _ => return None,
+            },
+            interval: parts
+                .get("INTERVAL")
+                .and_then(|value| value.parse().ok())
+                .filter(|interval| *interval > 0)
+                .unwrap_or(1),
+            count: parts.get("COUNT").and_then(|value| value.parse().ok()),
+            until: parts.get("UNTIL").map(|value| value.to_string()),
+            by_day: list("BYDAY")
+                .into_iter()
+                .filter_map(|day| {
+                    let split = day.len().checked_sub(2)?;
+                    let weekday = match &day[split..] {
+                        "MO" => Weekday::Mon,
+                        "TU" => Weekday::Tue,
+                        "WE" => Weekday::Wed,
+                        "TH" => Weekday::Thu,
+                        "FR" => Weekday::Fri,
+                        "SA" => Weekday::Sat,
+                        "SU" => Weekday::Sun,
+                        _ => return None,
+                    };
+                    let ordinal = &day[..split];
+                    Some((
+                        (!ordinal.is_empty())
+                            .then(|| ordinal.parse().ok())
+                            .flatten(),
+                        weekday,
+                    ))
+                })
+                .collect(),
+            by_month_day: list("BYMONTHDAY")
+                .into_iter()
+                .filter_map(|day| day.parse().ok())
+                .collect(),
+            by_month: list("BYMONTH")
+                .into_iter()
+                .filter_map(|month| month.parse().ok())
+                .collect(),
+        })
+    }
+
+    /// Start timestamps of the occurrences beginning before `to`, in order. Expansion happens in
+    /// the event's wall-clock time so meetings keep their local time across DST changes.
+    fn occurrences(&self, start: Stamp, to: i64) -> Vec<i64> {
+        let until = self.until.as_deref().and_then(|until| {
+            let stamp = Stamp::parse_list(until, &HashMap::new())
+                .into_iter()
+                .next()?;
+            if stamp.all_day {
+                start
+                    .at(stamp.local.date().and_time(NaiveTime::MIN) + Duration::days(1))
+                    .timestamp()
+                    .map(|end| end - 1)
+            } else if matches!(stamp.zone, Zone::Floating) {
+                start.at(stamp.local).timestamp()
+            } else {
+                stamp.timestamp()
+            }
+        });
+
+        let mut found = Vec::new();
+        for period in 0..MAX_RECURRENCE_PERIODS {
+            let Some(candidates) = self.period_candidates(start.local, period) else {
+                break;
+            };
+
+            for candidate in candidates {
+                if candidate < start.local {
+                    continue;
+                }
+                let Some(at) = start.at(candidate).timestamp() else {
+                    continue;
+                };
+                if at >= to || until.is_some_and(|until| at > until) {
+                    return found;
+                }
+                found.push(at);
+                if self.count.is_some_and(|count| found.len() >= count) {
+                    return found;
+                }
+            }
+        }
+        found
+    }
+
+    /// Sorted candidate start times in the `period`-th day/week/month/year after `start`.
+    fn period_candidates(&self, start: NaiveDateTime, period: u32) -> Option<Vec<NaiveDateTime>> {
+        let step = period.checked_mul(self.interval)?;
+        let days = match self.frequency {
+            Frequency::Daily => {
+                let day = start.date() + Duration::days(step.into());
+                vec![day]
+                    .into_iter()
+                    .filter(|day| self.by_month.is_empty() || self.by_month.contains(&day.month()))
+                    .filter(|day| {
+                        self.by_day.is_empty()
+                            || self
+                                .by_day
+                                .iter()
+                                .any(|(_, weekday)| *weekday == day.weekday())
+                    })
+                    .collect()
+            }
+            Frequency::Weekly => {
+                let week_start = start.date()
+                    - Duration::days(start.weekday().num_days_from_monday().into())
+                    + Duration::weeks(step.into());
+                let mut weekdays = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|(_, weekday)| *weekday).collect()
+                };
+                weekdays.sort_by_key(Weekday::num_days_from_monday);
+                weekdays.dedup();
+                weekdays
+                    .into_iter()
+                    .map(|weekday| {
+                        week_start + Duration::days(weekday.num_days_from_monday().into())
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let month = first_of_month(start.date()).checked_add_months(Months::new(step))?;
+                if !self.by_month.is_empty() && !self.by_month.contains(&month.month()) {
+                    Vec::new()
+                } else {
+                    self.days_in_month(month, start.date())
+                }
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(step).ok()?)?;
+                let months = if self.by_month.is_empty() {
+                    vec![start.month()]
+                } else {
+                    self.by_month.clone()
+                };
+                months
+                    .into_iter()
+                    .filter_map(|month| NaiveDate::from_ymd_opt(year, month, 1))
+                    .flat_map(|month| self.days_in_month(month, start.date()))
+                    .collect()
+            }
+        };
+
+        let mut candidates = days
+            .into_iter()
+            .map(|day: NaiveDate| day.and_time(start.time()))
+            .collect::<Vec<_>>();
+        candidates.sort();
+        candidates.dedup();
+        Some(candidates)
+    }
+
+    /// Days of the month starting at `month` matched by `BYDAY`/`BYMONTHDAY`, or the day of
+    /// `start` when neither is given (months without that day are skipped).
+    fn days_in_month(&self, month: NaiveDate, start: NaiveDate) -> Vec<NaiveDate> {
+        let Some(next_month) = month.checked_add_months(Months::new(1)) else {
+            return Vec::new();
+        };
+        let days = month
+            .iter_days()
+            .take_while(|day| *day < next_month)
+            .collect::<Vec<_>>();
+        let last = days.len() as i32;
+
+        let month_day_matches = |day: &NaiveDate| {
+            self.by_month_day.iter().any(|wanted| {
+                let ordinal = day.day() as i32;
+                *wanted == ordinal || (*wanted < 0 && last + wanted + 1 == ordinal)
+            })
+        };
+
+        if !self.by_day.is_empty() {
+            days.iter()
+                .copied()
+                .filter(|day| {
+                    self.by_day.iter().any(|(ordinal, weekday)| {
+                        if day.weekday() != *weekday {
+                            return false;
+                        }
+                        let from_start = (day.day0() / 7) as i32 + 1;
+                        let from_end = -(((last as u32 - day.day()) / 7) as i32 + 1);
+                        ordinal.is_none_or(|ordinal| ordinal == from_start || ordinal == from_end)
+                    })
+                })
+                .filter(|day| self.by_month_day.is_empty() || month_day_matches(day))
+                .collect()
+        } else if !self.by_month_day.is_empty() {
+            days.into_iter().filter(month_day_matches).collect()
+        } else {
+            days.into_iter()
+                .filter(|day| day.day() == start.day())
+                .collect()
+        }
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn local_midnight(date: NaiveDate) -> Result<i64> {
+    let midnight = date
+        .and_hms_opt(0, 0, 0)
+        .context("Failed to build midnight")?;
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|at: DateTime<Local>| at.timestamp())
+        .context("Failed to convert midnight to local time")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{events_between, sync_calendar_events};
+    use crate::config::Config;
+    use crate::db::{CalendarEventInput, Database};
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn expands_recurring_events_with_exceptions() {
+        let content = "BEGIN:VCALENDAR\r\n\
+X-WR-CALNAME:Work\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup\r\n\
+SUMMARY:Daily standup\r\n\
+DTSTART;TZID=Europe/Berlin:20260202T093000\r\n\
+DTEND;TZID=Europe/Berlin:20260202T094500\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\r\n\
+EXDATE;TZID=Europe/Berlin:20260216T093000\r\n\
+BEGIN:VALARM\r\n\
+TRIGGER:-PT5M\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:standup\r\n\
+RECURRENCE-ID;TZID=Europe/Berlin:20260218T093000\r\n\
+SUMMARY:Daily standup (moved)\r\n\
+DTSTART;TZID=Europe/Berlin:20260218T110000\r\n\
+DURATION:PT30M\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:review\r\n\
+SUMMARY:Design review\\, Q1\r\n\
+DTSTART:20260128T150000Z\r\n\
+DTEND:20260128T160000Z\r\n\
+RRULE:FREQ=MONTHLY;INTERVAL=1;BYDAY=-1WE;COUNT=3\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:holiday\r\n\
+SUMMARY:Offsite\r\n\
+DTSTART;VALUE=DATE:20260218\r\n\
+DTEND;VALUE=DATE:20260219\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:cancelled\r\n\
+SUMMARY:Cancelled sync\r\n\
+STATUS:CANCELLED\r\n\
+DTSTART:20260218T130000Z\r\n\
+DTEND:20260218T133000Z\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+        let at = |day, hour, minute| {
+            Utc.with_ymd_and_hms(2026, 2, day, hour, minute, 0)
+                .unwrap()
+                .timestamp()
+        };
+        let events_on = |day| {
+            events_between(content, "fallback", at(day, 0, 0), at(day + 1, 0, 0))
+                .into_iter()
+                .map(|event| (event.summary, event.starts_at, event.ends_at))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            events_on(16),
+            Vec::<(String, i64, i64)>::new(),
+            "EXDATE removes the Monday occurrence"
+        );
+        assert_eq!(
+            events_on(18),
+            vec![(
+                "Daily standup (moved)".to_string(),
+                at(18, 10, 0),
+                at(18, 10, 30)
+            )]
+        );
+        assert_eq!(
+            events_on(20),
+            vec![("Daily standup".to_string(), at(20, 8, 30), at(20, 8, 45))]
+        );
+        assert_eq!(
+            events_on(25),
+            vec![
+                ("Daily standup".to_string(), at(25, 8, 30), at(25, 8, 45)),
+                (
+                    "Design review, Q1".to_string(),
+                    at(25, 15, 0),
+                    at(25, 16, 0)
+                ),
+            ]
+        );
+        assert_eq!(
+            events_between(content, "fallback", at(18, 0, 0), at(19, 0, 0))[0].calendar,
+            "Work"
+        );
+    }
+
+    #[test]
+    fn keeps_stored_events_when_no_calendar_can_be_read() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let mut database = Database::open(&dir.path().join("activity.db")).expect("open db");
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+        database
+            .replace_calendar_events(
+                date,
+                &[CalendarEventInput {
+                    calendar: "Work".to_string(),
+                    uid: "standup".to_string(),
+                    summary: "Daily standup".to_string(),
+                    location: None,
+                    starts_at: 1_771_403_400,
+                    ends_at: 1_771_404_300,
+                }],
+            )
+            .expect("store events");
+        let config = Config {
+            calendar_paths: vec![dir.path().join("missing.ics")],
+            ..Config::default()
+        };
+
+        assert_eq!(
+            sync_calendar_events(&config, &mut database, date).expect("sync"),
+            0
+        );
+        assert_eq!(
+            database
+                .calendar_events_for_date(date)
+                .expect("events")
+                .len(),
+            1
+        );
+    }
+}
@@ -1,58 +1,310 @@
 use crate::analyzer::categorizer::CategoryRules;
 use crate::config::Config;
-use crate::db::ChromeVisitInput;
+use crate::db::{BrowserVisitInput, Database};
 use anyhow::{Context, Result};
-use chrono::NaiveDate;
+use chrono::{Duration, Local, NaiveDate, NaiveTime, TimeZone};
 use rusqlite::{Connection, params};
-use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use tracing::warn;
 use url::Url;
 
-pub fn sync_chrome_visits_for_date(
+/// Zero-duration visits younger than this are treated as tabs still open; older ones as abandoned.
+const OPEN_VISIT_SECONDS: i64 = 12 * 60 * 60;
+
+/// A visit read from a browser's history DB, with its duration already resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HistoryVisit {
+    pub id: i64,
+    pub url: String,
+    pub visited_at: i64,
+    pub duration_sec: i64,
+    pub transition: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChromiumBrowser {
+    Chrome,
+    Chromium,
+    Brave,
+    Edge,
+    Vivaldi,
+    Arc,
+}
+
+impl ChromiumBrowser {
+    pub const ALL: [Self; 6] = [
+        Self::Chrome,
+        Self::Chromium,
+        Self::Brave,
+        Self::Edge,
+        Self::Vivaldi,
+        Self::Arc,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Chrome => "chrome",
+            Self::Chromium => "chromium",
+            Self::Brave => "brave",
+            Self::Edge => "edge",
+            Self::Vivaldi => "vivaldi",
+            Self::Arc => "arc",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|browser| browser.id().eq_ignore_ascii_case(id.trim()))
+    }
+
+    /// The browser's "User Data" directory, which holds one sub-directory per profile.
+    pub fn user_data_dir(self) -> Option<PathBuf> {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+
+        if cfg!(target_os = "macos") {
+            let support = home.join("Library").join("Application Support");
+            let relative: &[&str] = match self {
+                Self::Chrome => &["Google", "Chrome"],
+                Self::Chromium => &["Chromium"],
+                Self::Brave => &["BraveSoftware", "Brave-Browser"],
+                Self::Edge => &["Microsoft Edge"],
+                Self::Vivaldi => &["Vivaldi"],
+                Self::Arc => &["Arc", "User Data"],
+            };
+            Some(join_all(support, relative))
+        } else if cfg!(target_os = "windows") {
+            let local = dirs::data_local_dir()?;
+            let relative: &[&str] = match self {
+                Self::Chrome => &["Google", "Chrome", "User Data"],
+                Self::Chromium => &["Chromium", "User Data"],
+                Self::Brave => &["BraveSoftware", "Brave-Browser", "User Data"],
+                Self::Edge => &["Microsoft", "Edge", "User Data"],
+                Self::Vivaldi => &["Vivaldi", "User Data"],
+                Self::Arc => return None,
+            };
+            Some(join_all(local, relative))
+        } else {
+            let config = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+            let relative: &[&str] = match self {
+                Self::Chrome => &["google-chrome"],
+                Self::Chromium => &["chromium"],
+                Self::Brave => &["BraveSoftware", "Brave-Browser"],
+                Self::Edge => &["microsoft-edge"],
+                Self::Vivaldi => &["vivaldi"],
+                Self::Arc => return None,
+            };
+            Some(join_all(config, relative))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserProfile {
+    pub browser: ChromiumBrowser,
+    pub profile: String,
+}
+
+impl BrowserProfile {
+    /// Parses `brave:Default`-style entries; a bare profile name (`Default`) means Google Chrome.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let Some((browser, profile)) = spec.split_once(':') else {
+            return Ok(Self {
+                browser: ChromiumBrowser::Chrome,
+                profile: spec.trim().to_string(),
+            });
+        };
+        let browser = ChromiumBrowser::from_id(browser).with_context(|| {
+            let ids = ChromiumBrowser::ALL.map(ChromiumBrowser::id).join(", ");
+            format!(
+                "Unknown browser `{}` in profile `{}`; expected one of {ids}",
+                browser.trim(),
+                spec.trim()
+            )
+        })?;
+
+        Ok(Self {
+            browser,
+            profile: profile.trim().to_string(),
+        })
+    }
+
+    pub fn spec(&self) -> String {
+        format!("{}:{}", self.browser.id(), self.profile)
+    }
+
+    pub fn history_path(&self) -> Option<PathBuf> {
+        self.browser
+            .user_data_dir()
+            .map(|root| root.join(&self.profile).join("History"))
+    }
+}
+
+/// Imports visits newer than each configured profile's watermark. Safe to run repeatedly: visits
+/// are upserted by id and the watermark stays below visits whose duration is not yet final.
+pub fn sync_chrome_visits(
     config: &Config,
     rules: &CategoryRules,
+    database: &mut Database,
+) -> Result<usize> {
+    let now = Local::now().timestamp();
+
+    configured_profiles(config)
+        .map(|(profile, path)| {
+            import_profile_visits(
+                database,
+                rules,
+                &profile.spec(),
+                profile.browser.id(),
+                |after_id, since| {
+                    let history = collect_profile_visits(path.as_path(), after_id, since)?;
+                    let next = next_watermark(after_id, &history, now);
+                    Ok((history, next))
+                },
+            )
+        })
+        .sum()
+}
+
+/// Imports one past day of every configured profile, which the watermark sync never reads when the
+/// day precedes the profile's first sync.
+pub fn backfill_chrome_visits(
+    config: &Config,
+    rules: &CategoryRules,
+    database: &mut Database,
     date: NaiveDate,
-) -> Result<Vec<ChromeVisitInput>> {
-    let entries = config
+) -> Result<usize> {
+    configured_profiles(config)
+        .map(|(profile, path)| {
+            backfill_profile_visits(
+                database,
+                rules,
+                &profile.spec(),
+                profile.browser.id(),
+                date,
+                |since| collect_profile_visits(path.as_path(), 0, since),
+            )
+        })
+        .sum()
+}
+
+fn configured_profiles(config: &Config) -> impl Iterator<Item = (BrowserProfile, PathBuf)> + '_ {
+    config
         .chrome_profiles
         .iter()
-        .map(|profile| profile_history_path(profile))
-        .filter(|path| path.exists())
-        .map(|path| collect_profile_visits(path.as_path(), date))
-        .collect::<Result<Vec<_>>>()?
+        .filter_map(|spec| {
+            BrowserProfile::parse(spec)
+                .inspect_err(|error| warn!(error = %error, "skipping browser profile"))
+                .ok()
+        })
+        .filter_map(|profile| {
+            profile
+                .history_path()
+                .filter(|path| path.exists())
+                .map(|path| (profile, path))
+        })
+}
+
+/// Watermark bookkeeping shared by every browser. `read` receives the last imported visit id and
+/// the earliest visit time to consider, and returns the new visits plus the next watermark.
+pub(crate) fn import_profile_visits(
+    database: &mut Database,
+    rules: &CategoryRules,
+    profile_key: &str,
+    browser: &str,
+    read: impl FnOnce(i64, i64) -> Result<(Vec<HistoryVisit>, i64)>,
+) -> Result<usize> {
+    let today = Local::now().date_naive();
+    let watermark = database.browser_watermark(profile_key)?;
+
+    // A profile's first sync starts at today's midnight rather than replaying months of history,
+    // and takes over from any day-level total an older version stored for today.
+    let since = match watermark {
+        Some(_) => i64::MIN,
+        None => {
+            database.delete_day_aggregates(today, browser)?;
+            local_midnight(today)
+        }
+    };
+
+    let (history, next) = read(watermark.unwrap_or_default(), since)?;
+    store_history_visits(database, rules, profile_key, browser, history, Some(next))
+}
+
+/// Stores the visits of `date` regardless of the watermark, which it leaves alone. `read` receives
+/// the earliest visit time to consider. Visits already stored are only refreshed.
+pub(crate) fn backfill_profile_visits(
+    database: &mut Database,
+    rules: &CategoryRules,
+    profile_key: &str,
+    browser: &str,
+    date: NaiveDate,
+    read: impl FnOnce(i64) -> Result<Vec<HistoryVisit>>,
+) -> Result<usize> {
+    let next_midnight = local_midnight(date + Duration::days(1));
+    let history = read(local_midnight(date))?
         .into_iter()
-        .flatten()
-        .fold(HashMap::new(), |mut acc, (domain, duration_sec)| {
-            let entry = acc.entry(domain).or_insert(0_i64);
-            *entry += duration_sec;
-            acc
-        });
-
-    let visits = entries
+        .filter(|visit| visit.visited_at < next_midnight)
+        .collect::<Vec<_>>();
+    if history.is_empty() {
+        return Ok(0);
+    }
+
+    database.delete_day_aggregates(date, browser)?;
+    store_history_visits(database, rules, profile_key, browser, history, None)
+}
+
+fn store_history_visits(
+    database: &mut Database,
+    rules: &CategoryRules,
+    profile_key: &str,
+    browser: &str,
+    history: Vec<HistoryVisit>,
+    watermark: Option<i64>,
+) -> Result<usize> {
+    let visits = history
         .into_iter()
-        .map(|(domain, duration_sec)| ChromeVisitInput {
-            category: rules.categorize_domain(&domain),
-            domain,
-            duration_sec,
+        .filter(|visit| visit.duration_sec > 0)
+        .filter_map(|visit| {
+            let domain = extract_domain(&visit.url)?;
+            let date = Local
+                .timestamp_opt(visit.visited_at, 0)
+                .single()?
+                .date_naive();
+            Some(BrowserVisitInput {
+                source_visit_id: visit.id,
+                date,
+                visited_at: visit.visited_at,
+                browser: browser.to_string(),
+                category: rules.categorize_domain(&domain, Some(&visit.url)),
+                url: visit.url,
+                transition: visit.transition.to_string(),
+                domain,
+                duration_sec: visit.duration_sec,
+            })
         })
         .collect::<Vec<_>>();
 
-    Ok(visits)
+    database.upsert_browser_visits(profile_key, &visits, watermark)
 }
 
-pub fn profile_history_path(profile: &str) -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("Library")
-        .join("Application Support")
-        .join("Google")
-        .join("Chrome")
-        .join(profile)
-        .join("History")
+fn local_midnight(date: NaiveDate) -> i64 {
+    Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .map_or(i64::MIN, |midnight| midnight.timestamp())
 }
 
-fn collect_profile_visits(path: &Path, date: NaiveDate) -> Result<Vec<(String, i64)>> {
+pub fn profile_history_path(spec: &str) -> Option<PathBuf> {
+    BrowserProfile::parse(spec).ok()?.history_path()
+}
+
+fn join_all(base: PathBuf, parts: &[&str]) -> PathBuf {
+    parts.iter().fold(base, |path, part| path.join(part))
+}
+
+fn collect_profile_visits(path: &Path, after_id: i64, since: i64) -> Result<Vec<HistoryVisit>> {
     let temp_dir = std::env::temp_dir().join(format!(
         "OpenTracker-chrome-{}",
         chrono::Utc::now().timestamp_millis()
@@ -74,13 +326,13 @@ fn collect_profile_visits(path: &Path, date: NaiveDate) -> Result<Vec<(String, i
         )
     })?;
 
-    let result = read_history_visits(&temp_db, date);
+    let result = read_history_visits(&temp_db, after_id, since);
     let _ = fs::remove_dir_all(&temp_dir);
 
     result
 }
 
-fn read_history_visits(path: &Path, date: NaiveDate) -> Result<Vec<(String, i64)>> {
+fn read_history_visits(path: &Path, after_id: i64, since: i64) -> Result<Vec<HistoryVisit>> {
     let conn = Connection::open(path).with_context(|| {
         format!(
             "Failed to open temporary Chrome History DB: {}",
@@ -89,68 +341,292 @@ fn read_history_visits(path: &Path, date: NaiveDate) -> Result<Vec<(String, i64)
     })?;
 
     let query = r#"
-        SELECT urls.url, COALESCE(visits.visit_duration, 0)
+        SELECT visits.id, urls.url, (visits.visit_time / 1000000) - 11644473600, COALESCE(visits.visit_duration, 0), COALESCE(visits.transition, 0)
         FROM visits
         JOIN urls ON visits.url = urls.id
-        WHERE DATE(datetime((visits.visit_time / 1000000) - 11644473600, 'unixepoch', 'localtime')) = ?1
+        WHERE visits.id > ?1 AND (visits.visit_time / 1000000) - 11644473600 >= ?2
+        ORDER BY visits.id ASC
     "#;
 
-    let date_str = date.format("%Y-%m-%d").to_string();
     let mut statement = conn
         .prepare(query)
         .context("Failed to prepare Chrome History query")?;
 
-    let rows = statement
-        .query_map(params![date_str], |row| {
-            let raw_url: String = row.get(0)?;
-            let duration_raw: i64 = row.get(1)?;
+    let visits = statement
+        .query_map(params![after_id, since], |row| {
+            let duration_raw: i64 = row.get(3)?;
+            let transition_raw: i64 = row.get(4)?;
 
-            Ok((raw_url, duration_raw))
+            Ok(HistoryVisit {
+                id: row.get(0)?,
+                url: row.get(1)?,
+                visited_at: row.get(2)?,
+                duration_sec: (duration_raw / 1_000_000).max(0),
+                transition: transition_name(transition_raw),
+            })
         })?
         .collect::<Result<Vec<_>, _>>()
         .context("Failed to read Chrome History rows")?;
 
-    let visits = rows
-        .into_iter()
-        .filter_map(|(raw_url, duration_raw)| {
-            extract_domain(&raw_url).map(|domain| {
-                let seconds = (duration_raw / 1_000_000).max(0);
-                (domain, seconds)
-            })
-        })
-        .filter(|(_, seconds)| *seconds > 0)
-        .collect::<Vec<_>>();
-
     Ok(visits)
 }
 
-fn extract_domain(raw_url: &str) -> Option<String> {
+/// Chrome's core page transition lives in the low byte of `visits.transition`; the rest are qualifiers.
+fn transition_name(raw: i64) -> &'static str {
+    match raw & 0xFF {
+        0 => "link",
+        1 => "typed",
+        2 => "bookmark",
+        3 | 4 => "subframe",
+        5 | 6 => "generated",
+        7 => "form_submit",
+        8 => "reload",
+        9 | 10 => "keyword",
+        _ => "other",
+    }
+}
+
+/// Chrome writes `visit_duration` only when the user navigates away, so recent zero-duration
+/// visits are still open; the watermark stops just before the oldest of them to re-read it later.
+fn next_watermark(previous: i64, visits: &[HistoryVisit], now: i64) -> i64 {
+    let open_visit = visits
+        .iter()
+        .filter(|visit| visit.duration_sec == 0 && now - visit.visited_at < OPEN_VISIT_SECONDS)
+        .map(|visit| visit.id)
+        .min();
+
+    match open_visit {
+        Some(id) => (id - 1).max(previous),
+        None => visits
+            .iter()
+            .map(|visit| visit.id)
+            .max()
+            .unwrap_or(previous),
+    }
+}
+
+pub(crate) fn extract_domain(raw_url: &str) -> Option<String> {
     Url::parse(raw_url)
         .ok()
         .and_then(|url| url.host_str().map(ToOwned::to_owned))
         .map(|host| host.trim_start_matches("www.").to_lowercase())
 }
 
-pub fn detect_chrome_profiles() -> Vec<String> {
-    let chrome_root = dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("Library")
-        .join("Application Support")
-        .join("Google")
-        .join("Chrome");
-
-    fs::read_dir(chrome_root)
-        .ok()
+/// Every profile of every installed Chromium-family browser, as `browser:profile` specs.
+pub fn detect_browser_profiles() -> Vec<String> {
+    ChromiumBrowser::ALL
         .into_iter()
-        .flatten()
-        .filter_map(|entry| {
-            let path = entry.ok()?.path();
-            if path.is_dir() && path.join("History").exists() {
-                path.file_name()
-                    .map(|name| name.to_string_lossy().to_string())
-            } else {
-                None
-            }
+        .filter_map(|browser| browser.user_data_dir().map(|root| (browser, root)))
+        .flat_map(|(browser, root)| {
+            let mut profiles = fs::read_dir(root)
+                .ok()
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    if path.is_dir() && path.join("History").exists() {
+                        path.file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            profiles.sort();
+
+            profiles
+                .into_iter()
+                .map(move |profile| BrowserProfile { browser, profile }.spec())
         })
         .collect::<Vec<_>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BrowserProfile, ChromiumBrowser, HistoryVisit, backfill_profile_visits, extract_domain,
+        next_watermark, read_history_visits,
+    };
+    use crate::analyzer::categorizer::CategoryRules;
+    use crate::analyzer::report::build_daily_report;
+    use crate::db::{BrowserVisitInput, Database};
+    use chrono::{Local, NaiveDate, TimeZone};
+    use rusqlite::{Connection, params};
+    use std::collections::HashMap;
+
+    #[test]
+    fn parses_browser_profile_specs() {
+        assert_eq!(
+            BrowserProfile::parse("brave:Profile 1").expect("brave"),
+            BrowserProfile {
+                browser: ChromiumBrowser::Brave,
+                profile: "Profile 1".to_string(),
+            }
+        );
+        assert_eq!(
+            BrowserProfile::parse("Default").expect("default"),
+            BrowserProfile {
+                browser: ChromiumBrowser::Chrome,
+                profile: "Default".to_string(),
+            }
+        );
+        assert_eq!(
+            BrowserProfile::parse("Edge:Default").expect("edge").spec(),
+            "edge:Default"
+        );
+        assert!(BrowserProfile::parse("brvae:Default").is_err());
+    }
+
+    #[test]
+    fn watermark_waits_for_open_visits_and_upserts_are_idempotent() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let history_path = dir.path().join("History");
+        let conn = Connection::open(&history_path).expect("open history");
+        conn.execute_batch(
+            "CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT);
+             CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER, visit_duration INTEGER, transition INTEGER);
+             INSERT INTO urls VALUES (1, 'https://github.com/rust-lang'), (2, 'https://www.youtube.com/watch');",
+        )
+        .expect("schema");
+
+        let now = 1_771_400_000_i64;
+        let chrome_time = |unix: i64| (unix + 11_644_473_600) * 1_000_000;
+        let insert_visit = |id: i64, url: i64, at: i64, seconds: i64| {
+            conn.execute(
+                "INSERT OR REPLACE INTO visits VALUES (?1, ?2, ?3, ?4, 805306369)",
+                params![id, url, chrome_time(at), seconds * 1_000_000],
+            )
+            .expect("visit");
+        };
+        insert_visit(1, 1, now - 3_600, 600);
+        insert_visit(2, 2, now - 1_800, 0);
+        insert_visit(3, 1, now - 900, 300);
+
+        let first = read_history_visits(&history_path, 0, i64::MIN).expect("visits");
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[0].visited_at, now - 3_600);
+        assert_eq!(first[0].transition, "typed");
+        assert_eq!(next_watermark(0, &first, now), 1);
+        assert_eq!(next_watermark(0, &first, now + 24 * 3_600), 3);
+
+        let mut database = Database::open(&dir.path().join("activity.db")).expect("open db");
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+        let stored = |visits: &[HistoryVisit]| {
+            visits
+                .iter()
+                .filter(|visit| visit.duration_sec > 0)
+                .map(|visit| BrowserVisitInput {
+                    source_visit_id: visit.id,
+                    date,
+                    visited_at: visit.visited_at,
+                    browser: "chrome".to_string(),
+                    url: visit.url.clone(),
+                    transition: visit.transition.to_string(),
+                    domain: extract_domain(&visit.url).expect("domain"),
+                    category: "other".to_string(),
+                    duration_sec: visit.duration_sec,
+                })
+                .collect::<Vec<_>>()
+        };
+        database
+            .upsert_browser_visits("chrome:Default", &stored(&first), Some(1))
+            .expect("upsert");
+
+        // The tab closes, so the next pass re-reads visits 2 and 3 without double counting 3.
+        insert_visit(2, 2, now - 1_800, 120);
+        let second = read_history_visits(&history_path, 1, i64::MIN).expect("visits");
+        assert_eq!(next_watermark(1, &second, now), 3);
+        database
+            .upsert_browser_visits("chrome:Default", &stored(&second), Some(3))
+            .expect("upsert");
+
+        let total = database
+            .chrome_visits_for_date(date)
+            .expect("rows")
+            .iter()
+            .map(|row| row.duration_sec)
+            .sum::<i64>();
+        assert_eq!(total, 1_020);
+        assert_eq!(
+            database
+                .browser_watermark("chrome:Default")
+                .expect("watermark"),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn backfills_a_past_day_for_its_report() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let history_path = dir.path().join("History");
+        let conn = Connection::open(&history_path).expect("open history");
+        conn.execute_batch(
+            "CREATE TABLE urls (id INTEGER PRIMARY KEY, url TEXT);
+             CREATE TABLE visits (id INTEGER PRIMARY KEY, url INTEGER, visit_time INTEGER, visit_duration INTEGER, transition INTEGER);
+             INSERT INTO urls VALUES (1, 'https://github.com/rust-lang'), (2, 'https://www.youtube.com/watch');",
+        )
+        .expect("schema");
+        let date = NaiveDate::from_ymd_opt(2026, 2, 10).expect("date");
+        let at = |day: NaiveDate, hour| {
+            Local
+                .from_local_datetime(&day.and_hms_opt(hour, 0, 0).expect("time"))
+                .single()
+                .expect("local time")
+                .timestamp()
+        };
+        for (id, url, visited_at, seconds) in [
+            (1, 1, at(date, 9), 600),
+            (2, 2, at(date, 23), 300),
+            (3, 1, at(date.succ_opt().expect("next day"), 9), 900),
+        ] {
+            conn.execute(
+                "INSERT INTO visits VALUES (?1, ?2, ?3, ?4, 0)",
+                params![
+                    id,
+                    url,
+                    (visited_at + 11_644_473_600) * 1_000_000,
+                    seconds * 1_000_000
+                ],
+            )
+            .expect("visit");
+        }
+
+        // The profile's first sync happened later, so its watermark is past these visits.
+        let mut database = Database::open(&dir.path().join("activity.db")).expect("open db");
+        database
+            .upsert_browser_visits("chrome:Default", &[], Some(3))
+            .expect("watermark");
+        let rules = CategoryRules {
+            apps: HashMap::new(),
+            domains: HashMap::from([("github.com".to_string(), "development".to_string())]),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+        let backfill = |database: &mut Database| {
+            backfill_profile_visits(
+                database,
+                &rules,
+                "chrome:Default",
+                "chrome",
+                date,
+                |since| read_history_visits(&history_path, 0, since),
+            )
+            .expect("backfill")
+        };
+        assert_eq!(backfill(&mut database), 2);
+        assert_eq!(backfill(&mut database), 2);
+
+        let visits = database.web_visits_between(date, date).expect("visits");
+        let report = build_daily_report(date, &[], &visits, rules.definitions());
+        assert_eq!(report.chrome_history_seconds, 900);
+        assert_eq!(report.chrome_categories_seconds["development"], 600);
+        assert_eq!(report.top_domains_history[0].name, "github.com");
+        assert_eq!(
+            database
+                .browser_watermark("chrome:Default")
+                .expect("watermark"),
+            Some(3)
+        );
+    }
+}
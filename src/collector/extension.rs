@@ -0,0 +1,194 @@
+use crate::analyzer::categorizer::CategoryRules;
+use crate::collector::chrome::extract_domain;
+use crate::db::{BrowserEventInput, BrowserEventRow, BrowserVisitInput, Database};
+use anyhow::{Result, bail};
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Extension visits are stored under this profile prefix, e.g. `extension:chrome`.
+pub const PROFILE_PREFIX: &str = "extension:";
+
+pub const MAX_EVENTS_PER_BATCH: usize = 1_000;
+
+/// A focused tab with no further event (e.g. the browser crashed) is credited at most this long.
+const MAX_FOCUS_SPAN_SECONDS: i64 = 30 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrowserEventKind {
+    /// The user switched to a tab (or the browser window regained focus).
+    TabFocus,
+    /// The focused tab navigated to a new URL.
+    UrlChange,
+    /// The browser window lost focus; ends the current span without starting a new one.
+    WindowBlur,
+}
+
+impl BrowserEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TabFocus => "tab_focus",
+            Self::UrlChange => "url_change",
+            Self::WindowBlur => "window_blur",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrowserEventBatch {
+    pub events: Vec<BrowserEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrowserEvent {
+    /// Client-generated id used for deduplication; retried batches may resend it.
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: BrowserEventKind,
+    pub timestamp_ms: i64,
+    #[serde(default = "default_browser")]
+    pub browser: String,
+    #[serde(default)]
+    pub tab_id: Option<i64>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IngestSummary {
+    pub received: usize,
+    pub accepted: usize,
+    pub duplicates: usize,
+    pub visits: usize,
+}
+
+fn default_browser() -> String {
+    "chrome".to_string()
+}
+
+/// Checks the whole batch up front so a bad event rejects the batch without storing any of it.
+pub fn validate_batch(batch: &BrowserEventBatch) -> Result<()> {
+    if batch.events.len() > MAX_EVENTS_PER_BATCH {
+        bail!(
+            "Too many events in one batch: {} (max {MAX_EVENTS_PER_BATCH})",
+            batch.events.len()
+        );
+    }
+
+    batch
+        .events
+        .iter()
+        .enumerate()
+        .try_for_each(|(index, event)| {
+            let browser = event.browser.trim();
+            if browser.is_empty()
+                || !browser
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+            {
+                bail!("events[{index}]: invalid browser id `{}`", event.browser);
+            }
+            if event.timestamp_ms <= 0 {
+                bail!("events[{index}]: timestamp_ms must be a positive Unix time in milliseconds");
+            }
+            if event.kind != BrowserEventKind::WindowBlur
+                && event.url.as_deref().and_then(extract_domain).is_none()
+            {
+                bail!(
+                    "events[{index}]: {} requires a url with a host",
+                    event.kind.as_str()
+                );
+            }
+            Ok(())
+        })
+}
+
+/// Stores new events and rebuilds the visits they affect. Each `tab_focus`/`url_change` becomes a
+/// visit lasting until the browser's next event.
+pub fn ingest_events(
+    database: &mut Database,
+    rules: &CategoryRules,
+    batch: &BrowserEventBatch,
+) -> Result<IngestSummary> {
+    validate_batch(batch)?;
+
+    let mut summary = IngestSummary {
+        received: batch.events.len(),
+        ..IngestSummary::default()
+    };
+    let mut earliest_by_browser = BTreeMap::new();
+
+    for event in &batch.events {
+        let browser = event.browser.trim().to_lowercase();
+        let url = event.url.as_deref().map(str::trim).map(ToOwned::to_owned);
+        let input = BrowserEventInput {
+            event_key: event.id.clone().unwrap_or_else(|| {
+                format!(
+                    "{}:{}:{}:{}",
+                    event.kind.as_str(),
+                    event.timestamp_ms,
+                    event.tab_id.unwrap_or_default(),
+                    url.as_deref().unwrap_or_default()
+                )
+            }),
+            browser: browser.clone(),
+            event_type: event.kind.as_str().to_string(),
+            occurred_at_ms: event.timestamp_ms,
+            tab_id: event.tab_id,
+            url,
+        };
+
+        if database.insert_browser_event(&input)? {
+            summary.accepted += 1;
+            let earliest = earliest_by_browser
+                .entry(browser)
+                .or_insert(event.timestamp_ms);
+            *earliest = (*earliest).min(event.timestamp_ms);
+        } else {
+            summary.duplicates += 1;
+        }
+    }
+
+    for (browser, from_ms) in earliest_by_browser {
+        let events = database.browser_events_from(&browser, from_ms)?;
+        let visits = focus_visits(&browser, rules, &events);
+        summary.visits +=
+            database.upsert_browser_visits(&format!("{PROFILE_PREFIX}{browser}"), &visits, None)?;
+    }
+
+    Ok(summary)
+}
+
+fn focus_visits(
+    browser: &str,
+    rules: &CategoryRules,
+    events: &[BrowserEventRow],
+) -> Vec<BrowserVisitInput> {
+    events
+        .windows(2)
+        .filter(|pair| pair[0].event_type != BrowserEventKind::WindowBlur.as_str())
+        .filter_map(|pair| {
+            let (event, next) = (&pair[0], &pair[1]);
+            let url = event.url.clone()?;
+            let domain = extract_domain(&url)?;
+            let visited_at = event.occurred_at_ms / 1_000;
+            let duration_sec = ((next.occurred_at_ms - event.occurred_at_ms) / 1_000)
+                .clamp(0, MAX_FOCUS_SPAN_SECONDS);
+            let date = Local.timestamp_opt(visited_at, 0).single()?.date_naive();
+
+            (duration_sec > 0).then(|| BrowserVisitInput {
+                source_visit_id: event.id,
+                date,
+                visited_at,
+                browser: browser.to_string(),
+                category: rules.categorize_domain(&domain, Some(&url)),
+                url,
+                transition: event.event_type.clone(),
+                domain,
+                duration_sec,
+            })
+        })
+        .collect()
+}
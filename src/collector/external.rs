@@ -0,0 +1,209 @@
+use crate::analyzer::categorizer::CategoryRules;
+use crate::analyzer::project::TitleParser;
+use crate::db::{
+    ActivityInput, Database, EXPLICIT_CATEGORY_KEY, ExternalActivityOutcome, WINDOW_SOURCE,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const MAX_SPANS_PER_BATCH: usize = 1_000;
+
+/// Longest span a single record may cover; longer activity should be split by the client.
+const MAX_SPAN_SECONDS: i64 = 24 * 60 * 60;
+
+/// A span recorded by another tool (editor plugin, CLI wrapper, phone export, ...).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExternalSpan {
+    /// Client-supplied id; re-posting the same `(source, id)` is a no-op.
+    pub id: String,
+    pub source: String,
+    pub app: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// A category defined in `categories.json`; falls back to its rules when omitted.
+    #[serde(default)]
+    pub category: Option<String>,
+    pub start: SpanTime,
+    pub end: SpanTime,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+}
+
+/// Unix seconds or an RFC 3339 string.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SpanTime {
+    Unix(i64),
+    Text(String),
+}
+
+impl SpanTime {
+    fn timestamp(&self) -> Result<i64> {
+        match self {
+            Self::Unix(seconds) => Ok(*seconds),
+            Self::Text(raw) => DateTime::parse_from_rfc3339(raw.trim())
+                .map(|time| time.timestamp())
+                .with_context(|| format!("invalid RFC 3339 time `{raw}`")),
+        }
+    }
+}
+
+/// Accepts a single span, a JSON array of spans or `{"activities": [...]}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ExternalPayload {
+    Batch { activities: Vec<ExternalSpan> },
+    List(Vec<ExternalSpan>),
+    Single(ExternalSpan),
+}
+
+impl ExternalPayload {
+    pub fn into_spans(self) -> Vec<ExternalSpan> {
+        match self {
+            Self::Batch { activities } | Self::List(activities) => activities,
+            Self::Single(span) => vec![span],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpanResult {
+    pub id: String,
+    pub status: &'static str,
+    pub activity_id: i64,
+}
+
+#[derive(Debug, Clone)]
+pub enum IngestResult {
+    Stored(Vec<SpanResult>),
+    /// Nothing was stored because these spans overlap existing activity of the same source.
+    Conflicts(Vec<SpanResult>),
+}
+
+pub fn to_activity_input(
+    span: &ExternalSpan,
+    rules: &CategoryRules,
+    titles: &TitleParser,
+) -> Result<ActivityInput> {
+    let source = span.source.trim().to_lowercase();
+    if source.is_empty() || source == WINDOW_SOURCE {
+        bail!("source must be set and cannot be `{WINDOW_SOURCE}`");
+    }
+    if span.id.trim().is_empty() {
+        bail!("id must not be empty");
+    }
+    if span.app.trim().is_empty() {
+        bail!("app must not be empty");
+    }
+
+    let start = span.start.timestamp().context("start")?;
+    let end = span.end.timestamp().context("end")?;
+    if end <= start {
+        bail!("end must be after start");
+    }
+    if end - start > MAX_SPAN_SECONDS {
+        bail!("span is longer than {MAX_SPAN_SECONDS} seconds");
+    }
+    if span
+        .metadata
+        .as_ref()
+        .is_some_and(|metadata| !metadata.is_object())
+    {
+        bail!("metadata must be a JSON object");
+    }
+    let category = match span.category.as_deref() {
+        Some(category) => match rules.category_id(category) {
+            Some(id) => id,
+            None => bail!("unknown category `{category}`"),
+        },
+        None => rules.categorize_app(&span.app, span.title.as_deref()),
+    };
+    let mut metadata = match &span.metadata {
+        Some(Value::Object(metadata)) => metadata.clone(),
+        _ => Map::new(),
+    };
+    metadata.remove(EXPLICIT_CATEGORY_KEY);
+    if span.category.is_some() {
+        metadata.insert(EXPLICIT_CATEGORY_KEY.to_string(), Value::Bool(true));
+    }
+
+    Ok(ActivityInput {
+        recorded_at: end,
+        app_name: span.app.trim().to_string(),
+        window_title: span
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+            .map(ToOwned::to_owned),
+        category,
+        project: titles.project(&span.app, span.title.as_deref()),
+        duration_sec: end - start,
+        state: "active".to_string(),
+        source,
+        external_id: Some(span.id.trim().to_string()),
+        metadata: (span.metadata.is_some() || !metadata.is_empty())
+            .then(|| Value::Object(metadata).to_string()),
+    })
+}
+
+/// Validates a request's spans, naming the first invalid one.
+pub fn prepare_spans(spans: &[ExternalSpan], rules: &CategoryRules) -> Result<Vec<ActivityInput>> {
+    if spans.is_empty() {
+        bail!("No activities in request");
+    }
+    if spans.len() > MAX_SPANS_PER_BATCH {
+        bail!(
+            "Too many activities in one request: {} (max {MAX_SPANS_PER_BATCH})",
+            spans.len()
+        );
+    }
+
+    let titles = rules.title_parser()?;
+    spans
+        .iter()
+        .enumerate()
+        .map(|(index, span)| {
+            to_activity_input(span, rules, &titles)
+                .map_err(|error| anyhow!("activities[{index}]: {error:#}"))
+        })
+        .collect()
+}
+
+/// Stores prepared spans in one transaction; see `Database::insert_external_activities`.
+pub fn store_spans(
+    database: &mut Database,
+    spans: &[ExternalSpan],
+    inputs: &[ActivityInput],
+) -> Result<IngestResult> {
+    let outcomes = database.insert_external_activities(inputs)?;
+    let results = spans
+        .iter()
+        .zip(&outcomes)
+        .map(|(span, outcome)| {
+            let (status, activity_id) = match outcome {
+                ExternalActivityOutcome::Created(id) => ("created", *id),
+                ExternalActivityOutcome::Duplicate(id) => ("duplicate", *id),
+                ExternalActivityOutcome::Overlaps(id) => ("overlaps", *id),
+            };
+            SpanResult {
+                id: span.id.trim().to_string(),
+                status,
+                activity_id,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if results.iter().any(|result| result.status == "overlaps") {
+        return Ok(IngestResult::Conflicts(
+            results
+                .into_iter()
+                .filter(|result| result.status == "overlaps")
+                .collect(),
+        ));
+    }
+
+    Ok(IngestResult::Stored(results))
+}
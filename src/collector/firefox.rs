@@ -0,0 +1,382 @@
+use crate::analyzer::categorizer::CategoryRules;
+use crate::collector::chrome::{HistoryVisit, backfill_profile_visits, import_profile_visits};
+use crate::config::Config;
+use crate::db::Database;
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use rusqlite::{Connection, params};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const BROWSER_NAME: &str = "firefox";
+
+/// Firefox only records when a visit started, so each visit lasts until the next one, up to this cap.
+const MAX_ESTIMATED_VISIT_SECONDS: i64 = 10 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirefoxProfile {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_default: bool,
+}
+
+/// Imports new Firefox visits for every configured profile, tracked by `moz_historyvisits.id`.
+pub fn sync_firefox_visits(
+    config: &Config,
+    rules: &CategoryRules,
+    database: &mut Database,
+) -> Result<usize> {
+    let now = Local::now().timestamp();
+
+    config
+        .firefox_profiles
+        .iter()
+        .filter_map(|profile| {
+            profile_places_path(profile)
+                .filter(|path| path.exists())
+                .map(|path| (profile, path))
+        })
+        .map(|(profile, path)| {
+            import_profile_visits(
+                database,
+                rules,
+                &format!("{BROWSER_NAME}:{profile}"),
+                BROWSER_NAME,
+                |after_id, since| collect_profile_visits(path.as_path(), after_id, since, now),
+            )
+        })
+        .sum()
+}
+
+/// Imports one past day of every configured profile, for reports of days before its first sync.
+pub fn backfill_firefox_visits(
+    config: &Config,
+    rules: &CategoryRules,
+    database: &mut Database,
+    date: NaiveDate,
+) -> Result<usize> {
+    let now = Local::now().timestamp();
+
+    config
+        .firefox_profiles
+        .iter()
+        .filter_map(|profile| {
+            profile_places_path(profile)
+                .filter(|path| path.exists())
+                .map(|path| (profile, path))
+        })
+        .map(|(profile, path)| {
+            backfill_profile_visits(
+                database,
+                rules,
+                &format!("{BROWSER_NAME}:{profile}"),
+                BROWSER_NAME,
+                date,
+                |since| {
+                    collect_profile_visits(path.as_path(), 0, since, now)
+                        .map(|(history, _)| history)
+                },
+            )
+        })
+        .sum()
+}
+
+pub fn firefox_root() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+
+    if cfg!(target_os = "macos") {
+        home.join("Library")
+            .join("Application Support")
+            .join("Firefox")
+    } else if cfg!(target_os = "windows") {
+        dirs::config_dir()
+            .unwrap_or(home)
+            .join("Mozilla")
+            .join("Firefox")
+    } else {
+        home.join(".mozilla").join("firefox")
+    }
+}
+
+pub fn detect_firefox_profiles() -> Vec<FirefoxProfile> {
+    let root = firefox_root();
+    fs::read_to_string(root.join("profiles.ini"))
+        .map(|content| parse_profiles_ini(&content, &root))
+        .unwrap_or_default()
+}
+
+/// Resolves a configured profile: `default`, a profile name from `profiles.ini`, its directory
+/// name (e.g. `abcd1234.default-release`) or an absolute profile directory.
+pub fn profile_places_path(profile: &str) -> Option<PathBuf> {
+    let requested = profile.trim();
+    let direct = PathBuf::from(requested);
+    if direct.is_absolute() {
+        return Some(direct.join("places.sqlite"));
+    }
+
+    let profiles = detect_firefox_profiles();
+    profiles
+        .iter()
+        .find(|candidate| {
+            if requested.eq_ignore_ascii_case("default") {
+                candidate.is_default
+            } else {
+                candidate.name == requested
+                    || candidate.path.file_name().and_then(|name| name.to_str()) == Some(requested)
+            }
+        })
+        .map(|candidate| candidate.path.join("places.sqlite"))
+}
+
+fn parse_profiles_ini(content: &str, root: &Path) -> Vec<FirefoxProfile> {
+    let mut sections: Vec<(String, HashMap<String, String>)> = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        if let Some(section) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            sections.push((section.to_string(), HashMap::new()));
+        } else if let Some((key, value)) = line.split_once('=')
+            && let Some((_, values)) = sections.last_mut()
+        {
+            values.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    // Since Firefox 67 the profile each install launches is recorded in `[Install…] Default=`.
+    let install_default = sections
+        .iter()
+        .find(|(section, _)| section.starts_with("Install"))
+        .and_then(|(_, values)| values.get("Default").cloned());
+
+    sections
+        .iter()
+        .filter(|(section, _)| section.starts_with("Profile"))
+        .filter_map(|(_, values)| {
+            let raw_path = values.get("Path")?;
+            let path = if values.get("IsRelative").map(String::as_str) == Some("0") {
+                PathBuf::from(raw_path)
+            } else {
+                root.join(raw_path)
+            };
+            let is_default = match &install_default {
+                Some(default_path) => default_path == raw_path,
+                None => values.get("Default").map(String::as_str) == Some("1"),
+            };
+
+            Some(FirefoxProfile {
+                name: values
+                    .get("Name")
+                    .cloned()
+                    .unwrap_or_else(|| raw_path.clone()),
+                path,
+                is_default,
+            })
+        })
+        .collect()
+}
+
+fn collect_profile_visits(
+    path: &Path,
+    after_id: i64,
+    since: i64,
+    now: i64,
+) -> Result<(Vec<HistoryVisit>, i64)> {
+    let temp_dir = std::env::temp_dir().join(format!(
+        "OpenTracker-firefox-{}",
+        chrono::Utc::now().timestamp_millis()
+    ));
+
+    fs::create_dir_all(&temp_dir).with_context(|| {
+        format!(
+            "Failed to create Firefox temp directory: {}",
+            temp_dir.display()
+        )
+    })?;
+
+    // Firefox keeps places.sqlite open in WAL mode; recent visits only exist in the -wal file.
+    let temp_db = temp_dir.join("places.sqlite");
+    let copied = ["", "-wal"].iter().try_for_each(|suffix| {
+        let source = PathBuf::from(format!("{}{suffix}", path.display()));
+        if suffix.is_empty() || source.exists() {
+            let target = PathBuf::from(format!("{}{suffix}", temp_db.display()));
+            fs::copy(&source, &target).with_context(|| {
+                format!(
+                    "Failed to copy Firefox places DB: {} -> {}",
+                    source.display(),
+                    target.display()
+                )
+            })?;
+        }
+        Ok::<_, anyhow::Error>(())
+    });
+
+    let result = copied.and_then(|_| read_places_visits(&temp_db, after_id, since, now));
+    let _ = fs::remove_dir_all(&temp_dir);
+
+    result
+}
+
+/// Reads visits after `after_id` and estimates their durations. The latest visit stays open
+/// (and below the returned watermark) until a later visit or the estimate cap settles it.
+fn read_places_visits(
+    path: &Path,
+    after_id: i64,
+    since: i64,
+    now: i64,
+) -> Result<(Vec<HistoryVisit>, i64)> {
+    let conn = Connection::open(path).with_context(|| {
+        format!(
+            "Failed to open temporary Firefox places DB: {}",
+            path.display()
+        )
+    })?;
+
+    let query = r#"
+        SELECT moz_historyvisits.id, moz_places.url, moz_historyvisits.visit_date / 1000000, COALESCE(moz_historyvisits.visit_type, 0)
+        FROM moz_historyvisits
+        JOIN moz_places ON moz_historyvisits.place_id = moz_places.id
+        WHERE moz_historyvisits.id > ?1 AND moz_historyvisits.visit_date / 1000000 >= ?2
+        ORDER BY moz_historyvisits.visit_date ASC, moz_historyvisits.id ASC
+    "#;
+
+    let mut statement = conn
+        .prepare(query)
+        .context("Failed to prepare Firefox history query")?;
+
+    let rows = statement
+        .query_map(params![after_id, since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to read Firefox history rows")?;
+
+    let open_visit = rows
+        .last()
+        .filter(|(_, _, visited_at, _)| now - visited_at < MAX_ESTIMATED_VISIT_SECONDS)
+        .map(|(id, ..)| *id);
+
+    let visits = rows
+        .iter()
+        .enumerate()
+        .filter(|(_, (id, ..))| Some(*id) != open_visit)
+        .map(|(index, (id, url, visited_at, visit_type))| {
+            let until = rows.get(index + 1).map_or(now, |(_, _, next, _)| *next);
+            HistoryVisit {
+                id: *id,
+                url: url.clone(),
+                visited_at: *visited_at,
+                duration_sec: (until - visited_at).clamp(0, MAX_ESTIMATED_VISIT_SECONDS),
+                transition: transition_name(*visit_type),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let watermark = match open_visit {
+        Some(id) => (id - 1).max(after_id),
+        None => visits
+            .iter()
+            .map(|visit| visit.id)
+            .max()
+            .unwrap_or(after_id),
+    };
+
+    Ok((visits, watermark))
+}
+
+fn transition_name(visit_type: i64) -> &'static str {
+    match visit_type {
+        1 => "link",
+        2 => "typed",
+        3 => "bookmark",
+        4 | 8 => "subframe",
+        5 | 6 => "redirect",
+        7 => "download",
+        9 => "reload",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_profiles_ini, read_places_visits};
+    use rusqlite::{Connection, params};
+    use std::path::Path;
+
+    #[test]
+    fn parses_profiles_ini_with_install_default() {
+        let content = "[Profile1]\nName=work\nIsRelative=1\nPath=Profiles/abcd.work\n\n[Profile0]\nName=default-release\nIsRelative=0\nPath=/data/ff/xyz.default-release\nDefault=1\n\n[Install4F96D1932A9F858E]\nDefault=Profiles/abcd.work\nLocked=1\n";
+
+        let profiles = parse_profiles_ini(content, Path::new("/home/me/.mozilla/firefox"));
+
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "work");
+        assert_eq!(
+            profiles[0].path,
+            Path::new("/home/me/.mozilla/firefox/Profiles/abcd.work")
+        );
+        assert!(profiles[0].is_default);
+        assert_eq!(profiles[1].path, Path::new("/data/ff/xyz.default-release"));
+        assert!(!profiles[1].is_default);
+    }
+
+    #[test]
+    fn estimates_visit_durations_from_places_db() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let db_path = dir.path().join("places.sqlite");
+        let conn = Connection::open(&db_path).expect("open places");
+        conn.execute_batch(
+            "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT);
+             CREATE TABLE moz_historyvisits (id INTEGER PRIMARY KEY, place_id INTEGER, visit_date INTEGER, visit_type INTEGER);",
+        )
+        .expect("schema");
+
+        let start = 1_771_405_200_i64;
+        [
+            (1, "https://github.com/rust-lang", start, 2),
+            (2, "https://www.youtube.com/watch", start + 180, 1),
+            (1, "https://github.com/rust-lang", start + 3_600, 1),
+        ]
+        .iter()
+        .for_each(|(place_id, url, visited_at, visit_type)| {
+            conn.execute(
+                "INSERT OR IGNORE INTO moz_places (id, url) VALUES (?1, ?2)",
+                params![place_id, url],
+            )
+            .expect("place");
+            conn.execute(
+                "INSERT INTO moz_historyvisits (place_id, visit_date, visit_type) VALUES (?1, ?2, ?3)",
+                params![place_id, visited_at * 1_000_000, visit_type],
+            )
+            .expect("visit");
+        });
+
+        let durations = |visits: &[super::HistoryVisit]| {
+            visits
+                .iter()
+                .map(|visit| (visit.id, visit.duration_sec, visit.transition))
+                .collect::<Vec<_>>()
+        };
+
+        // The 10:00 visit is still open, so the watermark stops just before it.
+        let (visits, watermark) =
+            read_places_visits(&db_path, 0, i64::MIN, start + 3_660).expect("visits");
+        assert_eq!(
+            durations(&visits),
+            vec![(1, 180, "typed"), (2, 600, "link")]
+        );
+        assert_eq!(watermark, 2);
+
+        let (visits, watermark) =
+            read_places_visits(&db_path, watermark, i64::MIN, start + 7_200).expect("visits");
+        assert_eq!(durations(&visits), vec![(3, 600, "link")]);
+        assert_eq!(watermark, 3);
+    }
+}
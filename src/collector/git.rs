@@ -0,0 +1,214 @@
+use crate::config::Config;
+use crate::db::{Database, GitCommitInput};
+use anyhow::{Context, Result, bail};
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::warn;
+
+/// Separates commits in the `git log` output; fields within a header use `\x1f`.
+const RECORD_SEPARATOR: char = '\x1e';
+const FIELD_SEPARATOR: char = '\x1f';
+
+/// Stores the configured authors' commits of `date` from every repository in `git_repos`.
+/// Repositories that cannot be read are skipped with a warning.
+pub fn sync_git_commits(
+    config: &Config,
+    database: &mut Database,
+    date: NaiveDate,
+) -> Result<usize> {
+    let authors = config
+        .git_author_emails
+        .iter()
+        .map(|email| email.trim().to_lowercase())
+        .filter(|email| !email.is_empty())
+        .collect::<Vec<_>>();
+
+    let mut stored = 0;
+    for repo in discover_repositories(&config.git_repos) {
+        match repo_commits(&repo, &authors, date) {
+            Ok(commits) => stored += database.upsert_git_commits(&commits)?,
+            Err(error) => {
+                warn!(repo = %repo.display(), error = %error, "failed to read git commits")
+            }
+        }
+    }
+
+    Ok(stored)
+}
+
+/// Each configured path is either a repository or a folder whose direct children are repositories.
+fn discover_repositories(paths: &[PathBuf]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .flat_map(|path| {
+            if path.join(".git").exists() {
+                vec![path.clone()]
+            } else {
+                let mut children = fs::read_dir(path)
+                    .map(|entries| {
+                        entries
+                            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                            .filter(|child| child.join(".git").exists())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                children.sort();
+                children
+            }
+        })
+        .collect()
+}
+
+fn repo_commits(repo: &Path, authors: &[String], date: NaiveDate) -> Result<Vec<GitCommitInput>> {
+    let authors = if authors.is_empty() {
+        // Without configured emails, the repository's own `user.email` identifies the user.
+        let email = git(repo, &["config", "user.email"])
+            .map(|output| output.trim().to_lowercase())
+            .unwrap_or_default();
+        if email.is_empty() {
+            bail!("no git_author_emails configured and no user.email set in the repository");
+        }
+        vec![email]
+    } else {
+        authors.to_vec()
+    };
+
+    let start = local_midnight(date)?;
+    let end = local_midnight(date + Duration::days(1))?;
+    let output = git(
+        repo,
+        &[
+            "log",
+            "--branches",
+            "--source",
+            "--no-merges",
+            &format!("--since={}", start.to_rfc3339()),
+            &format!("--until={}", end.to_rfc3339()),
+            "--pretty=format:%x1e%H%x1f%ct%x1f%ae%x1f%S%x1f%s",
+            "--numstat",
+        ],
+    )?;
+
+    let name = repo
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| repo.display().to_string());
+
+    Ok(parse_log(&output)
+        .into_iter()
+        .filter(|commit| authors.contains(&commit.author_email))
+        .filter(|commit| (start.timestamp()..end.timestamp()).contains(&commit.committed_at))
+        .map(|commit| GitCommitInput {
+            repo: name.clone(),
+            repo_path: repo.display().to_string(),
+            ..commit
+        })
+        .collect())
+}
+
+/// Parses `git log --numstat` output in the format requested by `repo_commits`. `repo` fields
+/// are left empty for the caller to fill in.
+fn parse_log(output: &str) -> Vec<GitCommitInput> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let header = lines.next()?.split(FIELD_SEPARATOR).collect::<Vec<_>>();
+            let [sha, committed_at, author_email, source, summary] = header[..] else {
+                return None;
+            };
+
+            let (lines_added, lines_removed, files_changed) = lines
+                .filter_map(|line| {
+                    let mut columns = line.splitn(3, '\t');
+                    let added = columns.next()?;
+                    let removed = columns.next()?;
+                    columns.next()?;
+                    // Binary files report `-` for both counts.
+                    Some((added.parse().unwrap_or(0), removed.parse().unwrap_or(0)))
+                })
+                .fold(
+                    (0, 0, 0),
+                    |(added, removed, files), (line_added, line_removed)| {
+                        (added + line_added, removed + line_removed, files + 1)
+                    },
+                );
+
+            Some(GitCommitInput {
+                repo: String::new(),
+                repo_path: String::new(),
+                sha: sha.to_string(),
+                branch: source.trim_start_matches("refs/heads/").to_string(),
+                author_email: author_email.trim().to_lowercase(),
+                committed_at: committed_at.parse().ok()?,
+                summary: summary.to_string(),
+                lines_added,
+                lines_removed,
+                files_changed,
+            })
+        })
+        .collect()
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn local_midnight(date: NaiveDate) -> Result<chrono::DateTime<Local>> {
+    let midnight = date
+        .and_hms_opt(0, 0, 0)
+        .context("Failed to build midnight")?;
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .context("Failed to convert midnight to local time")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_log;
+
+    #[test]
+    fn parses_commits_with_numstat() {
+        let output = "\u{1e}eb080e6\u{1f}1771405200\u{1f}Dev@Example.com\u{1f}feature/login\u{1f}Add login form\n\
+                      12\t3\tsrc/login.rs\n\
+                      -\t-\tassets/logo.png\n\
+                      \n\
+                      \u{1e}946d5f4\u{1f}1771401600\u{1f}dev@example.com\u{1f}main\u{1f}Fix typo\n\
+                      1\t1\tREADME.md";
+
+        let commits = parse_log(output);
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].sha, "eb080e6");
+        assert_eq!(commits[0].branch, "feature/login");
+        assert_eq!(commits[0].author_email, "dev@example.com");
+        assert_eq!(commits[0].committed_at, 1_771_405_200);
+        assert_eq!(
+            (
+                commits[0].lines_added,
+                commits[0].lines_removed,
+                commits[0].files_changed
+            ),
+            (12, 3, 2)
+        );
+        assert_eq!(commits[1].summary, "Fix typo");
+        assert_eq!(commits[1].files_changed, 1);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::collector::window::{WindowBackend, env_is_set};
+use crate::config::Config;
+use anyhow::{Context, Result, bail};
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityState {
+    Active,
+    Idle,
+}
+
+impl ActivityState {
+    pub fn from_idle_seconds(idle_seconds: Option<u64>, threshold_seconds: u64) -> Self {
+        match idle_seconds {
+            Some(seconds) if threshold_seconds > 0 && seconds >= threshold_seconds => Self::Idle,
+            _ => Self::Active,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Idle => "idle",
+        }
+    }
+}
+
+pub trait IdleDetector: Send {
+    fn name(&self) -> &str;
+
+    /// Seconds since the last keyboard/mouse input, or `None` when it cannot be determined.
+    fn idle_seconds(&mut self) -> Result<Option<u64>>;
+}
+
+pub fn from_config(config: &Config) -> Box<dyn IdleDetector> {
+    // Replayed samples carry no idle information of their own, so the host's idle time is irrelevant.
+    if config.idle_threshold_seconds == 0 || config.window_source.starts_with("replay:") {
+        return Box::new(NoIdleDetector);
+    }
+
+    Box::new(SystemIdleDetector::new(IdleMethod::detect(
+        WindowBackend::detect(),
+        env_is_set("WAYLAND_DISPLAY"),
+    )))
+}
+
+pub struct NoIdleDetector;
+
+impl IdleDetector for NoIdleDetector {
+    fn name(&self) -> &str {
+        "none"
+    }
+
+    fn idle_seconds(&mut self) -> Result<Option<u64>> {
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleMethod {
+    IoKit,
+    Xprintidle,
+    Logind,
+}
+
+impl IdleMethod {
+    /// `xprintidle` only sees input to X clients, so it is used on a plain X11 session only; under
+    /// a Wayland compositor (which exports `DISPLAY` for XWayland) logind is asked instead.
+    pub fn detect(backend: WindowBackend, wayland: bool) -> Self {
+        match backend {
+            WindowBackend::MacOs => Self::IoKit,
+            WindowBackend::X11 if !wayland => Self::Xprintidle,
+            _ => Self::Logind,
+        }
+    }
+}
+
+/// Uses IOKit `HIDIdleTime` on macOS, `xprintidle` on X11 and logind `IdleHint` elsewhere on Linux.
+pub struct SystemIdleDetector {
+    method: IdleMethod,
+    /// Set once `loginctl` failed; without a session it fails on every tick, so it is reported once.
+    logind_failed: bool,
+}
+
+impl SystemIdleDetector {
+    pub fn new(method: IdleMethod) -> Self {
+        Self {
+            method,
+            logind_failed: false,
+        }
+    }
+}
+
+impl IdleDetector for SystemIdleDetector {
+    fn name(&self) -> &str {
+        match self.method {
+            IdleMethod::IoKit => "iokit",
+            IdleMethod::Xprintidle => "xprintidle",
+            IdleMethod::Logind => "logind",
+        }
+    }
+
+    fn idle_seconds(&mut self) -> Result<Option<u64>> {
+        if self.method == IdleMethod::IoKit {
+            let output = run_command("ioreg", &["-c", "IOHIDSystem", "-d", "4"])?;
+            return Ok(parse_hid_idle_time(&output));
+        }
+
+        if self.method == IdleMethod::Xprintidle
+            && let Ok(output) = run_command("xprintidle", &[])
+        {
+            return Ok(output
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .map(|millis| millis / 1000));
+        }
+
+        let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+        let output = run_command(
+            "loginctl",
+            &[
+                "show-session",
+                &session,
+                "-p",
+                "IdleHint",
+                "-p",
+                "IdleSinceHint",
+            ],
+        );
+        match output {
+            Ok(output) => Ok(parse_logind_idle(
+                &output,
+                chrono::Utc::now().timestamp_micros(),
+            )),
+            Err(_) if self.logind_failed => Ok(None),
+            Err(error) => {
+                self.logind_failed = true;
+                Err(error.context("idle time is not tracked until the collector restarts"))
+            }
+        }
+    }
+}
+
+/// Deterministic detector for tests: returns the queued idle durations in order.
+#[cfg(test)]
+pub struct ScriptedIdleDetector {
+    pub idle_seconds: std::collections::VecDeque<Option<u64>>,
+}
+
+#[cfg(test)]
+impl IdleDetector for ScriptedIdleDetector {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    fn idle_seconds(&mut self) -> Result<Option<u64>> {
+        Ok(self.idle_seconds.pop_front().flatten())
+    }
+}
+
+fn run_command(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to execute {program}"))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        bail!("{program} error: {stderr}");
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_hid_idle_time(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))
+        .and_then(|line| line.split('=').nth(1))
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(|nanos| nanos / 1_000_000_000)
+}
+
+fn parse_logind_idle(output: &str, now_micros: i64) -> Option<u64> {
+    let value_of = |key: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(str::trim)
+    };
+
+    match value_of("IdleHint")? {
+        "no" => Some(0),
+        "yes" => value_of("IdleSinceHint")
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|since| *since > 0)
+            .map(|since| ((now_micros - since).max(0) / 1_000_000) as u64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActivityState, IdleMethod, parse_hid_idle_time, parse_logind_idle};
+    use crate::collector::window::WindowBackend;
+
+    #[test]
+    fn classifies_state_by_threshold() {
+        assert_eq!(
+            ActivityState::from_idle_seconds(Some(600), 300),
+            ActivityState::Idle
+        );
+        assert_eq!(
+            ActivityState::from_idle_seconds(Some(30), 300),
+            ActivityState::Active
+        );
+        assert_eq!(
+            ActivityState::from_idle_seconds(None, 300),
+            ActivityState::Active
+        );
+        assert_eq!(
+            ActivityState::from_idle_seconds(Some(600), 0),
+            ActivityState::Active
+        );
+    }
+
+    #[test]
+    fn uses_logind_under_wayland_compositors() {
+        assert_eq!(
+            IdleMethod::detect(WindowBackend::X11, false),
+            IdleMethod::Xprintidle
+        );
+        assert_eq!(
+            IdleMethod::detect(WindowBackend::X11, true),
+            IdleMethod::Logind
+        );
+        assert_eq!(
+            IdleMethod::detect(WindowBackend::Sway, false),
+            IdleMethod::Logind
+        );
+        assert_eq!(
+            IdleMethod::detect(WindowBackend::MacOs, false),
+            IdleMethod::IoKit
+        );
+    }
+
+    #[test]
+    fn parses_platform_idle_outputs() {
+        let ioreg = "    | |   \"HIDIdleTime\" = 125000000000\n";
+        assert_eq!(parse_hid_idle_time(ioreg), Some(125));
+
+        let now = 1_771_400_000_000_000_i64;
+        let idle = format!("IdleHint=yes\nIdleSinceHint={}\n", now - 90_000_000);
+        assert_eq!(parse_logind_idle(&idle, now), Some(90));
+        assert_eq!(
+            parse_logind_idle("IdleHint=no\nIdleSinceHint=0\n", now),
+            Some(0)
+        );
+    }
+}
@@ -1,48 +1,275 @@
+pub mod activitywatch;
+pub mod calendar;
 pub mod chrome;
+pub mod extension;
+pub mod external;
+pub mod firefox;
+pub mod git;
+pub mod idle;
+pub mod shell;
+pub mod source;
+pub mod timesheet;
+pub mod wakatime;
 pub mod window;
 
 use crate::analyzer::categorizer::CategoryRules;
+use crate::analyzer::project::TitleParser;
+use crate::collector::idle::{ActivityState, IdleDetector};
+use crate::collector::source::WindowSource;
+use crate::collector::window::WindowSample;
 use crate::config::Config;
-use crate::db::Database;
+use crate::db::{ActivityInput, Database, WINDOW_SOURCE};
 use anyhow::Result;
 use std::sync::Arc;
 use tokio::time::{Duration, MissedTickBehavior, interval};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-pub async fn run_activity_collector(config: Arc<Config>, rules: Arc<CategoryRules>) -> Result<()> {
+/// A gap longer than this many polling intervals means the machine slept or the service was down.
+const MAX_SAMPLE_GAP_INTERVALS: i64 = 2;
+
+/// How often browser history is pulled into the database between nightly reports.
+const BROWSER_SYNC_SECONDS: u64 = 5 * 60;
+
+pub async fn run_activity_collector(
+    config: Arc<Config>,
+    rules: Arc<CategoryRules>,
+    mut source: Box<dyn WindowSource>,
+    mut idle: Box<dyn IdleDetector>,
+) -> Result<()> {
+    let titles = rules.title_parser()?;
     let mut ticker = interval(Duration::from_secs(config.polling_seconds));
     ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
     info!(
         polling_seconds = config.polling_seconds,
+        source = source.name(),
+        idle_detector = idle.name(),
+        idle_threshold_seconds = config.idle_threshold_seconds,
         "activity collector started"
     );
 
+    let mut previous_recorded_at = Database::open(&config.db_path)
+        .and_then(|database| database.latest_activity_timestamp())
+        .unwrap_or_default();
+
     loop {
         ticker.tick().await;
 
-        let sample = window::collect_active_window();
-        let category = rules.categorize_app(&sample.app_name);
+        let sample = match source.next_sample() {
+            Ok(Some(sample)) => sample,
+            Ok(None) => {
+                info!(source = source.name(), "window source exhausted");
+                std::future::pending::<()>().await;
+                continue;
+            }
+            Err(error) => {
+                error!(error = %error, source = source.name(), "failed to read window sample");
+                continue;
+            }
+        };
+
+        let idle_seconds = idle.idle_seconds().unwrap_or_else(|error| {
+            warn!(error = %error, detector = idle.name(), "failed to read idle time");
+            None
+        });
+        let state = ActivityState::from_idle_seconds(idle_seconds, config.idle_threshold_seconds);
+        let duration_sec = sample_duration_seconds(
+            previous_recorded_at,
+            sample.recorded_at,
+            config.polling_seconds,
+        );
 
         let inserted = Database::open(&config.db_path)
             .and_then(|database| {
-                database.insert_activity(
-                    sample.recorded_at,
-                    &sample.app_name,
-                    sample.window_title.as_deref(),
-                    &category,
-                    config.polling_seconds as i64,
-                )?;
+                let category =
+                    record_sample(&database, &rules, &titles, &sample, duration_sec, state)?;
                 database.cleanup_old_activities(config.retention_days)?;
-                Ok(())
+                Ok(category)
             })
             .map_err(|error| {
                 error!(error = %error, "failed to store activity sample");
                 error
             });
 
-        if inserted.is_ok() {
-            info!(app = sample.app_name, category, "activity sample captured");
+        if let Ok(category) = inserted {
+            previous_recorded_at = Some(sample.recorded_at);
+            info!(
+                app = sample.app_name,
+                category,
+                state = state.as_str(),
+                duration_sec,
+                "activity sample captured"
+            );
+        }
+    }
+}
+
+pub async fn run_browser_sync(config: Arc<Config>, rules: Arc<CategoryRules>) -> Result<()> {
+    let mut ticker = interval(Duration::from_secs(BROWSER_SYNC_SECONDS));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    info!(
+        interval_seconds = BROWSER_SYNC_SECONDS,
+        profiles = config.chrome_profiles.len() + config.firefox_profiles.len(),
+        "browser sync started"
+    );
+
+    loop {
+        ticker.tick().await;
+
+        let synced = Database::open(&config.db_path).and_then(|mut database| {
+            Ok(chrome::sync_chrome_visits(&config, &rules, &mut database)?
+                + firefox::sync_firefox_visits(&config, &rules, &mut database)?)
+        });
+        match synced {
+            Ok(0) => {}
+            Ok(imported) => info!(imported, "browser visits synced"),
+            Err(error) => error!(error = %error, "failed to sync browser history"),
+        }
+    }
+}
+
+/// Credits a sample with the real time elapsed since the previous one. After a sleep/suspend-sized
+/// gap only one nominal interval is credited and the rest of the gap stays untracked.
+pub fn sample_duration_seconds(
+    previous_recorded_at: Option<i64>,
+    recorded_at: i64,
+    polling_seconds: u64,
+) -> i64 {
+    let interval = polling_seconds as i64;
+
+    match previous_recorded_at.map(|previous| recorded_at - previous) {
+        Some(elapsed) if elapsed > 0 && elapsed <= interval * MAX_SAMPLE_GAP_INTERVALS => elapsed,
+        Some(elapsed) if elapsed <= 0 => 0,
+        _ => interval,
+    }
+}
+
+pub fn record_sample(
+    database: &Database,
+    rules: &CategoryRules,
+    titles: &TitleParser,
+    sample: &WindowSample,
+    duration_sec: i64,
+    state: ActivityState,
+) -> Result<String> {
+    let category = rules.categorize_app(&sample.app_name, sample.window_title.as_deref());
+
+    let activity = ActivityInput {
+        recorded_at: sample.recorded_at,
+        app_name: sample.app_name.clone(),
+        window_title: sample.window_title.clone(),
+        category: category.clone(),
+        project: titles.project(&sample.app_name, sample.window_title.as_deref()),
+        duration_sec,
+        state: state.as_str().to_string(),
+        source: WINDOW_SOURCE.to_string(),
+        external_id: None,
+        metadata: None,
+    };
+    database.insert_activity(&activity)?;
+    database.record_session_sample(&activity)?;
+
+    Ok(category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{record_sample, sample_duration_seconds};
+    use crate::analyzer::categorizer::CategoryRules;
+    use crate::analyzer::report::build_daily_report;
+    use crate::collector::idle::{ActivityState, IdleDetector, ScriptedIdleDetector};
+    use crate::collector::source::{ScriptedWindowSource, WindowSource};
+    use crate::collector::window::WindowSample;
+    use crate::db::Database;
+    use chrono::{Local, NaiveDate, TimeZone};
+    use std::collections::HashMap;
+
+    #[test]
+    fn scripted_samples_flow_into_daily_report() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let database = Database::open(&dir.path().join("activity.db")).expect("open db");
+        let rules = CategoryRules {
+            apps: HashMap::from([
+                ("code".to_string(), "development".to_string()),
+                ("slack".to_string(), "communication".to_string()),
+            ]),
+            domains: HashMap::new(),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+
+        let titles = rules.title_parser().expect("title parser");
+
+        let date = NaiveDate::from_ymd_opt(2026, 2, 18).expect("date");
+        let at = |hour, minute| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(hour, minute, 0).expect("time"))
+                .single()
+                .expect("local time")
+                .timestamp()
+        };
+        let sample = |recorded_at, app: &str| WindowSample {
+            recorded_at,
+            app_name: app.to_string(),
+            window_title: None,
+        };
+
+        let mut source = ScriptedWindowSource::new([
+            sample(at(9, 0), "Code"),
+            sample(at(9, 5), "Code"),
+            sample(at(9, 10), "Slack"),
+            sample(at(9, 15), "Code"),
+        ]);
+        let mut idle = ScriptedIdleDetector {
+            idle_seconds: [Some(5), None, Some(40), Some(900)].into(),
+        };
+        while let Some(sample) = source.next_sample().expect("sample") {
+            let idle_seconds = idle.idle_seconds().expect("idle seconds");
+            let state = ActivityState::from_idle_seconds(idle_seconds, 300);
+            record_sample(&database, &rules, &titles, &sample, 300, state).expect("record sample");
         }
+
+        let activities = database.activities_for_date(date).expect("activities");
+        let report = build_daily_report(date, &activities, &[], rules.definitions());
+
+        assert_eq!(report.active_window_seconds, 900);
+        assert_eq!(report.idle_seconds, 300);
+        assert_eq!(report.categories_seconds["development"], 600);
+        assert_eq!(report.categories_seconds["communication"], 300);
+        assert_eq!(report.top_apps[0].name, "Code");
+
+        let sessions = database
+            .sessions_between(at(8, 0), at(10, 0))
+            .expect("sessions");
+        let spans = sessions
+            .iter()
+            .map(|session| {
+                (
+                    session.app_name.as_str(),
+                    session.state.as_str(),
+                    session.duration_sec,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                ("Code", "active", 600),
+                ("Slack", "active", 300),
+                ("Code", "idle", 300)
+            ]
+        );
+        assert_eq!(sessions[0].started_at, at(8, 55));
+    }
+
+    #[test]
+    fn sample_duration_uses_elapsed_time_and_caps_gaps() {
+        assert_eq!(sample_duration_seconds(None, 1_000, 30), 30);
+        assert_eq!(sample_duration_seconds(Some(1_000), 1_032, 30), 32);
+        assert_eq!(sample_duration_seconds(Some(1_000), 1_060, 30), 60);
+        assert_eq!(sample_duration_seconds(Some(1_000), 4_600, 30), 30);
+        assert_eq!(sample_duration_seconds(Some(1_000), 1_000, 30), 0);
     }
 }
@@ -0,0 +1,142 @@
+use crate::analyzer::project::repository_root;
+use crate::db::{Database, ShellCommandInput};
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Commands longer than this are stored truncated.
+const MAX_COMMAND_CHARS: usize = 4_096;
+
+/// Placeholder in the snippets under `assets/shell` replaced with the ingest URL.
+const URL_PLACEHOLDER: &str = "__OPENTRACKER_URL__";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// One finished command, as posted (form-encoded) by the shell hooks.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShellCommandReport {
+    pub command: String,
+    pub cwd: String,
+    pub exit_code: i64,
+    /// Unix seconds, possibly fractional.
+    pub started_at: f64,
+    pub ended_at: f64,
+    #[serde(default)]
+    pub shell: Option<String>,
+}
+
+/// The hook snippet printed by `OpenTracker shell-init <shell>`.
+pub fn init_script(shell: Shell, api_port: u16) -> String {
+    let template = match shell {
+        Shell::Bash => include_str!("../../assets/shell/opentracker.bash"),
+        Shell::Zsh => include_str!("../../assets/shell/opentracker.zsh"),
+        Shell::Fish => include_str!("../../assets/shell/opentracker.fish"),
+    };
+
+    template.replace(
+        URL_PLACEHOLDER,
+        &format!("http://127.0.0.1:{api_port}/api/v1/ingest/shell"),
+    )
+}
+
+pub fn validate_report(report: &ShellCommandReport) -> Result<()> {
+    if report.command.trim().is_empty() {
+        bail!("command must not be empty");
+    }
+    if report.cwd.trim().is_empty() {
+        bail!("cwd must not be empty");
+    }
+    if !report.started_at.is_finite() || !report.ended_at.is_finite() || report.started_at <= 0.0 {
+        bail!("started_at and ended_at must be Unix times in seconds");
+    }
+    if report.ended_at < report.started_at {
+        bail!("ended_at must not be before started_at");
+    }
+    Ok(())
+}
+
+pub fn store_command(database: &Database, report: &ShellCommandReport) -> Result<i64> {
+    validate_report(report)?;
+
+    let command = report.command.trim();
+    let cwd = report.cwd.trim();
+    database.insert_shell_command(&ShellCommandInput {
+        command: command.chars().take(MAX_COMMAND_CHARS).collect(),
+        program: command_name(command),
+        cwd: cwd.to_string(),
+        // Commands run in subdirectories group under their repository.
+        project_dir: repository_root(Path::new(cwd)).display().to_string(),
+        exit_code: report.exit_code,
+        started_at: report.started_at,
+        ended_at: report.ended_at,
+        shell: report
+            .shell
+            .as_deref()
+            .map(str::trim)
+            .filter(|shell| !shell.is_empty())
+            .map(str::to_lowercase),
+    })
+}
+
+/// Short name used to group commands: the program plus its subcommand for tools that have them,
+/// skipping leading `VAR=value` assignments and wrappers such as `sudo` or `time`.
+pub fn command_name(command: &str) -> String {
+    let words = command
+        .split_whitespace()
+        .skip_while(|word| {
+            word.contains('=') && !word.starts_with('-')
+                || matches!(*word, "sudo" | "time" | "nice" | "env" | "exec" | "command")
+        })
+        .collect::<Vec<_>>();
+    let Some(program) = words.first() else {
+        return String::new();
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+
+    let mut arguments = words
+        .iter()
+        .skip(1)
+        .copied()
+        .filter(|word| !word.starts_with('-'))
+        .filter(|word| {
+            word.chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == ':')
+        });
+    match (program, arguments.next()) {
+        ("npm" | "pnpm" | "yarn" | "bun", Some("run")) => match arguments.next() {
+            Some(script) => format!("{program} run {script}"),
+            None => format!("{program} run"),
+        },
+        (
+            "cargo" | "npm" | "pnpm" | "yarn" | "bun" | "go" | "git" | "docker" | "kubectl"
+            | "make" | "gradle" | "gradlew" | "mvn" | "dotnet" | "swift" | "bazel" | "uv"
+            | "poetry" | "pip" | "terraform",
+            Some(subcommand),
+        ) => format!("{program} {subcommand}"),
+        _ => program.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::command_name;
+
+    #[test]
+    fn groups_commands_by_program_and_subcommand() {
+        assert_eq!(command_name("cargo build --release"), "cargo build");
+        assert_eq!(
+            command_name("RUST_LOG=debug cargo test -p core"),
+            "cargo test"
+        );
+        assert_eq!(command_name("sudo /usr/bin/make -j8 all"), "make all");
+        assert_eq!(command_name("ls -la src"), "ls");
+        assert_eq!(command_name("npm run build"), "npm run build");
+        assert_eq!(command_name("git log --format='%h'"), "git log");
+    }
+}
@@ -0,0 +1,148 @@
+use crate::collector::window::{self, WindowSample};
+use crate::config::{Config, expand_home};
+use anyhow::{Context, Result, bail};
+#[cfg(test)]
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::path::{Path, PathBuf};
+
+pub trait WindowSource: Send {
+    fn name(&self) -> &str;
+
+    /// Returns `None` once the source has no more samples to offer.
+    fn next_sample(&mut self) -> Result<Option<WindowSample>>;
+}
+
+pub fn from_config(config: &Config) -> Result<Box<dyn WindowSource>> {
+    let spec = config.window_source.trim();
+
+    match spec.split_once(':') {
+        None if spec.is_empty() || spec == "auto" => Ok(Box::new(SystemWindowSource)),
+        Some(("replay", path)) => Ok(Box::new(ReplayWindowSource::open(&expand_home(
+            path.trim(),
+        ))?)),
+        _ => bail!("Unsupported window source: {spec}. Use `auto` or `replay:<path.jsonl>`"),
+    }
+}
+
+/// Samples the frontmost window through the platform backend (osascript, sway, Hyprland, X11).
+pub struct SystemWindowSource;
+
+impl WindowSource for SystemWindowSource {
+    fn name(&self) -> &str {
+        window::active_backend().name()
+    }
+
+    fn next_sample(&mut self) -> Result<Option<WindowSample>> {
+        Ok(Some(window::collect_active_window()))
+    }
+}
+
+/// In-memory fake used to drive the collector pipeline deterministically in tests.
+#[cfg(test)]
+#[derive(Default)]
+pub struct ScriptedWindowSource {
+    samples: VecDeque<WindowSample>,
+}
+
+#[cfg(test)]
+impl ScriptedWindowSource {
+    pub fn new(samples: impl IntoIterator<Item = WindowSample>) -> Self {
+        Self {
+            samples: samples.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl WindowSource for ScriptedWindowSource {
+    fn name(&self) -> &str {
+        "scripted"
+    }
+
+    fn next_sample(&mut self) -> Result<Option<WindowSample>> {
+        Ok(self.samples.pop_front())
+    }
+}
+
+/// Feeds samples from a JSONL file, one serialized `WindowSample` per line.
+pub struct ReplayWindowSource {
+    path: PathBuf,
+    lines: Lines<BufReader<File>>,
+    line_number: usize,
+}
+
+impl ReplayWindowSource {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open replay file: {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            lines: BufReader::new(file).lines(),
+            line_number: 0,
+        })
+    }
+}
+
+impl WindowSource for ReplayWindowSource {
+    fn name(&self) -> &str {
+        "replay"
+    }
+
+    fn next_sample(&mut self) -> Result<Option<WindowSample>> {
+        for line in self.lines.by_ref() {
+            self.line_number += 1;
+            let line = line
+                .with_context(|| format!("Failed to read replay file: {}", self.path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let sample = serde_json::from_str(&line).with_context(|| {
+                format!(
+                    "Invalid replay sample at {}:{}",
+                    self.path.display(),
+                    self.line_number
+                )
+            })?;
+            return Ok(Some(sample));
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplayWindowSource, WindowSource, from_config};
+    use crate::config::Config;
+    use std::fs;
+
+    #[test]
+    fn replay_source_reads_jsonl_samples() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("samples.jsonl");
+        fs::write(
+            &path,
+            "{\"recorded_at\":1771372800,\"app_name\":\"Code\",\"window_title\":\"main.rs\"}\n\n{\"recorded_at\":1771373100,\"app_name\":\"Slack\"}\n",
+        )
+        .expect("write replay file");
+
+        let mut source = ReplayWindowSource::open(&path).expect("open replay");
+        let first = source.next_sample().expect("first").expect("sample");
+        let second = source.next_sample().expect("second").expect("sample");
+
+        assert_eq!(first.window_title.as_deref(), Some("main.rs"));
+        assert_eq!(second.app_name, "Slack");
+        assert_eq!(second.window_title, None);
+        assert!(source.next_sample().expect("end").is_none());
+
+        let config = Config {
+            window_source: format!("replay: {}", path.display()),
+            ..Config::default()
+        };
+        assert!(from_config(&config).is_ok());
+    }
+}
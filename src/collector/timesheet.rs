@@ -0,0 +1,701 @@
+use crate::analyzer::categorizer::CategoryRules;
+use crate::analyzer::project::TitleParser;
+use crate::db::{ActivityInput, Database, EXPLICIT_CATEGORY_KEY, ImportOutcome, WINDOW_SOURCE};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+
+/// Longest span a single row may cover, as for spans posted to the API.
+const MAX_ROW_SECONDS: i64 = 24 * 60 * 60;
+
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%m/%d/%Y", "%d/%m/%Y", "%d.%m.%Y"];
+const TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+const DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CsvFormat {
+    /// Toggl Track detailed report export
+    Toggl,
+    /// Clockify detailed report export
+    Clockify,
+    /// RescueTime activity export (hourly rows)
+    Rescuetime,
+    /// Any CSV, described by a `--mapping` file
+    Generic,
+}
+
+impl CsvFormat {
+    fn source(self) -> &'static str {
+        match self {
+            Self::Toggl => "toggl",
+            Self::Clockify => "clockify",
+            Self::Rescuetime => "rescuetime",
+            Self::Generic => "csv",
+        }
+    }
+
+    fn preset(self) -> CsvMapping {
+        let columns = |start: &str, start_time: &str, end: &str, end_time: &str| CsvMapping {
+            start: Some(start.to_string()),
+            start_time: Some(start_time.to_string()),
+            end: Some(end.to_string()),
+            end_time: Some(end_time.to_string()),
+            ..CsvMapping::default()
+        };
+        match self {
+            Self::Toggl => CsvMapping {
+                title: Some("Description".to_string()),
+                project: Some("Project".to_string()),
+                labels: vec!["Client".to_string(), "Tags".to_string()],
+                default_app: Some("Toggl".to_string()),
+                ..columns("Start date", "Start time", "End date", "End time")
+            },
+            Self::Clockify => CsvMapping {
+                title: Some("Description".to_string()),
+                project: Some("Project".to_string()),
+                labels: vec!["Client".to_string(), "Tags".to_string()],
+                default_app: Some("Clockify".to_string()),
+                ..columns("Start Date", "Start Time", "End Date", "End Time")
+            },
+            Self::Rescuetime => CsvMapping {
+                start: Some("Date".to_string()),
+                duration: Some("Time Spent (seconds)".to_string()),
+                app: Some("Activity".to_string()),
+                category: Some("Category".to_string()),
+                ..CsvMapping::default()
+            },
+            Self::Generic => CsvMapping::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationUnit {
+    #[default]
+    Seconds,
+    Minutes,
+    Hours,
+}
+
+/// Which columns hold what, read from the `--mapping` JSON file. Required for `generic`; for the
+/// other formats any field given overrides the built-in preset.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CsvMapping {
+    /// Start date and time, or only the date when `start_time` is set.
+    pub start: Option<String>,
+    pub start_time: Option<String>,
+    /// End date and time, or only the date when `end_time` is set. Without it `duration` is used.
+    pub end: Option<String>,
+    pub end_time: Option<String>,
+    /// `HH:MM:SS`, or a number in `duration_unit`.
+    pub duration: Option<String>,
+    pub duration_unit: Option<DurationUnit>,
+    pub app: Option<String>,
+    pub title: Option<String>,
+    pub project: Option<String>,
+    pub category: Option<String>,
+    /// More columns (client, tags, ...) looked up in `categories`.
+    pub labels: Vec<String>,
+    /// App name for rows without an `app` column.
+    pub default_app: Option<String>,
+    /// chrono format of dates (or date-times in a single column); common formats are tried otherwise.
+    pub date_format: Option<String>,
+    pub time_format: Option<String>,
+    pub delimiter: Option<char>,
+    /// `activities.source` for `generic` imports (default `csv`).
+    pub source: Option<String>,
+    /// Category of rows whose category, project, label or title equals a key (case-insensitive).
+    pub categories: HashMap<String, String>,
+}
+
+impl CsvMapping {
+    /// Fields set in `self` win over the `preset`.
+    fn over(self, preset: Self) -> Self {
+        Self {
+            start: self.start.or(preset.start),
+            start_time: self.start_time.or(preset.start_time),
+            end: self.end.or(preset.end),
+            end_time: self.end_time.or(preset.end_time),
+            duration: self.duration.or(preset.duration),
+            duration_unit: self.duration_unit.or(preset.duration_unit),
+            app: self.app.or(preset.app),
+            title: self.title.or(preset.title),
+            project: self.project.or(preset.project),
+            category: self.category.or(preset.category),
+            labels: if self.labels.is_empty() {
+                preset.labels
+            } else {
+                self.labels
+            },
+            default_app: self.default_app.or(preset.default_app),
+            date_format: self.date_format.or(preset.date_format),
+            time_format: self.time_format.or(preset.time_format),
+            delimiter: self.delimiter.or(preset.delimiter),
+            source: self.source.or(preset.source),
+            categories: self
+                .categories
+                .into_iter()
+                .chain(preset.categories)
+                .map(|(key, value)| (key.trim().to_lowercase(), value))
+                .collect(),
+        }
+    }
+}
+
+/// Rows mapped to activity spans, plus the rows that could not be read.
+#[derive(Debug, Default)]
+pub struct ParsedCsv {
+    pub activities: Vec<ActivityInput>,
+    /// `row N: reason`, numbered as in a spreadsheet (header is row 1).
+    pub errors: Vec<String>,
+}
+
+/// Per-day totals of an import, for the summary printed after (or instead of, with `--dry-run`)
+/// storing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DaySummary {
+    pub rows: usize,
+    pub created: usize,
+    pub duplicates: usize,
+    pub already_tracked: usize,
+    /// Time of the created spans by category.
+    pub categories_seconds: BTreeMap<String, i64>,
+}
+
+pub fn parse_csv(
+    content: &str,
+    format: CsvFormat,
+    mapping: Option<CsvMapping>,
+    rules: &CategoryRules,
+) -> Result<ParsedCsv> {
+    let mapping = mapping.unwrap_or_default().over(format.preset());
+    if mapping.start.is_none() {
+        bail!("The mapping needs a `start` column");
+    }
+    if mapping.end.is_none() && mapping.end_time.is_none() && mapping.duration.is_none() {
+        bail!("The mapping needs an `end`, `end_time` or `duration` column");
+    }
+    if mapping.app.is_none() && mapping.default_app.is_none() && mapping.title.is_none() {
+        bail!("The mapping needs an `app` or `title` column, or a `default_app`");
+    }
+    let source = mapping
+        .source
+        .as_deref()
+        .map(str::trim)
+        .filter(|source| !source.is_empty())
+        .unwrap_or(format.source())
+        .to_lowercase();
+    if source == WINDOW_SOURCE {
+        bail!("source cannot be `{WINDOW_SOURCE}`");
+    }
+    if let Some(category) = mapping
+        .categories
+        .values()
+        .find(|category| rules.category_id(category).is_none())
+    {
+        bail!("Unknown category `{category}` in the mapping; declare it in categories.json");
+    }
+    let titles = rules.title_parser()?;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(mapping.delimiter.map_or(b',', |delimiter| delimiter as u8))
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+    let headers = reader
+        .headers()
+        .context("Failed to read the CSV header")?
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.to_lowercase(), index))
+        .collect::<HashMap<_, _>>();
+    for column in mapping_columns(&mapping) {
+        if !headers.contains_key(&column.to_lowercase()) {
+            bail!("Column `{column}` not found in the CSV header");
+        }
+    }
+
+    let mut rows = RowMapper {
+        mapping: &mapping,
+        format,
+        source: &source,
+        rules,
+        titles: &titles,
+        hour_offsets: HashMap::new(),
+    };
+    let mut parsed = ParsedCsv::default();
+    for (index, record) in reader.records().enumerate() {
+        let row_number = index + 2;
+        let mapped = record.map_err(anyhow::Error::from).and_then(|record| {
+            rows.activity(&Row {
+                headers: &headers,
+                record: &record,
+            })
+        });
+        match mapped {
+            Ok(Some(activity)) => parsed.activities.push(activity),
+            Ok(None) => {}
+            Err(error) => parsed.errors.push(format!("row {row_number}: {error:#}")),
+        }
+    }
+
+    Ok(parsed)
+}
+
+struct Row<'a> {
+    headers: &'a HashMap<String, usize>,
+    record: &'a csv::StringRecord,
+}
+
+impl<'a> Row<'a> {
+    fn get(&self, column: Option<&str>) -> Option<&'a str> {
+        self.headers
+            .get(&column?.to_lowercase())
+            .and_then(|index| self.record.get(*index))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+struct RowMapper<'a> {
+    mapping: &'a CsvMapping,
+    format: CsvFormat,
+    source: &'a str,
+    rules: &'a CategoryRules,
+    titles: &'a TitleParser,
+    /// RescueTime rows only say which hour they belong to; rows of one hour are laid end to end.
+    hour_offsets: HashMap<NaiveDateTime, i64>,
+}
+
+impl RowMapper<'_> {
+    /// `None` for rows without any duration.
+    fn activity(&mut self, row: &Row) -> Result<Option<ActivityInput>> {
+        let mapping = self.mapping;
+        let column = |column: &Option<String>| row.get(column.as_deref());
+
+        let mut start = parse_time(
+            column(&mapping.start).context("start is empty")?,
+            column(&mapping.start_time),
+            mapping,
+        )
+        .context("invalid start")?;
+        let duration = match (column(&mapping.end), column(&mapping.end_time)) {
+            (None, None) => parse_duration(
+                column(&mapping.duration).context("end and duration are empty")?,
+                mapping.duration_unit.unwrap_or_default(),
+            )?,
+            (Some(end), end_time) => {
+                (parse_time(end, end_time, mapping).context("invalid end")? - start).num_seconds()
+            }
+            (None, Some(end_time)) => {
+                let end = start
+                    .date()
+                    .and_time(parse_time_of_day(end_time, mapping).context("invalid end")?);
+                // An end time alone that is earlier than the start lies on the next day.
+                let end = if end < start {
+                    end + Duration::days(1)
+                } else {
+                    end
+                };
+                (end - start).num_seconds()
+            }
+        };
+        if duration <= 0 {
+            return Ok(None);
+        }
+        if duration > MAX_ROW_SECONDS {
+            bail!("span is longer than {MAX_ROW_SECONDS} seconds");
+        }
+
+        let rescuetime_hour = (self.format == CsvFormat::Rescuetime)
+            .then(|| start.with_minute(0).and_then(|hour| hour.with_second(0)))
+            .flatten();
+        if let Some(hour) = rescuetime_hour {
+            let offset = self.hour_offsets.entry(hour).or_default();
+            start = hour + Duration::seconds(*offset);
+            *offset += duration;
+        }
+
+        let start_ts = local_timestamp(start)?;
+        let app_name = column(&mapping.app)
+            .or(mapping.default_app.as_deref())
+            .or(column(&mapping.title))
+            .context("app is empty")?
+            .to_string();
+        let window_title = column(&mapping.title).map(ToOwned::to_owned);
+        let project = column(&mapping.project).map(ToOwned::to_owned);
+        let labels = mapping
+            .labels
+            .iter()
+            .filter_map(|label| row.get(Some(label)))
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect::<Vec<_>>();
+        let explicit_category = explicit_category(
+            mapping,
+            self.rules,
+            column(&mapping.category),
+            project.as_deref(),
+            &labels,
+            window_title.as_deref(),
+        );
+        let metadata = explicit_category
+            .is_some()
+            .then(|| json!({ EXPLICIT_CATEGORY_KEY: true }).to_string());
+        let category = explicit_category
+            .unwrap_or_else(|| rule_category(self.rules, &app_name, window_title.as_deref()));
+
+        // The start of a RescueTime row depends on the rows before it, so it is not part of its id.
+        let external_id = match rescuetime_hour {
+            Some(hour) => format!(
+                "{}:{app_name}:{}",
+                local_timestamp(hour)?,
+                column(&mapping.category).unwrap_or_default()
+            ),
+            None => format!(
+                "{start_ts}:{duration}:{app_name}:{}",
+                window_title.as_deref().unwrap_or_default()
+            ),
+        };
+
+        Ok(Some(ActivityInput {
+            recorded_at: start_ts + duration,
+            external_id: Some(external_id),
+            project: project.or_else(|| self.titles.project(&app_name, window_title.as_deref())),
+            metadata,
+            app_name,
+            window_title,
+            category,
+            duration_sec: duration,
+            state: "active".to_string(),
+            source: self.source.to_string(),
+        }))
+    }
+}
+
+/// Stores the spans (unless `dry_run`) and summarizes them per local day.
+pub fn import_activities(
+    database: &mut Database,
+    activities: &[ActivityInput],
+    dry_run: bool,
+) -> Result<BTreeMap<NaiveDate, DaySummary>> {
+    let outcomes = database.import_activities(activities, dry_run)?;
+
+    Ok(activities
+        .iter()
+        .zip(outcomes)
+        .fold(BTreeMap::new(), |mut days, (activity, outcome)| {
+            let date = Local
+                .timestamp_opt(activity.recorded_at - activity.duration_sec, 0)
+                .single()
+                .map(|time| time.date_naive())
+                .unwrap_or_default();
+            let day: &mut DaySummary = days.entry(date).or_default();
+            day.rows += 1;
+            match outcome {
+                ImportOutcome::Created => {
+                    day.created += 1;
+                    *day.categories_seconds
+                        .entry(activity.category.clone())
+                        .or_default() += activity.duration_sec;
+                }
+                ImportOutcome::Duplicate => day.duplicates += 1,
+                ImportOutcome::AlreadyTracked => day.already_tracked += 1,
+            }
+            days
+        }))
+}
+
+fn mapping_columns(mapping: &CsvMapping) -> Vec<&str> {
+    [
+        &mapping.start,
+        &mapping.start_time,
+        &mapping.end,
+        &mapping.end_time,
+        &mapping.duration,
+        &mapping.app,
+        &mapping.title,
+        &mapping.project,
+        &mapping.category,
+    ]
+    .into_iter()
+    .flatten()
+    .chain(&mapping.labels)
+    .map(String::as_str)
+    .collect()
+}
+
+/// The category a row names itself; first match wins: the mapping's `categories` (by category,
+/// project, labels, then title), then a known category name in the category column.
+fn explicit_category(
+    mapping: &CsvMapping,
+    rules: &CategoryRules,
+    category: Option<&str>,
+    project: Option<&str>,
+    labels: &[&str],
+    title: Option<&str>,
+) -> Option<String> {
+    category
+        .into_iter()
+        .chain(project)
+        .chain(labels.iter().copied())
+        .chain(title)
+        .find_map(|value| mapping.categories.get(&value.to_lowercase()))
+        .map(|value| rules.normalize_category(value))
+        .or_else(|| category.and_then(|category| known_category(rules, category)))
+}
+
+/// Rows naming a domain (RescueTime websites) use the domain rules.
+fn rule_category(rules: &CategoryRules, app_name: &str, title: Option<&str>) -> String {
+    if app_name.contains('.') && !app_name.contains(' ') {
+        rules.categorize_domain(app_name, None)
+    } else {
+        rules.categorize_app(app_name, title)
+    }
+}
+
+/// A defined category name, or RescueTime's default category for it when that is defined.
+fn known_category(rules: &CategoryRules, raw: &str) -> Option<String> {
+    let category = match raw.trim().to_lowercase().as_str() {
+        "software development" | "design & composition" => "development",
+        "reference & learning" => "research",
+        "communication & scheduling" => "communication",
+        "social networking" => "sns",
+        _ => raw,
+    };
+    rules.category_id(category)
+}
+
+fn parse_time(date: &str, time: Option<&str>, mapping: &CsvMapping) -> Result<NaiveDateTime> {
+    let Some(time) = time else {
+        if let Ok(time) = DateTime::parse_from_rfc3339(date) {
+            return Ok(time.with_timezone(&Local).naive_local());
+        }
+        return mapping
+            .date_format
+            .as_deref()
+            .into_iter()
+            .chain(DATETIME_FORMATS)
+            .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+            .with_context(|| format!("unrecognized date and time `{date}`"));
+    };
+
+    let day = mapping
+        .date_format
+        .as_deref()
+        .into_iter()
+        .chain(DATE_FORMATS)
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .with_context(|| format!("unrecognized date `{date}`"))?;
+
+    Ok(day.and_time(parse_time_of_day(time, mapping)?))
+}
+
+fn parse_time_of_day(time: &str, mapping: &CsvMapping) -> Result<NaiveTime> {
+    mapping
+        .time_format
+        .as_deref()
+        .into_iter()
+        .chain(TIME_FORMATS)
+        .find_map(|format| NaiveTime::parse_from_str(time, format).ok())
+        .with_context(|| format!("unrecognized time `{time}`"))
+}
+
+fn parse_duration(raw: &str, unit: DurationUnit) -> Result<i64> {
+    if raw.contains(':') {
+        let parts = raw
+            .split(':')
+            .map(|part| part.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("invalid duration `{raw}`"))?;
+        return match parts[..] {
+            [hours, minutes, seconds] => Ok(hours * 3_600 + minutes * 60 + seconds),
+            [hours, minutes] => Ok(hours * 3_600 + minutes * 60),
+            _ => bail!("invalid duration `{raw}`"),
+        };
+    }
+
+    let amount = raw
+        .replace(',', ".")
+        .parse::<f64>()
+        .with_context(|| format!("invalid duration `{raw}`"))?;
+    let seconds = match unit {
+        DurationUnit::Seconds => amount,
+        DurationUnit::Minutes => amount * 60.0,
+        DurationUnit::Hours => amount * 3_600.0,
+    };
+    Ok(seconds.round() as i64)
+}
+
+fn local_timestamp(time: NaiveDateTime) -> Result<i64> {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|time| time.timestamp())
+        .with_context(|| format!("{time} does not exist in the local time zone"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvFormat, CsvMapping, import_activities, parse_csv};
+    use crate::analyzer::categorizer::CategoryRules;
+    use crate::db::Database;
+    use chrono::{Local, TimeZone};
+    use std::collections::HashMap;
+
+    #[test]
+    fn maps_presets_and_generic_columns() {
+        let rules = CategoryRules {
+            apps: HashMap::from([("code".to_string(), "development".to_string())]),
+            domains: HashMap::from([("github.com".to_string(), "development".to_string())]),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+        let at = |day, hour, minute| {
+            Local
+                .with_ymd_and_hms(2026, 2, day, hour, minute, 0)
+                .single()
+                .expect("local time")
+                .timestamp()
+        };
+
+        let toggl = parse_csv(
+            "User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags\n\
+             Dev,dev@example.com,Acme,Website,,Client call,Yes,2026-02-18,09:00:00,2026-02-18,09:45:00,00:45:00,meeting\n\
+             Dev,dev@example.com,,,,Broken,No,2026-02-18,not a time,2026-02-18,10:00:00,00:10:00,\n",
+            CsvFormat::Toggl,
+            Some(CsvMapping {
+                categories: HashMap::from([("Meeting".to_string(), "communication".to_string())]),
+                ..CsvMapping::default()
+            }),
+            &rules,
+        )
+        .expect("toggl");
+        assert_eq!(toggl.activities.len(), 1);
+        assert_eq!(toggl.errors.len(), 1);
+        assert!(toggl.errors[0].starts_with("row 3: invalid start"));
+        let call = &toggl.activities[0];
+        assert_eq!(
+            (call.app_name.as_str(), call.window_title.as_deref()),
+            ("Toggl", Some("Client call"))
+        );
+        assert_eq!(
+            (call.recorded_at, call.duration_sec),
+            (at(18, 9, 45), 2_700)
+        );
+        assert_eq!(call.category, "communication");
+        assert_eq!(
+            call.metadata.as_deref(),
+            Some(r#"{"category_explicit":true}"#)
+        );
+        assert_eq!(call.project.as_deref(), Some("Website"));
+        assert_eq!(call.source, "toggl");
+
+        let rescuetime = parse_csv(
+            "Date,Time Spent (seconds),Number of People,Activity,Category,Productivity\n\
+             2026-02-18 09:00:00,1200,1,Code,Editing & IDEs,2\n\
+             2026-02-18 09:00:00,600,1,github.com,General Software Development,2\n\
+             2026-02-18 09:00:00,300,1,slack,Communication & Scheduling,0\n",
+            CsvFormat::Rescuetime,
+            None,
+            &rules,
+        )
+        .expect("rescuetime");
+        let spans = rescuetime
+            .activities
+            .iter()
+            .map(|activity| {
+                (
+                    activity.recorded_at - activity.duration_sec,
+                    activity.category.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                (at(18, 9, 0), "development"),
+                (at(18, 9, 20), "development"),
+                (at(18, 9, 30), "communication"),
+            ]
+        );
+
+        let generic = parse_csv(
+            "When;Minutes;Tool;Kind\n18.02.2026 14:00;30;Figma;research\n",
+            CsvFormat::Generic,
+            Some(
+                serde_json::from_str::<CsvMapping>(
+                    r#"{"start": "When", "duration": "Minutes", "duration_unit": "minutes",
+                        "app": "Tool", "category": "Kind", "date_format": "%d.%m.%Y %H:%M",
+                        "delimiter": ";", "source": "sheet"}"#,
+                )
+                .expect("mapping"),
+            ),
+            &rules,
+        )
+        .expect("generic");
+        let design = &generic.activities[0];
+        assert_eq!(
+            (design.recorded_at, design.duration_sec),
+            (at(18, 14, 30), 1_800)
+        );
+        assert_eq!(
+            (design.category.as_str(), design.source.as_str()),
+            ("research", "sheet")
+        );
+    }
+
+    #[test]
+    fn reimports_reordered_rescuetime_rows_as_duplicates() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let mut database = Database::open(&dir.path().join("activity.db")).expect("open db");
+        let rules = CategoryRules {
+            apps: HashMap::new(),
+            domains: HashMap::new(),
+            rules: Vec::new(),
+            categories: Vec::new(),
+            project_extractors: Vec::new(),
+        };
+        let import = |database: &mut Database, rows: &str| {
+            let parsed = parse_csv(
+                &format!(
+                    "Date,Time Spent (seconds),Number of People,Activity,Category,Productivity\n{rows}"
+                ),
+                CsvFormat::Rescuetime,
+                None,
+                &rules,
+            )
+            .expect("rescuetime");
+            import_activities(database, &parsed.activities, false)
+                .expect("import")
+                .into_values()
+                .next()
+                .expect("day")
+        };
+
+        let first = import(
+            &mut database,
+            "2026-02-18 09:00:00,1200,1,Code,Editing & IDEs,2\n\
+             2026-02-18 09:00:00,600,1,github.com,General Software Development,2\n",
+        );
+        assert_eq!(first.created, 2);
+
+        let again = import(
+            &mut database,
+            "2026-02-18 09:00:00,600,1,github.com,General Software Development,2\n\
+             2026-02-18 09:00:00,1200,1,Code,Editing & IDEs,2\n",
+        );
+        assert_eq!((again.created, again.duplicates), (0, 2));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::db::{Database, HeartbeatInput};
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+pub const MAX_HEARTBEATS_PER_BATCH: usize = 1_000;
+
+/// A heartbeat in the shape WakaTime editor plugins send. Fields OpenTracker does not use
+/// (dependencies, cursor position, ...) are ignored.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Heartbeat {
+    /// File path, domain or app name depending on `type`.
+    pub entity: String,
+    #[serde(rename = "type", default = "default_entity_type")]
+    pub entity_type: String,
+    /// WakaTime activity category such as `coding`, `debugging` or `browsing`.
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Unix seconds with a fractional part.
+    pub time: f64,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub is_write: Option<bool>,
+    #[serde(default)]
+    pub lines: Option<i64>,
+    #[serde(default)]
+    pub lineno: Option<i64>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+fn default_entity_type() -> String {
+    "file".to_string()
+}
+
+/// Rejects the whole batch if any heartbeat is unusable, so nothing is stored half-way.
+pub fn validate_heartbeats(heartbeats: &[Heartbeat]) -> Result<()> {
+    if heartbeats.len() > MAX_HEARTBEATS_PER_BATCH {
+        bail!(
+            "Too many heartbeats in one request: {} (max {MAX_HEARTBEATS_PER_BATCH})",
+            heartbeats.len()
+        );
+    }
+
+    heartbeats
+        .iter()
+        .enumerate()
+        .try_for_each(|(index, heartbeat)| {
+            if heartbeat.entity.trim().is_empty() {
+                bail!("heartbeats[{index}]: entity must not be empty");
+            }
+            if !heartbeat.time.is_finite() || heartbeat.time <= 0.0 {
+                bail!("heartbeats[{index}]: time must be a positive Unix time in seconds");
+            }
+            Ok(())
+        })
+}
+
+/// Stores the heartbeats and returns how many were new; a heartbeat already stored for the same
+/// entity and time is skipped because plugins resend their offline queue.
+pub fn store_heartbeats(database: &mut Database, heartbeats: &[Heartbeat]) -> Result<usize> {
+    validate_heartbeats(heartbeats)?;
+
+    let inputs = heartbeats
+        .iter()
+        .map(|heartbeat| HeartbeatInput {
+            time: heartbeat.time,
+            entity: heartbeat.entity.trim().to_string(),
+            entity_type: heartbeat.entity_type.trim().to_lowercase(),
+            category: non_empty(heartbeat.category.as_deref()).map(|value| value.to_lowercase()),
+            project: non_empty(heartbeat.project.as_deref()),
+            branch: non_empty(heartbeat.branch.as_deref()),
+            language: non_empty(heartbeat.language.as_deref()),
+            is_write: heartbeat.is_write.unwrap_or(false),
+            lines: heartbeat.lines,
+            lineno: heartbeat.lineno,
+            user_agent: non_empty(heartbeat.user_agent.as_deref()),
+        })
+        .collect::<Vec<_>>();
+
+    database.insert_heartbeats(&inputs)
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+}
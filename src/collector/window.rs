@@ -1,43 +1,143 @@
+#[cfg(target_os = "linux")]
+mod hyprland;
+#[cfg(target_os = "linux")]
+mod sway;
+#[cfg(target_os = "linux")]
+mod x11;
+
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowSample {
     pub recorded_at: i64,
     pub app_name: String,
+    #[serde(default)]
     pub window_title: Option<String>,
 }
 
-pub fn collect_active_window() -> WindowSample {
-    #[cfg(target_os = "macos")]
-    {
-        let app_name = run_osascript("tell application \"System Events\" to get name of first application process whose frontmost is true")
-            .ok()
-            .filter(|value| !value.is_empty())
-            .unwrap_or_else(|| "Unknown".to_string());
-
-        let window_title = run_osascript("tell application \"System Events\" to tell (first application process whose frontmost is true) to get name of front window")
-            .ok()
-            .filter(|value| !value.is_empty());
-
-        return WindowSample {
-            recorded_at: Utc::now().timestamp(),
-            app_name,
-            window_title,
-        };
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActiveWindow {
+    pub app_name: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowBackend {
+    MacOs,
+    Sway,
+    Hyprland,
+    X11,
+    Unsupported,
+}
+
+impl WindowBackend {
+    /// Wayland compositors are checked before X11 because XWayland also exports `DISPLAY`.
+    pub fn detect() -> Self {
+        if cfg!(target_os = "macos") {
+            return Self::MacOs;
+        }
+
+        if cfg!(target_os = "linux") {
+            if env_is_set("SWAYSOCK") {
+                return Self::Sway;
+            }
+            if env_is_set("HYPRLAND_INSTANCE_SIGNATURE") {
+                return Self::Hyprland;
+            }
+            if env_is_set("DISPLAY") {
+                return Self::X11;
+            }
+        }
+
+        Self::Unsupported
     }
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        WindowSample {
-            recorded_at: Utc::now().timestamp(),
-            app_name: "UnsupportedPlatform".to_string(),
-            window_title: None,
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MacOs => "macos",
+            Self::Sway => "sway",
+            Self::Hyprland => "hyprland",
+            Self::X11 => "x11",
+            Self::Unsupported => "unsupported",
         }
     }
 }
 
+pub fn collect_active_window() -> WindowSample {
+    let recorded_at = Utc::now().timestamp();
+    let (app_name, window_title) = match WindowBackend::detect() {
+        WindowBackend::MacOs => collect_macos(),
+        WindowBackend::Unsupported => ("UnsupportedPlatform".to_string(), None),
+        backend => collect_linux(backend),
+    };
+
+    WindowSample {
+        recorded_at,
+        app_name,
+        window_title,
+    }
+}
+
+pub fn active_backend() -> WindowBackend {
+    WindowBackend::detect()
+}
+
 pub fn accessibility_window_access_available() -> bool {
-    collect_active_window().window_title.is_some()
+    active_backend() != WindowBackend::Unsupported && collect_active_window().window_title.is_some()
+}
+
+pub(crate) fn env_is_set(key: &str) -> bool {
+    std::env::var_os(key).is_some_and(|value| !value.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn collect_macos() -> (String, Option<String>) {
+    let app_name = run_osascript("tell application \"System Events\" to get name of first application process whose frontmost is true")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let window_title = run_osascript("tell application \"System Events\" to tell (first application process whose frontmost is true) to get name of front window")
+        .ok()
+        .filter(|value| !value.is_empty());
+
+    (app_name, window_title)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn collect_macos() -> (String, Option<String>) {
+    ("UnsupportedPlatform".to_string(), None)
+}
+
+#[cfg(target_os = "linux")]
+fn collect_linux(backend: WindowBackend) -> (String, Option<String>) {
+    let result = match backend {
+        WindowBackend::Sway => std::env::var_os("SWAYSOCK")
+            .map(std::path::PathBuf::from)
+            .ok_or_else(|| anyhow::anyhow!("SWAYSOCK is not set"))
+            .and_then(|path| sway::focused_window(&path)),
+        WindowBackend::Hyprland => std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+            .map_err(anyhow::Error::from)
+            .and_then(|signature| hyprland::active_window(&hyprland::socket_path(&signature))),
+        _ => x11::active_window(),
+    };
+
+    match result {
+        Ok(window) => (
+            window.app_name.unwrap_or_else(|| "Unknown".to_string()),
+            window.title,
+        ),
+        Err(error) => {
+            tracing::debug!(backend = backend.name(), error = %error, "failed to read active window");
+            ("Unknown".to_string(), None)
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn collect_linux(_backend: WindowBackend) -> (String, Option<String>) {
+    ("UnsupportedPlatform".to_string(), None)
 }
 
 #[cfg(target_os = "macos")]
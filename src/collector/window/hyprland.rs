@@ -0,0 +1,110 @@
+use super::ActiveWindow;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const IPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Deserialize)]
+struct ActiveWindowPayload {
+    #[serde(default)]
+    class: String,
+    #[serde(default, rename = "initialClass")]
+    initial_class: String,
+    #[serde(default)]
+    title: String,
+}
+
+/// Hyprland ≥ 0.40 keeps its sockets under `$XDG_RUNTIME_DIR/hypr`; older releases used `/tmp/hypr`.
+pub fn socket_path(instance_signature: &str) -> PathBuf {
+    let runtime_socket = std::env::var_os("XDG_RUNTIME_DIR").map(|runtime| {
+        PathBuf::from(runtime)
+            .join("hypr")
+            .join(instance_signature)
+            .join(".socket.sock")
+    });
+
+    runtime_socket
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| {
+            PathBuf::from("/tmp/hypr")
+                .join(instance_signature)
+                .join(".socket.sock")
+        })
+}
+
+pub fn active_window(socket_path: &Path) -> Result<ActiveWindow> {
+    let mut stream = UnixStream::connect(socket_path).with_context(|| {
+        format!(
+            "Failed to connect to Hyprland socket: {}",
+            socket_path.display()
+        )
+    })?;
+    stream.set_read_timeout(Some(IPC_TIMEOUT))?;
+    stream.set_write_timeout(Some(IPC_TIMEOUT))?;
+
+    stream
+        .write_all(b"j/activewindow")
+        .context("Failed to write Hyprland request")?;
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .context("Failed to read Hyprland response")?;
+
+    parse_active_window(&response)
+}
+
+fn parse_active_window(response: &str) -> Result<ActiveWindow> {
+    let payload: ActiveWindowPayload =
+        serde_json::from_str(response).context("Failed to parse Hyprland activewindow JSON")?;
+
+    let app_name = [payload.class, payload.initial_class]
+        .into_iter()
+        .find(|value| !value.is_empty());
+    let title = Some(payload.title).filter(|value| !value.is_empty());
+
+    Ok(ActiveWindow { app_name, title })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::active_window;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    #[test]
+    fn reads_active_window_from_fake_socket() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let socket_path = dir.path().join(".socket.sock");
+        let listener = UnixListener::bind(&socket_path).expect("bind fake hyprland socket");
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let mut request = [0_u8; 14];
+            stream.read_exact(&mut request).expect("read request");
+            assert_eq!(&request, b"j/activewindow");
+
+            stream
+                .write_all(br#"{"address":"0x1","class":"kitty","initialClass":"kitty","title":"nvim src/main.rs"}"#)
+                .expect("write response");
+        });
+
+        let window = active_window(&socket_path).expect("active window");
+        server.join().expect("server thread");
+
+        assert_eq!(window.app_name.as_deref(), Some("kitty"));
+        assert_eq!(window.title.as_deref(), Some("nvim src/main.rs"));
+    }
+
+    #[test]
+    fn empty_workspace_has_no_window() {
+        let window = super::parse_active_window("{}").expect("parse");
+        assert_eq!(window.app_name, None);
+        assert_eq!(window.title, None);
+    }
+}
@@ -0,0 +1,153 @@
+use super::ActiveWindow;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::Duration;
+
+const IPC_MAGIC: &[u8; 6] = b"i3-ipc";
+const IPC_GET_TREE: u32 = 4;
+const IPC_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn focused_window(socket_path: &Path) -> Result<ActiveWindow> {
+    let tree = request(socket_path, IPC_GET_TREE, b"")?;
+    let root: Value = serde_json::from_slice(&tree).context("Failed to parse sway tree JSON")?;
+
+    find_focused(&root)
+        .map(window_from_node)
+        .context("sway tree has no focused window")
+}
+
+fn request(socket_path: &Path, message_type: u32, payload: &[u8]) -> Result<Vec<u8>> {
+    let mut stream = UnixStream::connect(socket_path).with_context(|| {
+        format!(
+            "Failed to connect to sway IPC socket: {}",
+            socket_path.display()
+        )
+    })?;
+    stream.set_read_timeout(Some(IPC_TIMEOUT))?;
+    stream.set_write_timeout(Some(IPC_TIMEOUT))?;
+
+    let mut message = Vec::with_capacity(14 + payload.len());
+    message.extend_from_slice(IPC_MAGIC);
+    message.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+    message.extend_from_slice(&message_type.to_ne_bytes());
+    message.extend_from_slice(payload);
+    stream
+        .write_all(&message)
+        .context("Failed to write sway IPC request")?;
+
+    let mut header = [0_u8; 14];
+    stream
+        .read_exact(&mut header)
+        .context("Failed to read sway IPC response header")?;
+    if &header[..6] != IPC_MAGIC {
+        bail!("Invalid sway IPC response magic");
+    }
+
+    let length = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]) as usize;
+    let mut body = vec![0_u8; length];
+    stream
+        .read_exact(&mut body)
+        .context("Failed to read sway IPC response body")?;
+
+    Ok(body)
+}
+
+fn find_focused(node: &Value) -> Option<&Value> {
+    let is_window = node.get("pid").is_some_and(|pid| !pid.is_null());
+    if is_window && node.get("focused").and_then(Value::as_bool) == Some(true) {
+        return Some(node);
+    }
+
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node.get(*key).and_then(Value::as_array))
+        .flatten()
+        .find_map(find_focused)
+}
+
+fn window_from_node(node: &Value) -> ActiveWindow {
+    // Native Wayland clients expose `app_id`; XWayland clients only carry X11 class properties.
+    let app_name = node
+        .get("app_id")
+        .and_then(Value::as_str)
+        .or_else(|| {
+            node.pointer("/window_properties/class")
+                .and_then(Value::as_str)
+        })
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned);
+
+    let title = node
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned);
+
+    ActiveWindow { app_name, title }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IPC_GET_TREE, IPC_MAGIC, focused_window};
+    use serde_json::json;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+
+    #[test]
+    fn reads_focused_window_from_fake_ipc_server() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let socket_path = dir.path().join("sway-ipc.sock");
+        let listener = UnixListener::bind(&socket_path).expect("bind fake sway socket");
+
+        let tree = json!({
+            "type": "root",
+            "nodes": [{
+                "type": "output",
+                "nodes": [{
+                    "type": "workspace",
+                    "nodes": [
+                        { "pid": 10, "focused": false, "app_id": "firefox", "name": "Mozilla Firefox" }
+                    ],
+                    "floating_nodes": [
+                        {
+                            "pid": 11,
+                            "focused": true,
+                            "app_id": null,
+                            "name": "Slack | general",
+                            "window_properties": { "class": "Slack" }
+                        }
+                    ]
+                }]
+            }]
+        });
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let mut header = [0_u8; 14];
+            stream.read_exact(&mut header).expect("read header");
+            assert_eq!(&header[..6], IPC_MAGIC);
+            assert_eq!(
+                u32::from_ne_bytes([header[10], header[11], header[12], header[13]]),
+                IPC_GET_TREE
+            );
+
+            let body = tree.to_string().into_bytes();
+            let mut response = Vec::new();
+            response.extend_from_slice(IPC_MAGIC);
+            response.extend_from_slice(&(body.len() as u32).to_ne_bytes());
+            response.extend_from_slice(&IPC_GET_TREE.to_ne_bytes());
+            response.extend_from_slice(&body);
+            stream.write_all(&response).expect("write response");
+        });
+
+        let window = focused_window(&socket_path).expect("focused window");
+        server.join().expect("server thread");
+
+        assert_eq!(window.app_name.as_deref(), Some("Slack"));
+        assert_eq!(window.title.as_deref(), Some("Slack | general"));
+    }
+}
@@ -0,0 +1,215 @@
+use super::ActiveWindow;
+use anyhow::{Context, Result, bail};
+use std::process::Command;
+
+pub fn active_window() -> Result<ActiveWindow> {
+    active_window_on(None)
+}
+
+/// Reads the active window of `display`, or of `$DISPLAY` when `None`.
+fn active_window_on(display: Option<&str>) -> Result<ActiveWindow> {
+    let root = run_xprop(display, &["-root", "_NET_ACTIVE_WINDOW"])?;
+    let window_id = parse_active_window_id(&root)
+        .context("_NET_ACTIVE_WINDOW is not set on the root window")?;
+
+    let properties = run_xprop(
+        display,
+        &["-id", &window_id, "WM_CLASS", "_NET_WM_NAME", "WM_NAME"],
+    )?;
+
+    Ok(parse_window_properties(&properties))
+}
+
+fn run_xprop(display: Option<&str>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("xprop");
+    if let Some(display) = display {
+        command.env("DISPLAY", display);
+    }
+    let output = command
+        .args(args)
+        .output()
+        .context("Failed to execute xprop")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        bail!("xprop error: {stderr}");
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_active_window_id(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find(|line| property_name(line) == Some("_NET_ACTIVE_WINDOW"))
+        .and_then(|line| line.split_once('#'))
+        .and_then(|(_, ids)| ids.split(',').next())
+        .map(str::trim)
+        .filter(|id| {
+            id.starts_with("0x") && u64::from_str_radix(&id[2..], 16).is_ok_and(|value| value != 0)
+        })
+        .map(ToOwned::to_owned)
+}
+
+fn parse_window_properties(output: &str) -> ActiveWindow {
+    let wm_class = property_strings(output, "WM_CLASS");
+    // WM_CLASS is "instance", "class"; the class is the stable, human-facing name.
+    let app_name = wm_class
+        .get(1)
+        .or_else(|| wm_class.first())
+        .filter(|value| !value.is_empty())
+        .cloned();
+
+    let title = property_strings(output, "_NET_WM_NAME")
+        .into_iter()
+        .next()
+        .or_else(|| property_strings(output, "WM_NAME").into_iter().next())
+        .filter(|value| !value.is_empty());
+
+    ActiveWindow { app_name, title }
+}
+
+fn property_name(line: &str) -> Option<&str> {
+    let end = line.find(['(', ':', '=', ' '])?;
+    Some(&line[..end])
+}
+
+fn property_strings(output: &str, name: &str) -> Vec<String> {
+    output
+        .lines()
+        .find(|line| property_name(line) == Some(name))
+        .and_then(|line| line.split_once(" = "))
+        .map(|(_, value)| parse_quoted_list(value))
+        .unwrap_or_default()
+}
+
+fn parse_quoted_list(raw: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut chars = raw.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '"' {
+            continue;
+        }
+
+        let mut value = String::new();
+        while let Some(inner) = chars.next() {
+            match inner {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        value.push(escaped);
+                    }
+                }
+                '"' => break,
+                other => value.push(other),
+            }
+        }
+        values.push(value);
+    }
+
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{active_window_on, parse_active_window_id, parse_window_properties};
+    use crate::collector::window::ActiveWindow;
+    use std::process::{Child, Command};
+    use std::thread;
+    use std::time::Duration;
+
+    /// Kills the spawned X processes when the test ends, also on failure.
+    struct Processes(Vec<Child>);
+
+    impl Drop for Processes {
+        fn drop(&mut self) {
+            for child in &mut self.0 {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+    }
+
+    #[test]
+    fn parses_active_window_id() {
+        let output = "_NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007\n";
+        assert_eq!(parse_active_window_id(output).as_deref(), Some("0x3a00007"));
+        assert_eq!(
+            parse_active_window_id("_NET_ACTIVE_WINDOW(WINDOW): window id # 0x0\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_class_and_title() {
+        let output = concat!(
+            "WM_CLASS(STRING) = \"code\", \"Code\"\n",
+            "_NET_WM_NAME(UTF8_STRING) = \"main.rs - \\\"crate\\\" - Visual Studio Code\"\n",
+            "WM_NAME(STRING) = \"fallback\"\n",
+        );
+
+        assert_eq!(
+            parse_window_properties(output),
+            ActiveWindow {
+                app_name: Some("Code".to_string()),
+                title: Some("main.rs - \"crate\" - Visual Studio Code".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn falls_back_to_wm_name() {
+        let output = concat!(
+            "WM_CLASS(STRING) = \"xterm\", \"XTerm\"\n",
+            "_NET_WM_NAME:  not found.\n",
+            "WM_NAME(STRING) = \"user@host: ~\"\n",
+        );
+
+        let window = parse_window_properties(output);
+        assert_eq!(window.app_name.as_deref(), Some("XTerm"));
+        assert_eq!(window.title.as_deref(), Some("user@host: ~"));
+    }
+
+    /// Needs `Xvfb`, `openbox` (which maintains `_NET_ACTIVE_WINDOW`), `xmessage` and `xprop`:
+    /// `cargo test x11 -- --ignored`.
+    #[test]
+    #[ignore = "needs Xvfb, openbox, xmessage and xprop"]
+    fn reads_the_focused_window_under_xvfb() {
+        let display = ":97";
+        let spawn = |program: &str, args: &[&str]| {
+            Command::new(program)
+                .args(args)
+                .env("DISPLAY", display)
+                .spawn()
+                .unwrap_or_else(|error| panic!("failed to start {program}: {error}"))
+        };
+
+        let mut processes = Processes(vec![spawn("Xvfb", &[display, "-nolisten", "tcp"])]);
+        thread::sleep(Duration::from_secs(1));
+        processes.0.push(spawn("openbox", &[]));
+        thread::sleep(Duration::from_secs(1));
+        processes.0.push(spawn(
+            "xmessage",
+            &["-title", "OpenTracker Xvfb test", "hello"],
+        ));
+
+        let mut window = None;
+        for _ in 0..50 {
+            thread::sleep(Duration::from_millis(100));
+            window = active_window_on(Some(display)).ok();
+            if window.as_ref().and_then(|window| window.title.as_deref())
+                == Some("OpenTracker Xvfb test")
+            {
+                break;
+            }
+        }
+
+        assert_eq!(
+            window,
+            Some(ActiveWindow {
+                app_name: Some("Xmessage".to_string()),
+                title: Some("OpenTracker Xvfb test".to_string()),
+            })
+        );
+    }
+}
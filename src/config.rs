@@ -1,3 +1,4 @@
+use crate::collector::chrome::BrowserProfile;
 use anyhow::{Context, Result, anyhow, bail};
 use chrono::NaiveTime;
 use dirs::home_dir;
@@ -11,13 +12,24 @@ const APP_DIR: &str = ".OpenTracker";
 const CONFIG_FILE: &str = "config.json";
 const CATEGORIES_FILE: &str = "categories.json";
 const DEFAULT_REPORT_TIME: &str = "23:30";
-pub const FIXED_POLLING_SECONDS: u64 = 300;
+pub const DEFAULT_POLLING_SECONDS: u64 = 300;
+pub const MIN_POLLING_SECONDS: u64 = 15;
+pub const MAX_POLLING_SECONDS: u64 = 3600;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
     pub polling_seconds: u64,
+    pub window_source: String,
+    pub idle_threshold_seconds: u64,
     pub chrome_profiles: Vec<String>,
+    pub firefox_profiles: Vec<String>,
+    /// Repositories, or folders of repositories, scanned for commits at report time.
+    pub git_repos: Vec<PathBuf>,
+    /// Commits by these authors are reported; empty uses each repository's `user.email`.
+    pub git_author_emails: Vec<String>,
+    /// `.ics` files, or folders of them, whose events are imported at report time.
+    pub calendar_paths: Vec<PathBuf>,
     pub report_time: String,
     pub report_dir: PathBuf,
     pub categories_path: PathBuf,
@@ -39,8 +51,14 @@ impl Default for Config {
         let report_dir = default_report_dir();
 
         Self {
-            polling_seconds: FIXED_POLLING_SECONDS,
+            polling_seconds: DEFAULT_POLLING_SECONDS,
+            window_source: "auto".to_string(),
+            idle_threshold_seconds: 300,
             chrome_profiles: vec!["Default".to_string()],
+            firefox_profiles: Vec::new(),
+            git_repos: Vec::new(),
+            git_author_emails: Vec::new(),
+            calendar_paths: Vec::new(),
             report_time: DEFAULT_REPORT_TIME.to_string(),
             report_dir,
             categories_path: root.join(CATEGORIES_FILE),
@@ -74,7 +92,9 @@ impl Config {
 
         let mut config: Self = serde_json::from_str(&content)
             .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
-        config.polling_seconds = FIXED_POLLING_SECONDS;
+        config.polling_seconds = config
+            .polling_seconds
+            .clamp(MIN_POLLING_SECONDS, MAX_POLLING_SECONDS);
 
         Ok(config)
     }
@@ -141,11 +161,26 @@ impl Config {
                 let parsed = value
                     .parse::<u64>()
                     .map_err(|_| anyhow!("polling_seconds must be a number"))?;
-                if parsed != FIXED_POLLING_SECONDS {
-                    bail!("polling_seconds is fixed to 300 seconds (5 minutes)");
+                if !(MIN_POLLING_SECONDS..=MAX_POLLING_SECONDS).contains(&parsed) {
+                    bail!(
+                        "polling_seconds must be between {MIN_POLLING_SECONDS} and {MAX_POLLING_SECONDS}"
+                    );
                 }
                 self.polling_seconds = parsed;
             }
+            "window_source" => {
+                let source = value.trim();
+                self.window_source = match source.strip_prefix("replay:").map(str::trim) {
+                    Some(path) if !path.is_empty() => format!("replay:{path}"),
+                    None if source == "auto" => source.to_string(),
+                    _ => bail!("window_source must be `auto` or `replay:<path.jsonl>`"),
+                };
+            }
+            "idle_threshold_seconds" => {
+                self.idle_threshold_seconds = value
+                    .parse::<u64>()
+                    .map_err(|_| anyhow!("idle_threshold_seconds must be a number (0 disables)"))?;
+            }
             "report_time" => {
                 parse_hhmm(value)?;
                 self.report_time = value.to_string();
@@ -164,8 +199,43 @@ impl Config {
                 if profiles.is_empty() {
                     bail!("chrome_profiles requires at least one profile");
                 }
+                for profile in &profiles {
+                    BrowserProfile::parse(profile)?;
+                }
                 self.chrome_profiles = profiles;
             }
+            "firefox_profiles" => {
+                self.firefox_profiles = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect::<Vec<_>>();
+            }
+            "git_repos" => {
+                self.git_repos = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty())
+                    .map(expand_home)
+                    .collect::<Vec<_>>();
+            }
+            "git_author_emails" => {
+                self.git_author_emails = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty())
+                    .map(str::to_lowercase)
+                    .collect::<Vec<_>>();
+            }
+            "calendar_paths" => {
+                self.calendar_paths = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty())
+                    .map(expand_home)
+                    .collect::<Vec<_>>();
+            }
             "api_port" => {
                 self.api_port = value
                     .parse::<u16>()
@@ -203,7 +273,7 @@ impl Config {
             }
             _ => {
                 bail!(
-                    "Unsupported config key: {key}. Supported keys: polling_seconds|collector.interval_seconds, report_time|report.time, report_dir|report.dir, chrome_profiles|chrome.profiles, api_port|api.port, retention_days|retention.days, notify_on_report|report.notify, ai_enabled|ai.enabled, ai_api_key|ai.api_key, ai_api_base_url|ai.base_url, ai_model|ai.model, ai_timeout_seconds|ai.timeout_seconds"
+                    "Unsupported config key: {key}. Supported keys: polling_seconds|collector.interval_seconds, window_source|collector.source, idle_threshold_seconds|collector.idle_threshold_seconds, report_time|report.time, report_dir|report.dir, chrome_profiles|chrome.profiles|browser.profiles, firefox_profiles|firefox.profiles, git_repos|git.repos, git_author_emails|git.author_emails, calendar_paths|calendar.paths, api_port|api.port, retention_days|retention.days, notify_on_report|report.notify, ai_enabled|ai.enabled, ai_api_key|ai.api_key, ai_api_base_url|ai.base_url, ai_model|ai.model, ai_timeout_seconds|ai.timeout_seconds"
                 );
             }
         }
@@ -223,11 +293,29 @@ impl Config {
     pub fn get_value(&self, key: &str) -> Option<String> {
         match normalize_config_key(key) {
             "polling_seconds" => Some(self.polling_seconds.to_string()),
+            "window_source" => Some(self.window_source.clone()),
+            "idle_threshold_seconds" => Some(self.idle_threshold_seconds.to_string()),
             "report_time" => Some(self.report_time.clone()),
             "report_dir" => Some(self.report_dir.display().to_string()),
             "categories_path" => Some(self.categories_path.display().to_string()),
             "db_path" => Some(self.db_path.display().to_string()),
             "chrome_profiles" => Some(self.chrome_profiles.join(",")),
+            "firefox_profiles" => Some(self.firefox_profiles.join(",")),
+            "git_repos" => Some(
+                self.git_repos
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            "git_author_emails" => Some(self.git_author_emails.join(",")),
+            "calendar_paths" => Some(
+                self.calendar_paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
             "api_port" => Some(self.api_port.to_string()),
             "retention_days" => Some(self.retention_days.to_string()),
             "notify_on_report" => Some(self.notify_on_report.to_string()),
@@ -250,9 +338,15 @@ impl Config {
 fn normalize_config_key(key: &str) -> &str {
     match key {
         "polling_seconds" | "collector.interval_seconds" => "polling_seconds",
+        "window_source" | "collector.source" => "window_source",
+        "idle_threshold_seconds" | "collector.idle_threshold_seconds" => "idle_threshold_seconds",
         "report_time" | "report.time" => "report_time",
         "report_dir" | "report.dir" => "report_dir",
-        "chrome_profiles" | "chrome.profiles" => "chrome_profiles",
+        "chrome_profiles" | "chrome.profiles" | "browser.profiles" => "chrome_profiles",
+        "firefox_profiles" | "firefox.profiles" => "firefox_profiles",
+        "git_repos" | "git.repos" => "git_repos",
+        "git_author_emails" | "git.author_emails" => "git_author_emails",
+        "calendar_paths" | "calendar.paths" => "calendar_paths",
         "api_port" | "api.port" => "api_port",
         "retention_days" | "retention.days" => "retention_days",
         "notify_on_report" | "report.notify" => "notify_on_report",
@@ -1,7 +1,8 @@
 use crate::config::Config;
 use anyhow::{Context, Result, bail};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "macos")]
 use std::process::Command;
 
 #[derive(Debug, Clone)]
@@ -44,6 +45,7 @@ pub fn load(config: &Config) -> Result<()> {
 
         let _ = run_launchctl(["bootout", &domain, plist.to_string_lossy().as_ref()]);
         run_launchctl(["bootstrap", &domain, plist.to_string_lossy().as_ref()])?;
+        Ok(())
     }
 
     #[cfg(not(target_os = "macos"))]
@@ -51,8 +53,6 @@ pub fn load(config: &Config) -> Result<()> {
         let _ = config;
         bail!("launchd is only supported on macOS");
     }
-
-    Ok(())
 }
 
 pub fn unload(config: &Config) -> Result<()> {
@@ -67,6 +67,7 @@ pub fn unload(config: &Config) -> Result<()> {
             let label = format!("{domain}/{}", config.daemon_label);
             let _ = run_launchctl(["bootout", &label]);
         }
+        Ok(())
     }
 
     #[cfg(not(target_os = "macos"))]
@@ -74,8 +75,6 @@ pub fn unload(config: &Config) -> Result<()> {
         let _ = config;
         bail!("launchd is only supported on macOS");
     }
-
-    Ok(())
 }
 
 pub fn restart(config: &Config) -> Result<()> {
@@ -124,7 +123,7 @@ pub fn plist_path(config: &Config) -> Result<PathBuf> {
         .join(format!("{}.plist", config.daemon_label)))
 }
 
-fn render_plist(label: &str, binary: &PathBuf) -> String {
+fn render_plist(label: &str, binary: &Path) -> String {
     format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
@@ -1,5 +1,6 @@
 pub mod queries;
 
+use crate::config::MAX_POLLING_SECONDS;
 use anyhow::{Context, Result};
 use chrono::{Duration, Local, NaiveDate, TimeZone};
 use rusqlite::{Connection, params};
@@ -7,6 +8,22 @@ use serde::Serialize;
 use std::fs;
 use std::path::Path;
 
+/// `activities.source` of samples taken by the window collector.
+pub const WINDOW_SOURCE: &str = "window";
+
+/// `PRAGMA user_version` once window history has been coalesced into sessions.
+const SESSIONS_BACKFILLED_VERSION: i64 = 1;
+
+/// `activities.metadata` key set on spans that were given a category instead of getting one from
+/// the rules; recategorizing leaves them alone.
+pub const EXPLICIT_CATEGORY_KEY: &str = "category_explicit";
+
+/// Samples whose span starts within this many seconds of the open session's end extend it.
+const SESSION_JOIN_TOLERANCE_SECONDS: i64 = 5;
+
+/// Longest span a window sample can cover: two polling intervals at the slowest polling rate.
+const MAX_WINDOW_SAMPLE_SECONDS: i64 = 2 * MAX_POLLING_SECONDS as i64;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ActivityRow {
     pub id: i64,
@@ -14,14 +31,50 @@ pub struct ActivityRow {
     pub app_name: String,
     pub window_title: Option<String>,
     pub category: String,
+    /// Parsed from the window title; `None` when no extractor matched.
+    pub project: Option<String>,
+    pub duration_sec: i64,
+    pub state: String,
+    /// `window` for collector samples, otherwise the external tool that posted the span.
+    pub source: String,
+    pub external_id: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRow {
+    pub id: i64,
+    pub app_name: String,
+    pub window_title: Option<String>,
+    pub category: String,
+    pub state: String,
+    pub started_at: i64,
+    pub ended_at: i64,
     pub duration_sec: i64,
+    pub sample_count: i64,
 }
 
+/// Daily per-domain total, derived from the stored visits.
 #[derive(Debug, Clone, Serialize)]
 pub struct ChromeVisitRow {
+    pub date: String,
+    pub domain: String,
+    pub category: String,
+    pub duration_sec: i64,
+    pub browser: String,
+}
+
+/// One stored browser visit. `visited_at`, `url` and `transition` are `None` for day-level
+/// aggregates carried over from older databases.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebVisitRow {
     pub id: i64,
     pub date: String,
+    pub visited_at: Option<i64>,
+    pub browser: String,
     pub domain: String,
+    pub url: Option<String>,
+    pub transition: Option<String>,
     pub category: String,
     pub duration_sec: i64,
 }
@@ -35,13 +88,221 @@ pub struct ReportMetaRow {
     pub json_path: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct ActivityInput {
+    pub recorded_at: i64,
+    pub app_name: String,
+    pub window_title: Option<String>,
+    pub category: String,
+    pub project: Option<String>,
+    pub duration_sec: i64,
+    pub state: String,
+    pub source: String,
+    pub external_id: Option<String>,
+    /// Serialized JSON object.
+    pub metadata: Option<String>,
+}
+
+/// What happened to one externally supplied span in `insert_external_activities`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalActivityOutcome {
+    Created(i64),
+    /// The `(source, external_id)` pair was already stored; carries the existing row id.
+    Duplicate(i64),
+    /// The span overlaps this existing activity of the same source.
+    Overlaps(i64),
+}
+
+/// What happened to one span in `import_activities`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Created,
+    /// The `(source, external_id)` pair was stored by an earlier import.
+    Duplicate,
+    /// The span overlaps samples of the window collector, which already counted that time.
+    AlreadyTracked,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportCounts {
+    pub created: usize,
+    pub duplicates: usize,
+    pub already_tracked: usize,
+}
+
+impl ImportCounts {
+    pub fn tally(outcomes: &[ImportOutcome]) -> Self {
+        outcomes
+            .iter()
+            .fold(Self::default(), |mut counts, outcome| {
+                match outcome {
+                    ImportOutcome::Created => counts.created += 1,
+                    ImportOutcome::Duplicate => counts.duplicates += 1,
+                    ImportOutcome::AlreadyTracked => counts.already_tracked += 1,
+                }
+                counts
+            })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ChromeVisitInput {
+    pub browser: String,
     pub domain: String,
     pub category: String,
     pub duration_sec: i64,
 }
 
+/// A domain's visits of one day that AI enrichment moved out of the category the rules give it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisitCategoryChange {
+    pub browser: String,
+    pub domain: String,
+    /// The domain's rule category; visits that a URL rule put elsewhere keep theirs.
+    pub from: String,
+    pub to: String,
+}
+
+/// A single history visit imported incrementally, keyed by the browser's own `visits.id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserVisitInput {
+    pub source_visit_id: i64,
+    pub date: NaiveDate,
+    pub visited_at: i64,
+    pub browser: String,
+    pub url: String,
+    pub transition: String,
+    pub domain: String,
+    pub category: String,
+    pub duration_sec: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct BrowserEventInput {
+    pub browser: String,
+    pub event_key: String,
+    pub event_type: String,
+    pub occurred_at_ms: i64,
+    pub tab_id: Option<i64>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BrowserEventRow {
+    pub id: i64,
+    pub event_type: String,
+    pub occurred_at_ms: i64,
+    pub url: Option<String>,
+}
+
+/// An editor heartbeat received on the WakaTime-compatible endpoint.
+#[derive(Debug, Clone)]
+pub struct HeartbeatInput {
+    pub time: f64,
+    pub entity: String,
+    pub entity_type: String,
+    pub category: Option<String>,
+    pub project: Option<String>,
+    pub branch: Option<String>,
+    pub language: Option<String>,
+    pub is_write: bool,
+    pub lines: Option<i64>,
+    pub lineno: Option<i64>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HeartbeatRow {
+    pub id: i64,
+    pub time: f64,
+    pub entity: String,
+    pub entity_type: String,
+    pub category: Option<String>,
+    pub project: Option<String>,
+    pub branch: Option<String>,
+    pub language: Option<String>,
+    pub is_write: bool,
+}
+
+/// A terminal command reported by the shell hooks.
+#[derive(Debug, Clone)]
+pub struct ShellCommandInput {
+    pub command: String,
+    /// Grouping name such as `cargo build`, see `collector::shell::command_name`.
+    pub program: String,
+    pub cwd: String,
+    pub project_dir: String,
+    pub exit_code: i64,
+    pub started_at: f64,
+    pub ended_at: f64,
+    pub shell: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShellCommandRow {
+    pub id: i64,
+    pub command: String,
+    pub program: String,
+    pub cwd: String,
+    pub project_dir: String,
+    pub exit_code: i64,
+    pub started_at: f64,
+    pub ended_at: f64,
+}
+
+/// A commit read from a local repository by the git collector.
+#[derive(Debug, Clone)]
+pub struct GitCommitInput {
+    /// Repository folder name, used for display.
+    pub repo: String,
+    pub repo_path: String,
+    pub sha: String,
+    pub branch: String,
+    pub author_email: String,
+    pub committed_at: i64,
+    pub summary: String,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub files_changed: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitCommitRow {
+    pub id: i64,
+    pub repo: String,
+    pub sha: String,
+    pub branch: String,
+    pub author_email: String,
+    pub committed_at: i64,
+    pub summary: String,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub files_changed: i64,
+}
+
+/// One occurrence of a calendar event, expanded for a report date by the calendar importer.
+#[derive(Debug, Clone)]
+pub struct CalendarEventInput {
+    /// File stem of the `.ics` file the event came from.
+    pub calendar: String,
+    pub uid: String,
+    pub summary: String,
+    pub location: Option<String>,
+    pub starts_at: i64,
+    pub ends_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarEventRow {
+    pub id: i64,
+    pub calendar: String,
+    pub uid: String,
+    pub summary: String,
+    pub location: Option<String>,
+    pub starts_at: i64,
+    pub ends_at: i64,
+}
+
 pub struct Database {
     conn: Connection,
 }
@@ -70,33 +331,331 @@ impl Database {
                     .execute(statement, [])
                     .context("Failed to initialize schema")
                     .map(|_| ())
+            })?;
+
+        queries::column_migrations()
+            .iter()
+            .try_for_each(|migration| self.apply_column_migration(migration))?;
+
+        queries::index_statements()
+            .iter()
+            .try_for_each(|statement| {
+                self.conn
+                    .execute(statement, [])
+                    .context("Failed to create indexes")
+                    .map(|_| ())
+            })?;
+
+        self.backfill_sessions()
+    }
+
+    fn apply_column_migration(&self, migration: &queries::ColumnMigration) -> Result<()> {
+        let exists = self
+            .conn
+            .prepare(&format!("PRAGMA table_info({})", migration.table))?
+            .query_map([], |row| row.get::<_, String>(1))?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to inspect table columns")?
+            .iter()
+            .any(|column| column == migration.column);
+
+        if !exists {
+            self.conn
+                .execute(
+                    &format!(
+                        "ALTER TABLE {} ADD COLUMN {} {}",
+                        migration.table, migration.column, migration.definition
+                    ),
+                    [],
+                )
+                .with_context(|| {
+                    format!(
+                        "Failed to migrate column {}.{}",
+                        migration.table, migration.column
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn insert_activity(&self, activity: &ActivityInput) -> Result<()> {
+        insert_activity_row(&self.conn, activity).map(|_| ())
+    }
+
+    /// Inserts spans posted by external tools, all or nothing. Spans whose `(source, external_id)`
+    /// already exists are skipped; if any span overlaps another of its source, nothing is stored.
+    pub fn insert_external_activities(
+        &mut self,
+        activities: &[ActivityInput],
+    ) -> Result<Vec<ExternalActivityOutcome>> {
+        let transaction = self
+            .conn
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        let outcomes = activities
+            .iter()
+            .map(|activity| {
+                let existing = transaction
+                    .query_row(
+                        "SELECT id FROM activities WHERE source = ?1 AND external_id = ?2",
+                        params![&activity.source, &activity.external_id],
+                        |row| row.get::<_, i64>(0),
+                    )
+                    .ok();
+                if let Some(id) = existing {
+                    return Ok(ExternalActivityOutcome::Duplicate(id));
+                }
+
+                let started_at = activity.recorded_at - activity.duration_sec;
+                let overlapping = transaction
+                    .query_row(
+                        "SELECT id FROM activities
+                         WHERE source = ?1 AND recorded_at > ?2 AND recorded_at - duration_sec < ?3
+                         LIMIT 1",
+                        params![&activity.source, started_at, activity.recorded_at],
+                        |row| row.get::<_, i64>(0),
+                    )
+                    .ok();
+                if let Some(id) = overlapping {
+                    return Ok(ExternalActivityOutcome::Overlaps(id));
+                }
+
+                insert_activity_row(&transaction, activity).map(ExternalActivityOutcome::Created)
             })
+            .collect::<Result<Vec<_>>>()?;
+
+        if !outcomes
+            .iter()
+            .any(|outcome| matches!(outcome, ExternalActivityOutcome::Overlaps(_)))
+        {
+            transaction
+                .commit()
+                .context("Failed to commit external activities")?;
+        }
+
+        Ok(outcomes)
     }
 
-    pub fn insert_activity(
-        &self,
-        recorded_at: i64,
-        app_name: &str,
-        window_title: Option<&str>,
-        category: &str,
-        duration_sec: i64,
-    ) -> Result<()> {
+    /// Stores spans imported from another tracker's history. Unlike `insert_external_activities`,
+    /// spans that cannot be stored are skipped instead of failing the batch. With `dry_run` the
+    /// outcomes are computed but nothing is kept.
+    pub fn import_activities(
+        &mut self,
+        activities: &[ActivityInput],
+        dry_run: bool,
+    ) -> Result<Vec<ImportOutcome>> {
+        let transaction = self
+            .conn
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        let mut outcomes = Vec::with_capacity(activities.len());
+        for activity in activities {
+            let duplicate = transaction
+                .query_row(
+                    "SELECT 1 FROM activities WHERE source = ?1 AND external_id = ?2",
+                    params![&activity.source, &activity.external_id],
+                    |_| Ok(()),
+                )
+                .is_ok();
+            if duplicate {
+                outcomes.push(ImportOutcome::Duplicate);
+                continue;
+            }
+
+            let started_at = activity.recorded_at - activity.duration_sec;
+            let tracked = transaction
+                .query_row(
+                    "SELECT 1 FROM activities
+                     WHERE source = ?1 AND recorded_at > ?2 AND recorded_at < ?3 + ?4
+                       AND recorded_at - duration_sec < ?3
+                     LIMIT 1",
+                    params![
+                        WINDOW_SOURCE,
+                        started_at,
+                        activity.recorded_at,
+                        MAX_WINDOW_SAMPLE_SECONDS
+                    ],
+                    |_| Ok(()),
+                )
+                .is_ok();
+            if tracked {
+                outcomes.push(ImportOutcome::AlreadyTracked);
+                continue;
+            }
+
+            insert_activity_row(&transaction, activity)?;
+            outcomes.push(ImportOutcome::Created);
+        }
+
+        if !dry_run {
+            transaction
+                .commit()
+                .context("Failed to commit imported activities")?;
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Extends the most recent session when the activity continues it, otherwise opens a new one.
+    pub fn record_session_sample(&self, activity: &ActivityInput) -> Result<i64> {
+        let started_at = activity.recorded_at - activity.duration_sec.max(0);
+        let latest = self
+            .conn
+            .query_row(
+                "SELECT id, app_name, window_title, category, state, ended_at
+                 FROM sessions
+                 ORDER BY ended_at DESC, id DESC
+                 LIMIT 1",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                        row.get::<_, i64>(5)?,
+                    ))
+                },
+            )
+            .ok();
+
+        let continues = latest.as_ref().is_some_and(
+            |(_, app_name, window_title, category, state, ended_at)| {
+                *app_name == activity.app_name
+                    && *window_title == activity.window_title
+                    && *category == activity.category
+                    && *state == activity.state
+                    && started_at <= ended_at + SESSION_JOIN_TOLERANCE_SECONDS
+                    && activity.recorded_at >= *ended_at
+            },
+        );
+
+        if let Some((session_id, ..)) = latest.filter(|_| continues) {
+            self.conn
+                .execute(
+                    "UPDATE sessions SET ended_at = ?1, sample_count = sample_count + 1 WHERE id = ?2",
+                    params![activity.recorded_at, session_id],
+                )
+                .context("Failed to extend session")?;
+            return Ok(session_id);
+        }
+
         self.conn
             .execute(
-                "INSERT INTO activities (recorded_at, app_name, window_title, category, duration_sec) VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![recorded_at, app_name, window_title, category, duration_sec],
+                "INSERT INTO sessions (app_name, window_title, category, state, started_at, ended_at, sample_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1)",
+                params![
+                    &activity.app_name,
+                    &activity.window_title,
+                    &activity.category,
+                    &activity.state,
+                    started_at,
+                    activity.recorded_at
+                ],
             )
-            .context("Failed to insert activity")?;
+            .context("Failed to insert session")?;
 
-        Ok(())
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn sessions_for_date(&self, date: NaiveDate) -> Result<Vec<SessionRow>> {
+        let (from_ts, to_ts) = local_day_bounds(date)?;
+        self.sessions_between(from_ts, to_ts)
+    }
+
+    pub fn sessions_between(&self, from_ts: i64, to_ts: i64) -> Result<Vec<SessionRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, app_name, window_title, category, state, started_at, ended_at, sample_count
+             FROM sessions
+             WHERE ended_at >= ?1 AND started_at <= ?2
+             ORDER BY started_at ASC",
+        )?;
+
+        let rows = statement
+            .query_map(params![from_ts, to_ts], |row| {
+                let started_at: i64 = row.get(5)?;
+                let ended_at: i64 = row.get(6)?;
+                Ok(SessionRow {
+                    id: row.get(0)?,
+                    app_name: row.get(1)?,
+                    window_title: row.get(2)?,
+                    category: row.get(3)?,
+                    state: row.get(4)?,
+                    started_at,
+                    ended_at,
+                    duration_sec: ended_at - started_at,
+                    sample_count: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to query sessions")?;
+
+        Ok(rows)
+    }
+
+    /// Databases created before sessions existed get their window history coalesced once, as a
+    /// migration recorded in `PRAGMA user_version`.
+    fn backfill_sessions(&self) -> Result<()> {
+        let version: i64 = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .context("Failed to read schema version")?;
+        if version >= SESSIONS_BACKFILLED_VERSION {
+            return Ok(());
+        }
+
+        let has_sessions: bool = self
+            .conn
+            .query_row("SELECT EXISTS(SELECT 1 FROM sessions)", [], |row| {
+                row.get(0)
+            })
+            .context("Failed to inspect sessions")?;
+        if !has_sessions {
+            let mut statement = self.conn.prepare(
+                "SELECT recorded_at, app_name, window_title, category, project, duration_sec, state
+                 FROM activities
+                 WHERE source = ?1
+                 ORDER BY recorded_at ASC",
+            )?;
+            let activities = statement
+                .query_map(params![WINDOW_SOURCE], |row| {
+                    Ok(ActivityInput {
+                        recorded_at: row.get(0)?,
+                        app_name: row.get(1)?,
+                        window_title: row.get(2)?,
+                        category: row.get(3)?,
+                        project: row.get(4)?,
+                        duration_sec: row.get(5)?,
+                        state: row.get(6)?,
+                        source: WINDOW_SOURCE.to_string(),
+                        external_id: None,
+                        metadata: None,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()
+                .context("Failed to query window activities")?;
+            activities
+                .iter()
+                .try_for_each(|activity| self.record_session_sample(activity).map(|_| ()))?;
+        }
+
+        self.conn
+            .execute_batch(&format!(
+                "PRAGMA user_version = {SESSIONS_BACKFILLED_VERSION}"
+            ))
+            .context("Failed to record schema version")
     }
 
     pub fn latest_activity_timestamp(&self) -> Result<Option<i64>> {
         let timestamp = self
             .conn
             .query_row(
-                "SELECT recorded_at FROM activities ORDER BY recorded_at DESC LIMIT 1",
-                [],
+                "SELECT recorded_at FROM activities WHERE source = ?1 ORDER BY recorded_at DESC LIMIT 1",
+                params![WINDOW_SOURCE],
                 |row| row.get(0),
             )
             .ok();
@@ -106,7 +665,7 @@ impl Database {
 
     pub fn activities_between(&self, from_ts: i64, to_ts: i64) -> Result<Vec<ActivityRow>> {
         let mut statement = self.conn.prepare(
-            "SELECT id, recorded_at, app_name, window_title, category, duration_sec
+            "SELECT id, recorded_at, app_name, window_title, category, duration_sec, state, source, external_id, metadata, project
              FROM activities
              WHERE recorded_at >= ?1 AND recorded_at <= ?2
              ORDER BY recorded_at ASC",
@@ -121,6 +680,13 @@ impl Database {
                     window_title: row.get(3)?,
                     category: row.get(4)?,
                     duration_sec: row.get(5)?,
+                    state: row.get(6)?,
+                    source: row.get(7)?,
+                    external_id: row.get(8)?,
+                    metadata: row
+                        .get::<_, Option<String>>(9)?
+                        .and_then(|raw| serde_json::from_str(&raw).ok()),
+                    project: row.get(10)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()
@@ -130,33 +696,342 @@ impl Database {
     }
 
     pub fn activities_for_date(&self, date: NaiveDate) -> Result<Vec<ActivityRow>> {
-        let from = date
-            .and_hms_opt(0, 0, 0)
-            .context("Failed to build from timestamp")?;
-        let to = (date + Duration::days(1))
-            .and_hms_opt(0, 0, 0)
-            .context("Failed to build to timestamp")?;
-
-        let from_ts = Local
-            .from_local_datetime(&from)
-            .single()
-            .context("Failed to convert from timestamp to local time")?
-            .timestamp();
-        let to_ts = Local
-            .from_local_datetime(&to)
-            .single()
-            .context("Failed to convert to timestamp to local time")?
-            .timestamp()
-            - 1;
-
+        let (from_ts, to_ts) = local_day_bounds(date)?;
         self.activities_between(from_ts, to_ts)
     }
 
-    pub fn replace_chrome_visits_for_date(
+    /// Drops one browser's day-level aggregate rows for a date, once per-visit rows replace them.
+    pub fn delete_day_aggregates(&self, date: NaiveDate, browser: &str) -> Result<usize> {
+        let deleted = self
+            .conn
+            .execute(
+                "DELETE FROM chrome_visits WHERE date = ?1 AND browser = ?2 AND visited_at IS NULL",
+                params![date.format("%Y-%m-%d").to_string(), browser],
+            )
+            .context("Failed to delete day-level browser aggregates")?;
+
+        Ok(deleted)
+    }
+
+    /// Last `visits.id` imported for a browser profile, or `None` before its first sync.
+    pub fn browser_watermark(&self, profile: &str) -> Result<Option<i64>> {
+        let watermark = self
+            .conn
+            .query_row(
+                "SELECT last_visit_id FROM browser_sync_state WHERE profile = ?1",
+                params![profile],
+                |row| row.get(0),
+            )
+            .ok();
+
+        Ok(watermark)
+    }
+
+    /// Upserts visits by `(profile, source_visit_id)` and advances the profile's watermark, if
+    /// given, in one transaction. Re-imported visits only refresh their duration, keeping any
+    /// recategorization.
+    pub fn upsert_browser_visits(
+        &mut self,
+        profile: &str,
+        visits: &[BrowserVisitInput],
+        watermark: Option<i64>,
+    ) -> Result<usize> {
+        let transaction = self
+            .conn
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        visits.iter().try_for_each(|visit| {
+            transaction
+                .execute(
+                    "INSERT INTO chrome_visits (date, domain, category, duration_sec, browser, profile, source_visit_id, visited_at, url, transition)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                     ON CONFLICT(profile, source_visit_id)
+                     DO UPDATE SET duration_sec=excluded.duration_sec",
+                    params![
+                        visit.date.format("%Y-%m-%d").to_string(),
+                        &visit.domain,
+                        &visit.category,
+                        visit.duration_sec,
+                        &visit.browser,
+                        profile,
+                        visit.source_visit_id,
+                        visit.visited_at,
+                        &visit.url,
+                        &visit.transition
+                    ],
+                )
+                .context("Failed to upsert browser visit")
+                .map(|_| ())
+        })?;
+
+        if let Some(watermark) = watermark {
+            transaction
+                .execute(
+                    "INSERT INTO browser_sync_state (profile, last_visit_id, updated_at)
+                     VALUES (?1, ?2, ?3)
+                     ON CONFLICT(profile)
+                     DO UPDATE SET last_visit_id=excluded.last_visit_id, updated_at=excluded.updated_at",
+                    params![profile, watermark, Local::now().timestamp()],
+                )
+                .context("Failed to update browser sync watermark")?;
+        }
+
+        transaction
+            .commit()
+            .context("Failed to commit browser visits")?;
+        Ok(visits.len())
+    }
+
+    /// Stores an extension event; returns `false` when the same event was already received.
+    pub fn insert_browser_event(&self, event: &BrowserEventInput) -> Result<bool> {
+        let inserted = self
+            .conn
+            .execute(
+                "INSERT OR IGNORE INTO browser_events (browser, event_key, event_type, occurred_at_ms, tab_id, url)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    &event.browser,
+                    &event.event_key,
+                    &event.event_type,
+                    event.occurred_at_ms,
+                    event.tab_id,
+                    &event.url
+                ],
+            )
+            .context("Failed to insert browser event")?;
+
+        Ok(inserted > 0)
+    }
+
+    /// A browser's events from `from_ms` on, preceded by the last event before it (whose span
+    /// the new events may end).
+    pub fn browser_events_from(&self, browser: &str, from_ms: i64) -> Result<Vec<BrowserEventRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, event_type, occurred_at_ms, url
+             FROM browser_events
+             WHERE browser = ?1
+               AND occurred_at_ms >= COALESCE(
+                 (SELECT MAX(occurred_at_ms) FROM browser_events WHERE browser = ?1 AND occurred_at_ms < ?2),
+                 ?2
+               )
+             ORDER BY occurred_at_ms ASC, id ASC",
+        )?;
+
+        let rows = statement
+            .query_map(params![browser, from_ms], |row| {
+                Ok(BrowserEventRow {
+                    id: row.get(0)?,
+                    event_type: row.get(1)?,
+                    occurred_at_ms: row.get(2)?,
+                    url: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to query browser events")?;
+
+        Ok(rows)
+    }
+
+    /// Inserts heartbeats in one transaction and returns how many were new. Heartbeats with the
+    /// same entity and time as a stored one are ignored.
+    pub fn insert_heartbeats(&mut self, heartbeats: &[HeartbeatInput]) -> Result<usize> {
+        let transaction = self
+            .conn
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        let mut inserted = 0;
+        for heartbeat in heartbeats {
+            inserted += transaction
+                .execute(
+                    "INSERT OR IGNORE INTO heartbeats (time, entity, entity_type, category, project, branch, language, is_write, lines, lineno, user_agent)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    params![
+                        heartbeat.time,
+                        &heartbeat.entity,
+                        &heartbeat.entity_type,
+                        &heartbeat.category,
+                        &heartbeat.project,
+                        &heartbeat.branch,
+                        &heartbeat.language,
+                        heartbeat.is_write,
+                        heartbeat.lines,
+                        heartbeat.lineno,
+                        &heartbeat.user_agent
+                    ],
+                )
+                .context("Failed to insert heartbeat")?;
+        }
+
+        transaction
+            .commit()
+            .context("Failed to commit heartbeats")?;
+
+        Ok(inserted)
+    }
+
+    /// Heartbeats within the Unix seconds `from_ts..=to_ts` (inclusive of fractions), oldest first.
+    pub fn heartbeats_between(&self, from_ts: i64, to_ts: i64) -> Result<Vec<HeartbeatRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, time, entity, entity_type, category, project, branch, language, is_write
+             FROM heartbeats
+             WHERE time >= ?1 AND time < ?2 + 1
+             ORDER BY time ASC, id ASC",
+        )?;
+
+        let rows = statement
+            .query_map(params![from_ts, to_ts], |row| {
+                Ok(HeartbeatRow {
+                    id: row.get(0)?,
+                    time: row.get(1)?,
+                    entity: row.get(2)?,
+                    entity_type: row.get(3)?,
+                    category: row.get(4)?,
+                    project: row.get(5)?,
+                    branch: row.get(6)?,
+                    language: row.get(7)?,
+                    is_write: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to query heartbeats")?;
+
+        Ok(rows)
+    }
+
+    pub fn heartbeats_for_date(&self, date: NaiveDate) -> Result<Vec<HeartbeatRow>> {
+        let (from_ts, to_ts) = local_day_bounds(date)?;
+        self.heartbeats_between(from_ts, to_ts)
+    }
+
+    pub fn insert_shell_command(&self, command: &ShellCommandInput) -> Result<i64> {
+        self.conn
+            .execute(
+                "INSERT INTO shell_commands (command, program, cwd, project_dir, exit_code, started_at, ended_at, shell)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    &command.command,
+                    &command.program,
+                    &command.cwd,
+                    &command.project_dir,
+                    command.exit_code,
+                    command.started_at,
+                    command.ended_at,
+                    &command.shell
+                ],
+            )
+            .context("Failed to insert shell command")?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Commands started within the Unix seconds `from_ts..=to_ts`, oldest first.
+    pub fn shell_commands_between(&self, from_ts: i64, to_ts: i64) -> Result<Vec<ShellCommandRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, command, program, cwd, project_dir, exit_code, started_at, ended_at
+             FROM shell_commands
+             WHERE started_at >= ?1 AND started_at < ?2 + 1
+             ORDER BY started_at ASC, id ASC",
+        )?;
+
+        let rows = statement
+            .query_map(params![from_ts, to_ts], |row| {
+                Ok(ShellCommandRow {
+                    id: row.get(0)?,
+                    command: row.get(1)?,
+                    program: row.get(2)?,
+                    cwd: row.get(3)?,
+                    project_dir: row.get(4)?,
+                    exit_code: row.get(5)?,
+                    started_at: row.get(6)?,
+                    ended_at: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to query shell commands")?;
+
+        Ok(rows)
+    }
+
+    pub fn shell_commands_for_date(&self, date: NaiveDate) -> Result<Vec<ShellCommandRow>> {
+        let (from_ts, to_ts) = local_day_bounds(date)?;
+        self.shell_commands_between(from_ts, to_ts)
+    }
+
+    /// Stores commits keyed by repository path and SHA; re-syncing a day refreshes the branch.
+    pub fn upsert_git_commits(&mut self, commits: &[GitCommitInput]) -> Result<usize> {
+        let transaction = self
+            .conn
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        for commit in commits {
+            transaction
+                .execute(
+                    "INSERT INTO git_commits (repo, repo_path, sha, branch, author_email, committed_at, summary, lines_added, lines_removed, files_changed)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                     ON CONFLICT(repo_path, sha) DO UPDATE SET branch = excluded.branch",
+                    params![
+                        &commit.repo,
+                        &commit.repo_path,
+                        &commit.sha,
+                        &commit.branch,
+                        &commit.author_email,
+                        commit.committed_at,
+                        &commit.summary,
+                        commit.lines_added,
+                        commit.lines_removed,
+                        commit.files_changed
+                    ],
+                )
+                .context("Failed to upsert git commit")?;
+        }
+
+        transaction
+            .commit()
+            .context("Failed to commit git commits")?;
+
+        Ok(commits.len())
+    }
+
+    pub fn git_commits_for_date(&self, date: NaiveDate) -> Result<Vec<GitCommitRow>> {
+        let (from_ts, to_ts) = local_day_bounds(date)?;
+        let mut statement = self.conn.prepare(
+            "SELECT id, repo, sha, branch, author_email, committed_at, summary, lines_added, lines_removed, files_changed
+             FROM git_commits
+             WHERE committed_at BETWEEN ?1 AND ?2
+             ORDER BY committed_at ASC, id ASC",
+        )?;
+
+        let rows = statement
+            .query_map(params![from_ts, to_ts], |row| {
+                Ok(GitCommitRow {
+                    id: row.get(0)?,
+                    repo: row.get(1)?,
+                    sha: row.get(2)?,
+                    branch: row.get(3)?,
+                    author_email: row.get(4)?,
+                    committed_at: row.get(5)?,
+                    summary: row.get(6)?,
+                    lines_added: row.get(7)?,
+                    lines_removed: row.get(8)?,
+                    files_changed: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to query git commits")?;
+
+        Ok(rows)
+    }
+
+    /// Replaces the events stored for `date`, so events deleted from a calendar disappear on the
+    /// next import. Returns the number of events stored.
+    pub fn replace_calendar_events(
         &mut self,
         date: NaiveDate,
-        visits: &[ChromeVisitInput],
-    ) -> Result<()> {
+        events: &[CalendarEventInput],
+    ) -> Result<usize> {
         let date_str = date.format("%Y-%m-%d").to_string();
         let transaction = self
             .conn
@@ -165,44 +1040,118 @@ impl Database {
 
         transaction
             .execute(
-                "DELETE FROM chrome_visits WHERE date = ?1",
+                "DELETE FROM calendar_events WHERE date = ?1",
                 params![&date_str],
             )
-            .context("Failed to delete existing Chrome visits")?;
+            .context("Failed to clear calendar events")?;
 
-        visits.iter().try_for_each(|visit| {
+        let mut stored = 0;
+        for event in events {
+            // The same event may be exported into several calendars.
+            stored += transaction
+                .execute(
+                    "INSERT OR IGNORE INTO calendar_events (date, calendar, uid, summary, location, starts_at, ends_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        &date_str,
+                        &event.calendar,
+                        &event.uid,
+                        &event.summary,
+                        &event.location,
+                        event.starts_at,
+                        event.ends_at
+                    ],
+                )
+                .context("Failed to insert calendar event")?;
+        }
+
+        transaction
+            .commit()
+            .context("Failed to commit calendar events")?;
+
+        Ok(stored)
+    }
+
+    pub fn calendar_events_for_date(&self, date: NaiveDate) -> Result<Vec<CalendarEventRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, calendar, uid, summary, location, starts_at, ends_at
+             FROM calendar_events
+             WHERE date = ?1
+             ORDER BY starts_at ASC, id ASC",
+        )?;
+
+        let rows = statement
+            .query_map(params![date.format("%Y-%m-%d").to_string()], |row| {
+                Ok(CalendarEventRow {
+                    id: row.get(0)?,
+                    calendar: row.get(1)?,
+                    uid: row.get(2)?,
+                    summary: row.get(3)?,
+                    location: row.get(4)?,
+                    starts_at: row.get(5)?,
+                    ends_at: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to query calendar events")?;
+
+        Ok(rows)
+    }
+
+    /// Applies AI enrichment's per-domain categories to the day's visits that still have the
+    /// domain's rule category (or an earlier AI answer), and marks them as AI-categorized.
+    pub fn update_chrome_visit_categories(
+        &mut self,
+        date: NaiveDate,
+        changes: &[VisitCategoryChange],
+    ) -> Result<()> {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        let transaction = self
+            .conn
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        changes.iter().try_for_each(|change| {
             transaction
                 .execute(
-                    "INSERT INTO chrome_visits (date, domain, category, duration_sec) VALUES (?1, ?2, ?3, ?4)",
-                    params![&date_str, &visit.domain, &visit.category, visit.duration_sec],
+                    "UPDATE chrome_visits SET category = ?1, category_source = 'ai'
+                     WHERE date = ?2 AND browser = ?3 AND domain = ?4
+                       AND (category = ?5 OR category_source = 'ai')",
+                    params![
+                        &change.to,
+                        &date_str,
+                        &change.browser,
+                        &change.domain,
+                        &change.from
+                    ],
                 )
-                .context("Failed to insert Chrome visit")
+                .context("Failed to update Chrome visit category")
                 .map(|_| ())
         })?;
 
         transaction
             .commit()
-            .context("Failed to commit Chrome visits")?;
+            .context("Failed to commit Chrome visit categories")?;
         Ok(())
     }
 
     pub fn chrome_visits_for_date(&self, date: NaiveDate) -> Result<Vec<ChromeVisitRow>> {
-        let date_str = date.format("%Y-%m-%d").to_string();
         let mut statement = self.conn.prepare(
-            "SELECT id, date, domain, category, duration_sec
-             FROM chrome_visits
+            "SELECT date, domain, category, SUM(duration_sec) AS total, browser
+             FROM effective_chrome_visits
              WHERE date = ?1
-             ORDER BY duration_sec DESC",
+             GROUP BY date, browser, domain, category
+             ORDER BY total DESC",
         )?;
 
         let rows = statement
-            .query_map(params![date_str], |row| {
+            .query_map(params![date.format("%Y-%m-%d").to_string()], |row| {
                 Ok(ChromeVisitRow {
-                    id: row.get(0)?,
-                    date: row.get(1)?,
-                    domain: row.get(2)?,
-                    category: row.get(3)?,
-                    duration_sec: row.get(4)?,
+                    date: row.get(0)?,
+                    domain: row.get(1)?,
+                    category: row.get(2)?,
+                    duration_sec: row.get(3)?,
+                    browser: row.get(4)?,
                 })
             })?
             .collect::<Result<Vec<_>, _>>()
@@ -211,6 +1160,113 @@ impl Database {
         Ok(rows)
     }
 
+    pub fn web_visits_between(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<WebVisitRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, date, visited_at, browser, domain, url, transition, category, duration_sec
+             FROM effective_chrome_visits
+             WHERE date >= ?1 AND date <= ?2
+             ORDER BY date ASC, visited_at ASC, id ASC",
+        )?;
+
+        let rows = statement
+            .query_map(
+                params![
+                    from.format("%Y-%m-%d").to_string(),
+                    to.format("%Y-%m-%d").to_string()
+                ],
+                |row| {
+                    Ok(WebVisitRow {
+                        id: row.get(0)?,
+                        date: row.get(1)?,
+                        visited_at: row.get(2)?,
+                        browser: row.get(3)?,
+                        domain: row.get(4)?,
+                        url: row.get(5)?,
+                        transition: row.get(6)?,
+                        category: row.get(7)?,
+                        duration_sec: row.get(8)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to query web visits")?;
+
+        Ok(rows)
+    }
+
+    /// Every stored visit of the dates whose category came from the rules, including day-level
+    /// aggregates and history visits that `effective_chrome_visits` hides.
+    pub fn rule_categorized_visits_between(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<WebVisitRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, date, visited_at, browser, domain, url, transition, category, duration_sec
+             FROM chrome_visits
+             WHERE date >= ?1 AND date <= ?2 AND category_source = 'rules'
+             ORDER BY date ASC, visited_at ASC, id ASC",
+        )?;
+
+        let rows = statement
+            .query_map(
+                params![
+                    from.format("%Y-%m-%d").to_string(),
+                    to.format("%Y-%m-%d").to_string()
+                ],
+                |row| {
+                    Ok(WebVisitRow {
+                        id: row.get(0)?,
+                        date: row.get(1)?,
+                        visited_at: row.get(2)?,
+                        browser: row.get(3)?,
+                        domain: row.get(4)?,
+                        url: row.get(5)?,
+                        transition: row.get(6)?,
+                        category: row.get(7)?,
+                        duration_sec: row.get(8)?,
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()
+            .context("Failed to query stored visits")?;
+
+        Ok(rows)
+    }
+
+    /// Sets new categories by row id for activities, sessions and visits, in one transaction.
+    pub fn update_categories(
+        &mut self,
+        activities: &[(i64, String)],
+        sessions: &[(i64, String)],
+        visits: &[(i64, String)],
+    ) -> Result<()> {
+        let transaction = self
+            .conn
+            .transaction()
+            .context("Failed to start transaction")?;
+
+        for (table, rows) in [
+            ("activities", activities),
+            ("sessions", sessions),
+            ("chrome_visits", visits),
+        ] {
+            let mut statement =
+                transaction.prepare(&format!("UPDATE {table} SET category = ?1 WHERE id = ?2"))?;
+            rows.iter().try_for_each(|(id, category)| {
+                statement
+                    .execute(params![category, id])
+                    .with_context(|| format!("Failed to update {table} category"))
+                    .map(|_| ())
+            })?;
+        }
+
+        transaction
+            .commit()
+            .context("Failed to commit category updates")?;
+        Ok(())
+    }
+
     pub fn report_meta(&self, date: NaiveDate) -> Result<Option<ReportMetaRow>> {
         let date_str = date.format("%Y-%m-%d").to_string();
         let row = self
@@ -299,6 +1355,7 @@ impl Database {
         Ok(())
     }
 
+    /// Deletes everything recorded before the retention window; returns how many activities went.
     pub fn cleanup_old_activities(&self, retention_days: u32) -> Result<usize> {
         let threshold = (Local::now() - Duration::days(i64::from(retention_days))).timestamp();
 
@@ -310,6 +1367,104 @@ impl Database {
             )
             .context("Failed to clean up old activities")?;
 
+        self.conn
+            .execute(
+                "DELETE FROM sessions WHERE ended_at < ?1",
+                params![threshold],
+            )
+            .context("Failed to clean up old sessions")?;
+
+        self.conn
+            .execute("DELETE FROM heartbeats WHERE time < ?1", params![threshold])
+            .context("Failed to clean up old heartbeats")?;
+
+        self.conn
+            .execute(
+                "DELETE FROM shell_commands WHERE started_at < ?1",
+                params![threshold],
+            )
+            .context("Failed to clean up old shell commands")?;
+
+        self.conn
+            .execute(
+                "DELETE FROM browser_events WHERE occurred_at_ms < ?1",
+                params![threshold * 1000],
+            )
+            .context("Failed to clean up old browser events")?;
+
+        self.conn
+            .execute(
+                "DELETE FROM git_commits WHERE committed_at < ?1",
+                params![threshold],
+            )
+            .context("Failed to clean up old git commits")?;
+
+        self.conn
+            .execute(
+                "DELETE FROM calendar_events WHERE ends_at < ?1",
+                params![threshold],
+            )
+            .context("Failed to clean up old calendar events")?;
+
+        // Day-level aggregates have no `visited_at`, so visits are pruned by date.
+        let threshold_date = Local
+            .timestamp_opt(threshold, 0)
+            .single()
+            .map(|time| time.date_naive().format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        self.conn
+            .execute(
+                "DELETE FROM chrome_visits WHERE date < ?1",
+                params![threshold_date],
+            )
+            .context("Failed to clean up old browser visits")?;
+
         Ok(deleted)
     }
 }
+
+/// First and last Unix second of a local calendar day.
+fn local_day_bounds(date: NaiveDate) -> Result<(i64, i64)> {
+    let from = date
+        .and_hms_opt(0, 0, 0)
+        .context("Failed to build from timestamp")?;
+    let to = (date + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .context("Failed to build to timestamp")?;
+
+    let from_ts = Local
+        .from_local_datetime(&from)
+        .single()
+        .context("Failed to convert from timestamp to local time")?
+        .timestamp();
+    let to_ts = Local
+        .from_local_datetime(&to)
+        .single()
+        .context("Failed to convert to timestamp to local time")?
+        .timestamp()
+        - 1;
+
+    Ok((from_ts, to_ts))
+}
+
+fn insert_activity_row(conn: &Connection, activity: &ActivityInput) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO activities (recorded_at, app_name, window_title, category, duration_sec, state, source, external_id, metadata, project)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            activity.recorded_at,
+            &activity.app_name,
+            &activity.window_title,
+            &activity.category,
+            activity.duration_sec,
+            &activity.state,
+            &activity.source,
+            &activity.external_id,
+            &activity.metadata,
+            &activity.project
+        ],
+    )
+    .context("Failed to insert activity")?;
+
+    Ok(conn.last_insert_rowid())
+}
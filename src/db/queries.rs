@@ -5,7 +5,12 @@ CREATE TABLE IF NOT EXISTS activities (
   app_name     TEXT NOT NULL,
   window_title TEXT,
   category     TEXT NOT NULL DEFAULT 'other',
-  duration_sec INTEGER NOT NULL DEFAULT 0
+  duration_sec INTEGER NOT NULL DEFAULT 0,
+  state        TEXT NOT NULL DEFAULT 'active',
+  source       TEXT NOT NULL DEFAULT 'window',
+  external_id  TEXT,
+  metadata     TEXT,
+  project      TEXT
 );
 "#;
 
@@ -15,10 +20,129 @@ CREATE TABLE IF NOT EXISTS chrome_visits (
   date         TEXT NOT NULL,
   domain       TEXT NOT NULL,
   category     TEXT NOT NULL DEFAULT 'other',
-  duration_sec INTEGER NOT NULL DEFAULT 0
+  duration_sec INTEGER NOT NULL DEFAULT 0,
+  browser      TEXT NOT NULL DEFAULT 'chrome',
+  profile      TEXT,
+  source_visit_id INTEGER,
+  visited_at   INTEGER,
+  url          TEXT,
+  transition   TEXT,
+  category_source TEXT NOT NULL DEFAULT 'rules'
 );
 "#;
 
+pub const CREATE_SESSIONS: &str = r#"
+CREATE TABLE IF NOT EXISTS sessions (
+  id           INTEGER PRIMARY KEY AUTOINCREMENT,
+  app_name     TEXT NOT NULL,
+  window_title TEXT,
+  category     TEXT NOT NULL DEFAULT 'other',
+  state        TEXT NOT NULL DEFAULT 'active',
+  started_at   INTEGER NOT NULL,
+  ended_at     INTEGER NOT NULL,
+  sample_count INTEGER NOT NULL DEFAULT 1
+);
+"#;
+
+pub const CREATE_BROWSER_SYNC_STATE: &str = r#"
+CREATE TABLE IF NOT EXISTS browser_sync_state (
+  profile       TEXT PRIMARY KEY,
+  last_visit_id INTEGER NOT NULL DEFAULT 0,
+  updated_at    INTEGER NOT NULL
+);
+"#;
+
+pub const CREATE_BROWSER_EVENTS: &str = r#"
+CREATE TABLE IF NOT EXISTS browser_events (
+  id             INTEGER PRIMARY KEY AUTOINCREMENT,
+  browser        TEXT NOT NULL,
+  event_key      TEXT NOT NULL,
+  event_type     TEXT NOT NULL,
+  occurred_at_ms INTEGER NOT NULL,
+  tab_id         INTEGER,
+  url            TEXT,
+  UNIQUE(browser, event_key)
+);
+"#;
+
+pub const CREATE_HEARTBEATS: &str = r#"
+CREATE TABLE IF NOT EXISTS heartbeats (
+  id          INTEGER PRIMARY KEY AUTOINCREMENT,
+  time        REAL NOT NULL,
+  entity      TEXT NOT NULL,
+  entity_type TEXT NOT NULL DEFAULT 'file',
+  category    TEXT,
+  project     TEXT,
+  branch      TEXT,
+  language    TEXT,
+  is_write    INTEGER NOT NULL DEFAULT 0,
+  lines       INTEGER,
+  lineno      INTEGER,
+  user_agent  TEXT,
+  UNIQUE(entity, time)
+);
+"#;
+
+pub const CREATE_SHELL_COMMANDS: &str = r#"
+CREATE TABLE IF NOT EXISTS shell_commands (
+  id          INTEGER PRIMARY KEY AUTOINCREMENT,
+  command     TEXT NOT NULL,
+  program     TEXT NOT NULL,
+  cwd         TEXT NOT NULL,
+  project_dir TEXT NOT NULL,
+  exit_code   INTEGER NOT NULL,
+  started_at  REAL NOT NULL,
+  ended_at    REAL NOT NULL,
+  shell       TEXT
+);
+"#;
+
+pub const CREATE_GIT_COMMITS: &str = r#"
+CREATE TABLE IF NOT EXISTS git_commits (
+  id            INTEGER PRIMARY KEY AUTOINCREMENT,
+  repo          TEXT NOT NULL,
+  repo_path     TEXT NOT NULL,
+  sha           TEXT NOT NULL,
+  branch        TEXT NOT NULL,
+  author_email  TEXT NOT NULL,
+  committed_at  INTEGER NOT NULL,
+  summary       TEXT NOT NULL,
+  lines_added   INTEGER NOT NULL DEFAULT 0,
+  lines_removed INTEGER NOT NULL DEFAULT 0,
+  files_changed INTEGER NOT NULL DEFAULT 0,
+  UNIQUE(repo_path, sha)
+);
+"#;
+
+/// Meetings expanded from `.ics` files for one report `date`; re-importing a day replaces its rows.
+pub const CREATE_CALENDAR_EVENTS: &str = r#"
+CREATE TABLE IF NOT EXISTS calendar_events (
+  id        INTEGER PRIMARY KEY AUTOINCREMENT,
+  date      TEXT NOT NULL,
+  calendar  TEXT NOT NULL,
+  uid       TEXT NOT NULL,
+  summary   TEXT NOT NULL,
+  location  TEXT,
+  starts_at INTEGER NOT NULL,
+  ends_at   INTEGER NOT NULL,
+  UNIQUE(date, uid, starts_at)
+);
+"#;
+
+/// Visits as reports should see them: for a day and browser with extension visits, history
+/// visits of that browser are hidden because the extension knows which tab was really focused.
+pub const CREATE_EFFECTIVE_CHROME_VISITS: &str = r#"
+CREATE VIEW IF NOT EXISTS effective_chrome_visits AS
+SELECT v.id, v.date, v.domain, v.category, v.duration_sec, v.browser, v.profile,
+       v.source_visit_id, v.visited_at, v.url, v.transition
+FROM chrome_visits AS v
+WHERE v.profile LIKE 'extension:%'
+   OR NOT EXISTS (
+     SELECT 1 FROM chrome_visits AS e
+     WHERE e.profile LIKE 'extension:%' AND e.date = v.date AND e.browser = v.browser
+   );
+"#;
+
 pub const CREATE_REPORTS: &str = r#"
 CREATE TABLE IF NOT EXISTS reports (
   id           INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -32,19 +156,136 @@ CREATE TABLE IF NOT EXISTS reports (
 pub const INDEX_ACTIVITIES_RECORDED_AT: &str =
     "CREATE INDEX IF NOT EXISTS idx_activities_recorded_at ON activities(recorded_at);";
 
+pub const INDEX_ACTIVITIES_EXTERNAL_ID: &str = "CREATE UNIQUE INDEX IF NOT EXISTS idx_activities_external_id ON activities(source, external_id);";
+
 pub const INDEX_CHROME_VISITS_DATE: &str =
     "CREATE INDEX IF NOT EXISTS idx_chrome_visits_date ON chrome_visits(date);";
 
+pub const INDEX_CHROME_VISITS_SOURCE: &str = "CREATE UNIQUE INDEX IF NOT EXISTS idx_chrome_visits_source ON chrome_visits(profile, source_visit_id);";
+
+pub const INDEX_BROWSER_EVENTS_TIME: &str = "CREATE INDEX IF NOT EXISTS idx_browser_events_time ON browser_events(browser, occurred_at_ms);";
+
+pub const INDEX_HEARTBEATS_TIME: &str =
+    "CREATE INDEX IF NOT EXISTS idx_heartbeats_time ON heartbeats(time);";
+
+pub const INDEX_SHELL_COMMANDS_STARTED_AT: &str =
+    "CREATE INDEX IF NOT EXISTS idx_shell_commands_started_at ON shell_commands(started_at);";
+
+pub const INDEX_GIT_COMMITS_COMMITTED_AT: &str =
+    "CREATE INDEX IF NOT EXISTS idx_git_commits_committed_at ON git_commits(committed_at);";
+
+pub const INDEX_CALENDAR_EVENTS_DATE: &str =
+    "CREATE INDEX IF NOT EXISTS idx_calendar_events_date ON calendar_events(date);";
+
+pub const INDEX_SESSIONS_RANGE: &str =
+    "CREATE INDEX IF NOT EXISTS idx_sessions_range ON sessions(started_at, ended_at);";
+
 pub const INDEX_REPORTS_DATE: &str =
     "CREATE INDEX IF NOT EXISTS idx_reports_date ON reports(date);";
 
+/// Columns added after the initial release; applied with `ALTER TABLE` when an older DB lacks them.
+pub struct ColumnMigration {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub definition: &'static str,
+}
+
+pub fn column_migrations() -> Vec<ColumnMigration> {
+    vec![
+        ColumnMigration {
+            table: "activities",
+            column: "state",
+            definition: "TEXT NOT NULL DEFAULT 'active'",
+        },
+        ColumnMigration {
+            table: "activities",
+            column: "source",
+            definition: "TEXT NOT NULL DEFAULT 'window'",
+        },
+        ColumnMigration {
+            table: "activities",
+            column: "external_id",
+            definition: "TEXT",
+        },
+        ColumnMigration {
+            table: "activities",
+            column: "metadata",
+            definition: "TEXT",
+        },
+        ColumnMigration {
+            table: "activities",
+            column: "project",
+            definition: "TEXT",
+        },
+        ColumnMigration {
+            table: "chrome_visits",
+            column: "browser",
+            definition: "TEXT NOT NULL DEFAULT 'chrome'",
+        },
+        ColumnMigration {
+            table: "chrome_visits",
+            column: "profile",
+            definition: "TEXT",
+        },
+        ColumnMigration {
+            table: "chrome_visits",
+            column: "source_visit_id",
+            definition: "INTEGER",
+        },
+        // Rows from before per-visit storage keep `visited_at` NULL and stay day-level aggregates.
+        ColumnMigration {
+            table: "chrome_visits",
+            column: "visited_at",
+            definition: "INTEGER",
+        },
+        ColumnMigration {
+            table: "chrome_visits",
+            column: "url",
+            definition: "TEXT",
+        },
+        ColumnMigration {
+            table: "chrome_visits",
+            column: "transition",
+            definition: "TEXT",
+        },
+        // `ai` once AI enrichment changed the category; recategorizing leaves those visits alone.
+        ColumnMigration {
+            table: "chrome_visits",
+            column: "category_source",
+            definition: "TEXT NOT NULL DEFAULT 'rules'",
+        },
+    ]
+}
+
 pub fn schema_statements() -> Vec<&'static str> {
     vec![
         CREATE_ACTIVITIES,
         CREATE_CHROME_VISITS,
+        CREATE_SESSIONS,
+        CREATE_BROWSER_SYNC_STATE,
+        CREATE_BROWSER_EVENTS,
+        CREATE_HEARTBEATS,
+        CREATE_SHELL_COMMANDS,
+        CREATE_GIT_COMMITS,
+        CREATE_CALENDAR_EVENTS,
         CREATE_REPORTS,
+    ]
+}
+
+/// Indexes and views run after the column migrations so they may reference migrated columns.
+pub fn index_statements() -> Vec<&'static str> {
+    vec![
         INDEX_ACTIVITIES_RECORDED_AT,
+        INDEX_ACTIVITIES_EXTERNAL_ID,
         INDEX_CHROME_VISITS_DATE,
+        INDEX_CHROME_VISITS_SOURCE,
+        INDEX_BROWSER_EVENTS_TIME,
+        INDEX_HEARTBEATS_TIME,
+        INDEX_SHELL_COMMANDS_STARTED_AT,
+        INDEX_GIT_COMMITS_COMMITTED_AT,
+        INDEX_CALENDAR_EVENTS_DATE,
+        INDEX_SESSIONS_RANGE,
         INDEX_REPORTS_DATE,
+        CREATE_EFFECTIVE_CHROME_VISITS,
     ]
 }
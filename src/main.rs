@@ -8,15 +8,18 @@ mod daemon;
 mod db;
 mod scheduler;
 
-use crate::analyzer::categorizer::CategoryRules;
+use crate::analyzer::categorizer::{CategoryRules, Target};
+use crate::analyzer::{recategorize, report};
 use crate::cli::onboard::run_onboarding;
-use crate::cli::{AiCommands, Cli, Commands, ConfigCommands};
-use crate::collector::chrome;
-use crate::config::{Config, FIXED_POLLING_SECONDS};
-use crate::db::Database;
+use crate::cli::{AiCommands, CategoriesCommands, Cli, Commands, ConfigCommands, ImportCommands};
+use crate::collector::timesheet::CsvMapping;
+use crate::collector::{activitywatch, calendar, chrome, firefox, git, timesheet};
+use crate::config::Config;
+use crate::db::{ChromeVisitInput, Database};
 use anyhow::{Context, Result, bail};
 use chrono::{Local, NaiveDate};
 use clap::Parser;
+use std::collections::BTreeMap;
 use std::fs;
 use std::net::{Ipv4Addr, SocketAddr, TcpStream};
 use std::process::{Command, Stdio};
@@ -26,6 +29,7 @@ use std::time::Duration;
 use tokio::signal;
 use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
+#[cfg(target_os = "macos")]
 use url::Url;
 
 #[tokio::main]
@@ -52,10 +56,23 @@ async fn main() -> Result<()> {
         Commands::Dashboard => handle_dashboard(),
         Commands::Report { date } => handle_report(date),
         Commands::Ai { command } => handle_ai_command(command),
+        Commands::Import { command } => handle_import_command(command),
+        Commands::Categories { command } => handle_categories_command(command),
+        Commands::Recategorize {
+            from,
+            to,
+            dry_run,
+            regenerate_reports,
+        } => handle_recategorize(from, to, dry_run, regenerate_reports),
         Commands::Service => {
             let config = load_config()?;
             run_service(config).await
         }
+        Commands::ShellInit { shell } => {
+            let config = load_or_default_config()?;
+            print!("{}", collector::shell::init_script(shell, config.api_port));
+            Ok(())
+        }
         Commands::Update => handle_update(),
         Commands::Uninstall => handle_uninstall(),
     }
@@ -162,6 +179,29 @@ fn handle_doctor() -> Result<()> {
         println!("[OK] AI feature disabled");
     }
 
+    let window_backend = collector::window::active_backend();
+    if window_backend == collector::window::WindowBackend::Unsupported {
+        println!("[WARN] no window backend available on this platform/session");
+        issues.push("window backend unsupported".to_string());
+    } else {
+        println!("[OK] window backend: {}", window_backend.name());
+    }
+
+    let mut idle_detector = collector::idle::from_config(&config);
+    match idle_detector.idle_seconds() {
+        Ok(Some(_)) => println!("[OK] idle detector: {}", idle_detector.name()),
+        Ok(None) if config.idle_threshold_seconds == 0 => {
+            println!("[OK] idle detection disabled (idle_threshold_seconds = 0)")
+        }
+        Ok(None) | Err(_) => {
+            println!(
+                "[WARN] idle detector `{}` unavailable; away time will count as active",
+                idle_detector.name()
+            );
+            issues.push("idle detection unavailable".to_string());
+        }
+    }
+
     let window_access = collector::window::accessibility_window_access_available();
     if window_access {
         println!("[OK] window title collection available (Accessibility likely granted)");
@@ -173,18 +213,37 @@ fn handle_doctor() -> Result<()> {
     let missing_profiles = config
         .chrome_profiles
         .iter()
-        .filter(|profile| !chrome::profile_history_path(profile).exists())
+        .filter(|profile| !chrome::profile_history_path(profile).is_some_and(|path| path.exists()))
         .cloned()
         .collect::<Vec<_>>();
 
     if missing_profiles.is_empty() {
-        println!("[OK] Chrome profile paths verified");
+        println!("[OK] Chromium-family browser profile paths verified");
     } else {
         println!(
-            "[WARN] Missing Chrome profile paths: {}",
+            "[WARN] Missing browser profile paths: {}",
             missing_profiles.join(", ")
         );
-        issues.push("chrome profile missing".to_string());
+        issues.push("browser profile missing".to_string());
+    }
+
+    let missing_firefox_profiles = config
+        .firefox_profiles
+        .iter()
+        .filter(|profile| !firefox::profile_places_path(profile).is_some_and(|path| path.exists()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if config.firefox_profiles.is_empty() {
+        println!("[OK] Firefox history import disabled (firefox_profiles is empty)");
+    } else if missing_firefox_profiles.is_empty() {
+        println!("[OK] Firefox profile paths verified");
+    } else {
+        println!(
+            "[WARN] Missing Firefox profiles (places.sqlite not found): {}",
+            missing_firefox_profiles.join(", ")
+        );
+        issues.push("firefox profile missing".to_string());
     }
 
     if issues.is_empty() {
@@ -276,16 +335,282 @@ fn handle_ai_command(command: AiCommands) -> Result<()> {
     }
 }
 
+fn handle_import_command(command: ImportCommands) -> Result<()> {
+    match command {
+        ImportCommands::ActivityWatch {
+            file,
+            regenerate_reports,
+        } => {
+            let config = load_config()?;
+            let rules = load_category_rules(&config)?;
+            let content = fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read export: {}", file.display()))?;
+
+            let mut database = Database::open(&config.db_path)?;
+            let summary = activitywatch::import_export(
+                &mut database,
+                &rules,
+                &content,
+                config.retention_days,
+            )?;
+
+            println!("ActivityWatch import finished");
+            println!("- activities_created: {}", summary.activities.created);
+            println!(
+                "- activities_skipped_duplicates: {}",
+                summary.activities.duplicates
+            );
+            println!(
+                "- activities_skipped_already_tracked: {}",
+                summary.activities.already_tracked
+            );
+            println!("- web_visits: {}", summary.visits);
+            if summary.older_than_retention > 0 {
+                println!(
+                    "- skipped_older_than_retention: {} (retention_days = {})",
+                    summary.older_than_retention, config.retention_days
+                );
+            }
+            if !summary.skipped_buckets.is_empty() {
+                println!(
+                    "- unsupported_buckets: {}",
+                    summary.skipped_buckets.join(", ")
+                );
+            }
+
+            if regenerate_reports && let Some((from, to)) = summary.dates {
+                regenerate_reports_between(&config, from, to)?;
+            }
+            Ok(())
+        }
+        ImportCommands::Csv {
+            file,
+            format,
+            mapping,
+            dry_run,
+            regenerate_reports,
+        } => {
+            let mapping = mapping
+                .map(|path| {
+                    let content = fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read mapping: {}", path.display()))?;
+                    serde_json::from_str::<CsvMapping>(&content)
+                        .with_context(|| format!("Failed to parse mapping: {}", path.display()))
+                })
+                .transpose()?;
+            if format == timesheet::CsvFormat::Generic && mapping.is_none() {
+                bail!("--format generic requires --mapping");
+            }
+
+            let config = load_config()?;
+            let rules = load_category_rules(&config)?;
+            let content = fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read CSV: {}", file.display()))?;
+            let parsed = timesheet::parse_csv(&content, format, mapping, &rules)?;
+
+            let mut database = Database::open(&config.db_path)?;
+            let days = timesheet::import_activities(&mut database, &parsed.activities, dry_run)?;
+
+            if dry_run {
+                println!("CSV import dry run: nothing was stored");
+            } else {
+                println!("CSV import finished");
+            }
+            println!(
+                "| Date | Rows | New | Duplicates | Already tracked | New time | Categories |"
+            );
+            println!(
+                "|------|------|-----|------------|-----------------|----------|------------|"
+            );
+            for (date, day) in &days {
+                let categories = day
+                    .categories_seconds
+                    .iter()
+                    .map(|(category, seconds)| {
+                        format!(
+                            "{category} {}",
+                            report::format_duration_seconds((*seconds).max(0) as u64)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!(
+                    "| {} | {} | {} | {} | {} | {} | {} |",
+                    date.format("%Y-%m-%d"),
+                    day.rows,
+                    day.created,
+                    day.duplicates,
+                    day.already_tracked,
+                    report::format_duration_seconds(
+                        day.categories_seconds.values().sum::<i64>().max(0) as u64
+                    ),
+                    if categories.is_empty() {
+                        "-".to_string()
+                    } else {
+                        categories
+                    }
+                );
+            }
+            if !parsed.errors.is_empty() {
+                println!("- skipped_rows: {}", parsed.errors.len());
+                for error in parsed.errors.iter().take(10) {
+                    println!("  - {error}");
+                }
+            }
+
+            if regenerate_reports
+                && let (Some(from), Some(to)) = (days.keys().next(), days.keys().last())
+            {
+                regenerate_reports_between(&config, *from, *to)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn handle_categories_command(command: CategoriesCommands) -> Result<()> {
+    let config = load_config()?;
+    let rules = load_category_rules(&config)?;
+
+    match command {
+        CategoriesCommands::Test {
+            app,
+            title,
+            domain,
+            url,
+        } => {
+            let domain = domain.or_else(|| url.as_deref().and_then(chrome::extract_domain));
+            let explanation = rules.explain(&Target {
+                app: app.as_deref(),
+                title: title.as_deref(),
+                domain: domain.as_deref(),
+                url: url.as_deref(),
+            });
+
+            println!("Category: {}", explanation.category);
+            let Some((matched, alternatives)) = explanation.matches.split_first() else {
+                println!("- No rule matched; `other` is used");
+                return Ok(());
+            };
+            println!(
+                "- Matched: {} ({}) -> {}",
+                matched.rule, matched.conditions, matched.category
+            );
+            if !alternatives.is_empty() {
+                println!("- Also matched (not used):");
+                for alternative in alternatives {
+                    println!(
+                        "  - {} ({}) -> {}",
+                        alternative.rule, alternative.conditions, alternative.category
+                    );
+                }
+            }
+            Ok(())
+        }
+        CategoriesCommands::Lint => {
+            let issues = rules.lint();
+            if issues.is_empty() {
+                println!("- No issues");
+                return Ok(());
+            }
+            for issue in &issues {
+                println!(
+                    "- {} {}: {}",
+                    issue.kind.as_str(),
+                    issue.rule,
+                    issue.message
+                );
+            }
+            bail!(
+                "{} issue(s) found in {}",
+                issues.len(),
+                config.categories_path.display()
+            );
+        }
+    }
+}
+
+fn handle_recategorize(
+    from: String,
+    to: Option<String>,
+    dry_run: bool,
+    regenerate_reports: bool,
+) -> Result<()> {
+    let from = parse_optional_date(Some(from))?;
+    let to = parse_optional_date(to)?;
+    let config = load_config()?;
+    let rules = load_category_rules(&config)?;
+
+    let mut database = Database::open(&config.db_path)?;
+    let summary = recategorize::recategorize(&mut database, &rules, from, to, dry_run)?;
+
+    if dry_run {
+        println!("Recategorize dry run: nothing was stored");
+    } else {
+        println!("Recategorize finished");
+    }
+    println!("- activities_changed: {}", summary.activities_changed);
+    println!("- sessions_changed: {}", summary.sessions_changed);
+    println!("- visits_changed: {}", summary.visits_changed);
+    let moves = summary
+        .activity_moves
+        .iter()
+        .map(|category_move| ("activities", category_move))
+        .chain(
+            summary
+                .visit_moves
+                .iter()
+                .map(|category_move| ("browser visits", category_move)),
+        )
+        .collect::<Vec<_>>();
+    if !moves.is_empty() {
+        println!("| Data | From | To | Time |");
+        println!("|------|------|----|------|");
+        for (label, category_move) in moves {
+            println!(
+                "| {label} | {} | {} | {} |",
+                category_move.from,
+                category_move.to,
+                report::format_duration_seconds(category_move.seconds.max(0) as u64)
+            );
+        }
+    }
+
+    if regenerate_reports {
+        for date in &summary.dates {
+            analyzer::regenerate_report(&config, &rules, *date)?;
+        }
+        println!("- reports_regenerated: {}", summary.dates.len());
+    }
+    Ok(())
+}
+
+/// Rebuilds stored reports from the database only: no browser sync or AI enrichment, which only
+/// make sense for the current day.
+fn regenerate_reports_between(config: &Config, from: NaiveDate, to: NaiveDate) -> Result<()> {
+    let rules = load_category_rules(config)?;
+    for date in from.iter_days().take_while(|date| *date <= to) {
+        analyzer::regenerate_report(config, &rules, date)?;
+    }
+    println!(
+        "- reports_regenerated: {} ({} to {})",
+        (to - from).num_days() + 1,
+        from.format("%Y-%m-%d"),
+        to.format("%Y-%m-%d")
+    );
+    Ok(())
+}
+
 fn handle_uninstall() -> Result<()> {
     let config = load_or_default_config()?;
 
     let _ = daemon::unload(&config);
 
-    if let Ok(plist_path) = daemon::plist_path(&config) {
-        if plist_path.exists() {
-            let _ = fs::remove_file(&plist_path);
-            println!("Removed daemon plist: {}", plist_path.display());
-        }
+    if let Ok(plist_path) = daemon::plist_path(&config)
+        && plist_path.exists()
+    {
+        let _ = fs::remove_file(&plist_path);
+        println!("Removed daemon plist: {}", plist_path.display());
     }
 
     let current_exe = std::env::current_exe().context("Failed to resolve current executable")?;
@@ -338,6 +663,10 @@ async fn run_service(config: Config) -> Result<()> {
     let shared_config = Arc::new(config);
     let collector_config = Arc::clone(&shared_config);
     let collector_rules = Arc::new(load_category_rules(&shared_config)?);
+    let collector_source = collector::source::from_config(&shared_config)?;
+    let collector_idle = collector::idle::from_config(&shared_config);
+    let browser_sync_config = Arc::clone(&shared_config);
+    let browser_sync_rules = Arc::clone(&collector_rules);
 
     let scheduler_config = Arc::clone(&shared_config);
     let scheduler_schedule_fallback = Arc::clone(&shared_config);
@@ -347,9 +676,12 @@ async fn run_service(config: Config) -> Result<()> {
     info!("OpenTracker service started");
 
     tokio::select! {
-        collector_result = collector::run_activity_collector(collector_config, collector_rules) => {
+        collector_result = collector::run_activity_collector(collector_config, collector_rules, collector_source, collector_idle) => {
             collector_result?;
         }
+        browser_sync_result = collector::run_browser_sync(browser_sync_config, browser_sync_rules) => {
+            browser_sync_result?;
+        }
         scheduler_result = scheduler::run_cron_scheduler(move || {
             let report_time = Config::load()
                 .map(|runtime| runtime.report_time)
@@ -378,18 +710,26 @@ async fn run_service(config: Config) -> Result<()> {
 
 fn run_daily_pipeline(config: &Config, date: NaiveDate) -> Result<()> {
     let rules = load_category_rules(config)?;
-    let visits = chrome::sync_chrome_visits_for_date(config, &rules, date)?;
-    let enrichment = ai::enrich_chrome_visits(config, date, &visits).unwrap_or_else(|error| {
-        warn!(error = %error, "AI enrichment failed. fallback to rule-based categorization");
-        ai::AiEnrichment {
-            visits: visits.clone(),
-            insights: Vec::new(),
-        }
-    });
     let mut database = Database::open(&config.db_path)?;
-    database.replace_chrome_visits_for_date(date, &enrichment.visits)?;
+    chrome::sync_chrome_visits(config, &rules, &mut database)?;
+    firefox::sync_firefox_visits(config, &rules, &mut database)?;
+    if date < Local::now().date_naive() {
+        chrome::backfill_chrome_visits(config, &rules, &mut database, date)?;
+        firefox::backfill_firefox_visits(config, &rules, &mut database, date)?;
+    }
+    git::sync_git_commits(config, &mut database, date)?;
+    calendar::sync_calendar_events(config, &mut database, date)?;
 
-    let (report, saved) = analyzer::generate_and_store_report(config, date, enrichment.insights)?;
+    let visits = daily_domain_visits(&database, &rules, date)?;
+    let enrichment =
+        ai::enrich_chrome_visits(config, &rules, date, &visits).unwrap_or_else(|error| {
+            warn!(error = %error, "AI enrichment failed. fallback to rule-based categorization");
+            ai::AiEnrichment::default()
+        });
+    database.update_chrome_visit_categories(date, &enrichment.changes)?;
+
+    let (report, saved) =
+        analyzer::generate_and_store_report(config, &rules, date, enrichment.insights)?;
 
     if config.notify_on_report {
         send_macos_notification(&report.date, &saved.markdown_path);
@@ -402,6 +742,32 @@ fn run_daily_pipeline(config: &Config, date: NaiveDate) -> Result<()> {
     Ok(())
 }
 
+/// Per-browser, per-domain totals of the day's stored visits with the category the domain rules
+/// give the domain, as sent to AI enrichment.
+fn daily_domain_visits(
+    database: &Database,
+    rules: &CategoryRules,
+    date: NaiveDate,
+) -> Result<Vec<ChromeVisitInput>> {
+    let totals = database.chrome_visits_for_date(date)?.into_iter().fold(
+        BTreeMap::new(),
+        |mut acc: BTreeMap<(String, String), i64>, row| {
+            *acc.entry((row.browser, row.domain)).or_default() += row.duration_sec;
+            acc
+        },
+    );
+
+    Ok(totals
+        .into_iter()
+        .map(|((browser, domain), duration_sec)| ChromeVisitInput {
+            category: rules.categorize_domain(&domain, None),
+            browser,
+            domain,
+            duration_sec,
+        })
+        .collect())
+}
+
 fn parse_optional_date(input: Option<String>) -> Result<NaiveDate> {
     input
         .as_deref()
@@ -432,14 +798,8 @@ fn load_or_default_config() -> Result<Config> {
 }
 
 fn load_config() -> Result<Config> {
-    let mut config = Config::load()
-        .with_context(|| "Config file not found. Run `OpenTracker onboard` first.".to_string())?;
-
-    if config.polling_seconds != FIXED_POLLING_SECONDS {
-        config.polling_seconds = FIXED_POLLING_SECONDS;
-    }
-
-    Ok(config)
+    Config::load()
+        .with_context(|| "Config file not found. Run `OpenTracker onboard` first.".to_string())
 }
 
 #[cfg(target_os = "macos")]